@@ -51,11 +51,93 @@ pub enum Commands {
         #[arg(short, long)]
         strict: bool,
         
+        /// Output format (text, json, sarif, junit)
+        #[arg(short, long, default_value = "text")]
+        output: String,
+        
+        /// Apply safe automatic fixes to the file before validating
+        #[arg(long)]
+        fix: bool,
+        
+        /// With --fix, show the fixes as a diff without writing the file
+        #[arg(long, requires = "fix")]
+        dry_run: bool,
+    },
+    
+    /// Report lint findings that validation doesn't treat as errors
+    Lint {
+        /// Path to the FDML file to lint
+        file: String,
+        
+        /// Report orphaned entities, actions, flows and constraints, and empty features and scenarios
+        #[arg(long)]
+        unused: bool,
+        
+        /// Check scenarios against the writing rules: business language, one when step, a then step,
+        /// no duplicates, and when steps that match actions (word lists configurable in fdml.yaml)
+        #[arg(long)]
+        scenarios: bool,
+        
+        /// Fail when there are findings
+        #[arg(short, long)]
+        strict: bool,
+        
         /// Output format (text, json)
         #[arg(short, long, default_value = "text")]
         output: String,
     },
     
+    /// Format FDML files into the canonical layout
+    Fmt {
+        /// FDML files or directories to format
+        #[arg(default_value = ".")]
+        paths: Vec<String>,
+        
+        /// Report unformatted files instead of rewriting them
+        #[arg(long)]
+        check: bool,
+    },
+    
+    /// Watch specs and migrations, re-validating (and optionally regenerating) on change
+    Watch {
+        /// Project directory to watch
+        #[arg(default_value = ".")]
+        path: String,
+        
+        /// Regenerate code for changed specs
+        #[arg(long)]
+        generate: bool,
+        
+        /// Languages to generate (defaults to the formats in fdml.yaml)
+        #[arg(short, long)]
+        language: Vec<String>,
+        
+        /// Milliseconds to wait after the last change before re-checking
+        #[arg(long, default_value = "300")]
+        debounce: u64,
+        
+        /// Check once and exit instead of watching
+        #[arg(long)]
+        once: bool,
+    },
+    
+    /// Start the FDML language server on stdio
+    Lsp,
+    
+    /// Order features into implementation waves by their dependencies
+    Plan {
+        /// Path to the FDML file
+        file: String,
+        
+        /// Output format (markdown, json)
+        #[arg(short, long, default_value = "markdown")]
+        format: String,
+        
+        /// Output file (prints to stdout if omitted)
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+    
     /// Generate code from FDML features
     Generate {
         /// Path to the FDML file or project directory
@@ -101,6 +183,18 @@ pub enum Commands {
         #[command(subcommand)]
         operation: TraceCommands,
     },
+    
+    /// Design principle operations
+    Principles {
+        #[command(subcommand)]
+        operation: PrinciplesCommands,
+    },
+    
+    /// Constraint condition operations
+    Constraints {
+        #[command(subcommand)]
+        operation: ConstraintsCommands,
+    },
 }
 
 #[derive(Subcommand)]
@@ -115,6 +209,10 @@ pub enum MigrateCommands {
         #[arg(short, long)]
         target: Option<String>,
         
+        /// Apply only up to this migration (and the migrations it depends on)
+        #[arg(long)]
+        to: Option<String>,
+        
         /// Dry run mode (don't apply changes)
         #[arg(long)]
         dry_run: bool,
@@ -134,6 +232,10 @@ pub enum MigrateCommands {
         #[arg(short, long, default_value = "1")]
         count: usize,
         
+        /// Roll back every migration applied after this one (overrides --count)
+        #[arg(long)]
+        to: Option<String>,
+        
         /// Dry run mode (don't apply changes)
         #[arg(long)]
         dry_run: bool,
@@ -145,6 +247,135 @@ pub enum MigrateCommands {
         #[arg(short, long, default_value = "./migrations")]
         path: String,
     },
+    
+    /// Verify that replaying all migrations reproduces the target file
+    Verify {
+        /// Path to migration files
+        #[arg(short, long, default_value = "./migrations")]
+        path: String,
+        
+        /// Target FDML file to compare against
+        #[arg(short, long)]
+        target: String,
+        
+        /// Document to replay migrations on (defaults to an empty document)
+        #[arg(long)]
+        baseline: Option<String>,
+    },
+    
+    /// Squash a contiguous range of migrations into one
+    Squash {
+        /// Path to migration files
+        #[arg(short, long, default_value = "./migrations")]
+        path: String,
+        
+        /// First migration of the range
+        #[arg(long)]
+        from: String,
+        
+        /// Last migration of the range
+        #[arg(long)]
+        to: String,
+        
+        /// Dry run mode (don't write changes)
+        #[arg(long)]
+        dry_run: bool,
+    },
+    
+    /// Manage backups taken before migration runs
+    Backups {
+        #[command(subcommand)]
+        operation: BackupCommands,
+    },
+    
+    /// Create a new timestamped migration template
+    New {
+        /// Migration title
+        title: String,
+        
+        /// Path to migration files
+        #[arg(short, long, default_value = "./migrations")]
+        path: String,
+    },
+    
+    /// Show migration history
+    History {
+        /// Path to migration files
+        #[arg(short, long, default_value = "./migrations")]
+        path: String,
+        
+        /// Show design principle evolution per element
+        #[arg(long)]
+        principles: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum BackupCommands {
+    /// List backups with the migration run that produced them
+    List {
+        /// Path to migration files
+        #[arg(short, long, default_value = "./migrations")]
+        path: String,
+    },
+    
+    /// Restore a backup and the migration state recorded with it
+    Restore {
+        /// Backup name
+        name: String,
+        
+        /// Path to migration files
+        #[arg(short, long, default_value = "./migrations")]
+        path: String,
+        
+        /// Target FDML file to restore (defaults to the file the backup was taken from)
+        #[arg(short, long)]
+        target: Option<String>,
+    },
+    
+    /// Delete old backups
+    Prune {
+        /// Number of most recent backups to keep
+        #[arg(long)]
+        keep: usize,
+        
+        /// Path to migration files
+        #[arg(short, long, default_value = "./migrations")]
+        path: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum PrinciplesCommands {
+    /// Report which elements declare and apply which design principles
+    Report {
+        /// Path to the FDML file
+        file: String,
+        
+        /// Output format (markdown, html, json)
+        #[arg(short, long, default_value = "markdown")]
+        format: String,
+        
+        /// Output file (prints to stdout if omitted)
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ConstraintsCommands {
+    /// Evaluate constraint conditions against sample records
+    Eval {
+        /// Path to the FDML file
+        file: String,
+        
+        /// JSON file mapping entity IDs to lists of sample records
+        records: String,
+        
+        /// Only evaluate this constraint
+        #[arg(short, long)]
+        constraint: Option<String>,
+    },
 }
 
 #[derive(Subcommand)]
@@ -1,13 +1,21 @@
-use crate::cli::args::{Cli, Commands, MigrateCommands, TraceCommands, AddCommands, ListCommands};
+use crate::cli::args::{Cli, Commands, MigrateCommands, BackupCommands, TraceCommands, PrinciplesCommands, ConstraintsCommands, AddCommands, ListCommands};
+use crate::condition::{parse_condition, Evaluator};
 use crate::error::{print_error, print_info, print_success, print_warning, Result};
 use crate::parser::{parse_fdml_yaml, parse_fdml};
-use crate::project::ProjectInitializer;
+use crate::parser::format::format_fdml;
+use crate::principles::report::CoverageReport;
+use crate::project::{ImplementationPlan, ProjectConfig, ProjectInitializer, ProjectWatcher, WatchOptions};
 use crate::validator::Validator;
+use crate::validator::conditions::CONDITION_TYPES;
+use crate::validator::fix::{fix_content, unified_diff, FixOutcome};
+use crate::validator::report::{junit_report, sarif_report};
+use crate::validator::unused::{empty_scenarios, orphaned_elements};
 use crate::generators::{create_generator, GeneratorConfig};
 use crate::generators::test_gen::TestGenerator;
-use crate::migration::{MigrationRunner, Migration, MigrationOperation};
+use crate::migration::{MigrationRunner, Migration, MigrationOperation, DesignImpact, PrincipleChange};
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 pub struct CommandRunner {
     verbose: bool,
@@ -22,7 +30,16 @@ impl CommandRunner {
         match cli.command {
             Commands::Init { name, force } => self.run_init(name, force),
             Commands::Parse { file, output, debug } => self.run_parse(file, output, debug),
-            Commands::Validate { file, strict, output } => self.run_validate(file, strict, output),
+            Commands::Validate { file, strict, output, fix, dry_run } => {
+                self.run_validate(file, strict, output, fix, dry_run)
+            },
+            Commands::Lint { file, unused, scenarios, strict, output } => self.run_lint(file, unused, scenarios, strict, output),
+            Commands::Fmt { paths, check } => self.run_fmt(paths, check),
+            Commands::Watch { path, generate, language, debounce, once } => {
+                self.run_watch(path, generate, language, debounce, once)
+            },
+            Commands::Lsp => crate::lsp::run_stdio(),
+            Commands::Plan { file, format, output } => self.run_plan(file, format, output),
             Commands::Generate { input, language, output, template, with_tests } => {
                 self.run_generate(input, language, output, template, with_tests)
             },
@@ -30,6 +47,8 @@ impl CommandRunner {
             Commands::List { operation } => self.run_list(operation),
             Commands::Migrate { operation } => self.run_migrate(operation),
             Commands::Trace { operation } => self.run_trace(operation),
+            Commands::Principles { operation } => self.run_principles(operation),
+            Commands::Constraints { operation } => self.run_constraints(operation),
         }
     }
     
@@ -54,16 +73,32 @@ impl CommandRunner {
         Ok(())
     }
     
-    fn run_validate(&self, file: String, strict: bool, output: String) -> Result<()> {
+    fn run_validate(&self, file: String, strict: bool, output: String, fix: bool, dry_run: bool) -> Result<()> {
         if self.verbose {
             print_info(&format!("Validating FDML file: {}", file));
         }
         
         // Read the file
-        let content = fs::read_to_string(&file).map_err(|e| {
+        let mut content = fs::read_to_string(&file).map_err(|e| {
             crate::error::FdmlError::project_error(format!("Failed to read file '{}': {}", file, e))
         })?;
         
+        // Optional rules enabled in the project's fdml.yaml
+        let optional_rules = ProjectConfig::find(Path::new(&file))?.map(|config| config.validation_rules()).unwrap_or_default();
+        let validator = Validator::with_optional_rules(&optional_rules);
+        
+        // Apply safe fixes first so only the remaining findings are reported
+        if fix {
+            let outcome = fix_content(&validator, &content).map_err(|e| {
+                crate::error::FdmlError::project_error(format!("Not fixing '{}': {}", file, e))
+            })?;
+            self.report_fixes(&file, &content, &outcome, dry_run, output != "text");
+            if !dry_run && outcome.content != content {
+                fs::write(&file, &outcome.content)?;
+            }
+            content = outcome.content;
+        }
+        
         // Parse the FDML document
         let document = parse_fdml_yaml(&content)?;
         
@@ -72,12 +107,17 @@ impl CommandRunner {
         }
         
         // Validate the document
-        let validator = Validator::new();
-        let validation_errors = validator.validate(&document)?;
+        let findings = validator.validate_by_rule(&document);
+        let validation_errors: Vec<String> = findings.iter().flat_map(|f| f.messages.clone()).collect();
         
         // Output results
         match output.as_str() {
             "json" => self.output_json_results(&validation_errors)?,
+            "sarif" => {
+                let report = sarif_report(&file, &content, &findings, strict);
+                println!("{}", serde_json::to_string_pretty(&report)?);
+            },
+            "junit" => print!("{}", junit_report(&file, &content, &findings)),
             "text" | _ => self.output_text_results(&file, &validation_errors, strict)?,
         }
         
@@ -116,6 +156,35 @@ impl CommandRunner {
         Ok(())
     }
     
+    /// Describe applied (or, with `dry_run`, pending) fixes; machine-readable outputs keep stdout clean
+    fn report_fixes(&self, file: &str, original: &str, outcome: &FixOutcome, dry_run: bool, to_stderr: bool) {
+        let mut lines = Vec::new();
+        if outcome.fixes.is_empty() {
+            lines.push("No automatic fixes available".to_string());
+        } else {
+            let verb = if dry_run { "Would apply" } else { "Applied" };
+            lines.push(format!("{} {} fix(es) to {}:", verb, outcome.fixes.len(), file));
+            lines.extend(outcome.fixes.iter().map(|fix| format!("  [{}] {}", fix.rule, fix.description)));
+        }
+        if dry_run {
+            let diff = unified_diff(file, original, &outcome.content);
+            if !diff.is_empty() {
+                lines.push(diff.trim_end().to_string());
+            }
+        }
+        
+        if to_stderr {
+            lines.iter().for_each(|line| eprintln!("{}", line));
+            return;
+        }
+        if outcome.fixes.is_empty() {
+            print_info(&lines[0]);
+        } else {
+            print_success(&lines[0]);
+        }
+        lines[1..].iter().for_each(|line| println!("{}", line));
+    }
+    
     fn output_json_results(&self, errors: &[String]) -> Result<()> {
         let result = serde_json::json!({
             "valid": errors.is_empty(),
@@ -127,6 +196,60 @@ impl CommandRunner {
         Ok(())
     }
     
+    fn run_lint(&self, file: String, unused: bool, scenarios: bool, strict: bool, output: String) -> Result<()> {
+        if self.verbose {
+            print_info(&format!("Linting FDML file: {}", file));
+        }
+        
+        let content = fs::read_to_string(&file).map_err(|e| {
+            crate::error::FdmlError::project_error(format!("Failed to read file '{}': {}", file, e))
+        })?;
+        let document = parse_fdml_yaml(&content)?;
+        
+        // Every analysis runs when none is selected
+        let all = !unused && !scenarios;
+        let mut findings: Vec<(&str, String)> = Vec::new();
+        if unused || all {
+            findings.extend(orphaned_elements(&document).into_iter().map(|message| ("unused_elements", message)));
+            findings.extend(empty_scenarios(&document).into_iter().map(|message| ("empty_scenarios", message)));
+        }
+        if scenarios || all {
+            let config = ProjectConfig::find(Path::new(&file))?.unwrap_or_default();
+            findings.extend(config.scenario_lint().check(&document));
+        }
+        
+        match output.as_str() {
+            "json" => {
+                let findings: Vec<_> = findings
+                    .iter()
+                    .map(|(check, message)| serde_json::json!({ "check": check, "message": message }))
+                    .collect();
+                let result = serde_json::json!({
+                    "file": file,
+                    "finding_count": findings.len(),
+                    "findings": findings
+                });
+                println!("{}", serde_json::to_string_pretty(&result)?);
+            },
+            _ => {
+                if findings.is_empty() {
+                    print_success(&format!("✓ No lint findings in {}", file));
+                } else {
+                    print_warning(&format!("Lint findings for {}", file));
+                    for (i, (check, message)) in findings.iter().enumerate() {
+                        println!("  {}. [{}] {}", i + 1, check, message);
+                    }
+                    print_info(&format!("Found {} lint finding(s)", findings.len()));
+                }
+            },
+        }
+        
+        if strict && !findings.is_empty() {
+            return Err(crate::error::FdmlError::validation_error("Lint failed in strict mode"));
+        }
+        Ok(())
+    }
+    
     fn run_parse(&self, file: String, output: String, debug: bool) -> Result<()> {
         if self.verbose {
             print_info(&format!("Parsing FDML file: {}", file));
@@ -167,6 +290,102 @@ impl CommandRunner {
         Ok(())
     }
     
+    fn run_fmt(&self, paths: Vec<String>, check: bool) -> Result<()> {
+        let mut files = Vec::new();
+        for path in &paths {
+            collect_fdml_files(Path::new(path), &mut files)?;
+        }
+        
+        let mut unformatted = Vec::new();
+        for file in &files {
+            let content = fs::read_to_string(file).map_err(|e| {
+                crate::error::FdmlError::project_error(format!("Failed to read file '{}': {}", file.display(), e))
+            })?;
+            let formatted = format_fdml(&content).map_err(|e| {
+                crate::error::FdmlError::simple_parser_error(format!("{}: {}", file.display(), e))
+            })?;
+            
+            if formatted == content {
+                if self.verbose {
+                    print_info(&format!("{} is already formatted", file.display()));
+                }
+                continue;
+            }
+            
+            if check {
+                println!("  ✗ {}", file.display());
+            } else {
+                fs::write(file, formatted)?;
+                println!("  ✏️  Formatted {}", file.display());
+            }
+            unformatted.push(file);
+        }
+        
+        if check && !unformatted.is_empty() {
+            return Err(crate::error::FdmlError::validation_error(format!(
+                "{} of {} file(s) are not formatted; run 'fdml fmt' to fix them",
+                unformatted.len(),
+                files.len()
+            )));
+        }
+        
+        if check {
+            print_success(&format!("All {} file(s) are formatted", files.len()));
+        } else {
+            print_success(&format!("Formatted {} of {} file(s)", unformatted.len(), files.len()));
+        }
+        Ok(())
+    }
+    
+    fn run_watch(&self, path: String, generate: bool, languages: Vec<String>, debounce: u64, once: bool) -> Result<()> {
+        let options = WatchOptions {
+            generate,
+            languages,
+            debounce: Duration::from_millis(debounce),
+            ..Default::default()
+        };
+        let mut watcher = ProjectWatcher::new(&path, options)?;
+        watcher.watch(once)?;
+        
+        let (problems, _) = watcher.problem_count();
+        if once && problems > 0 {
+            return Err(crate::error::FdmlError::validation_error(format!("Found {} problem(s)", problems)));
+        }
+        Ok(())
+    }
+    
+    fn run_plan(&self, file: String, format: String, output: Option<String>) -> Result<()> {
+        if self.verbose {
+            print_info(&format!("Planning features from: {}", file));
+        }
+        
+        let content = fs::read_to_string(&file).map_err(|e| {
+            crate::error::FdmlError::project_error(format!("Failed to read file '{}': {}", file, e))
+        })?;
+        let document = parse_fdml_yaml(&content)?;
+        let plan = ImplementationPlan::build(&document)?;
+        
+        let rendered = match format.as_str() {
+            "markdown" | "md" => plan.to_markdown(),
+            "json" => format!("{}\n", serde_json::to_string_pretty(&plan)?),
+            other => {
+                return Err(crate::error::FdmlError::project_error(format!(
+                    "Unsupported plan format '{}' (expected markdown or json)",
+                    other
+                )));
+            },
+        };
+        
+        match output {
+            Some(path) => {
+                fs::write(&path, rendered)?;
+                print_success(&format!("Wrote implementation plan with {} wave(s) to {}", plan.waves.len(), path));
+            },
+            None => print!("{}", rendered),
+        }
+        Ok(())
+    }
+    
     fn run_generate(&self, input: String, language: String, output: String, template: Option<String>, with_tests: bool) -> Result<()> {
         if self.verbose {
             print_info(&format!("Generating {} code from: {}", language, input));
@@ -242,7 +461,7 @@ impl CommandRunner {
     
     fn run_migrate(&self, operation: MigrateCommands) -> Result<()> {
         match operation {
-            MigrateCommands::Apply { path, target, dry_run } => {
+            MigrateCommands::Apply { path, target, to, dry_run } => {
                 if self.verbose {
                     print_info(&format!("Applying migrations from: {}", path));
                     if let Some(ref target_file) = target {
@@ -255,7 +474,7 @@ impl CommandRunner {
                     runner = runner.with_target_file(&target_file);
                 }
                 
-                let applied = runner.apply_migrations(dry_run)?;
+                let applied = runner.apply_migrations_to(to.as_deref(), dry_run)?;
                 
                 if applied.is_empty() && !dry_run {
                     print_info("No pending migrations to apply");
@@ -263,7 +482,7 @@ impl CommandRunner {
                     print_success(&format!("Applied {} migrations", applied.len()));
                 }
             },
-            MigrateCommands::Rollback { path, target, count, dry_run } => {
+            MigrateCommands::Rollback { path, target, count, to, dry_run } => {
                 if self.verbose {
                     print_info(&format!("Rolling back {} migrations from: {}", count, path));
                     if let Some(ref target_file) = target {
@@ -276,7 +495,10 @@ impl CommandRunner {
                     runner = runner.with_target_file(&target_file);
                 }
                 
-                let rolled_back = runner.rollback_migrations(count, dry_run)?;
+                let rolled_back = match to {
+                    Some(target_id) => runner.rollback_migrations_to(&target_id, dry_run)?,
+                    None => runner.rollback_migrations(count, dry_run)?,
+                };
                 
                 if rolled_back.is_empty() && !dry_run {
                     print_info("No migrations to rollback");
@@ -297,17 +519,146 @@ impl CommandRunner {
                 println!("  Applied: {}", status.applied_count);
                 println!("  Pending: {}", status.pending_count);
                 
+                if !status.integrity_issues.is_empty() {
+                    println!();
+                    for issue in &status.integrity_issues {
+                        print_warning(&issue.to_string());
+                    }
+                }
+                
                 if !status.applied_migrations.is_empty() {
                     println!("\nApplied migrations:");
                     for migration in &status.applied_migrations {
-                        println!("  ✓ {}", migration);
+                        match status.authorship.get(migration) {
+                            Some(authorship) => println!("  ✓ {} ({})", migration, authorship),
+                            None => println!("  ✓ {}", migration),
+                        }
+                        if let Some(impact) = status.design_impacts.get(migration) {
+                            self.print_design_impact(impact);
+                        }
                     }
                 }
                 
                 if !status.pending_migrations.is_empty() {
                     println!("\nPending migrations:");
                     for migration in &status.pending_migrations {
-                        println!("  - {}", migration);
+                        match status.authorship.get(migration) {
+                            Some(authorship) => println!("  - {} ({})", migration, authorship),
+                            None => println!("  - {}", migration),
+                        }
+                        if let Some(impact) = status.design_impacts.get(migration) {
+                            self.print_design_impact(impact);
+                        }
+                    }
+                }
+            },
+            MigrateCommands::Verify { path, target, baseline } => {
+                if self.verbose {
+                    print_info(&format!("Verifying {} against migrations in: {}", target, path));
+                }
+                
+                let runner = MigrationRunner::new(&path).with_target_file(&target);
+                let verification = runner.verify_migrations(baseline.as_deref().map(Path::new))?;
+                
+                println!();
+                if !verification.pending.is_empty() {
+                    print_warning(&format!(
+                        "{} migrations are not applied yet and were included in the replay",
+                        verification.pending.len()
+                    ));
+                }
+                
+                if verification.drift.is_empty() {
+                    print_success(&format!(
+                        "{} matches the result of {} migrations",
+                        target, verification.replayed.len()
+                    ));
+                } else {
+                    println!("❌ {} differs from the migrated spec:", target);
+                    for drift in &verification.drift {
+                        println!("  - {}", drift);
+                    }
+                    return Err(crate::error::FdmlError::migration_error(format!(
+                        "Found {} element(s) changed without a migration", verification.drift.len()
+                    )));
+                }
+            },
+            MigrateCommands::Squash { path, from, to, dry_run } => {
+                if self.verbose {
+                    print_info(&format!("Squashing migrations {}..{} in: {}", from, to, path));
+                }
+                
+                let runner = MigrationRunner::new(&path);
+                let squashed = runner.squash_migrations(&from, &to, dry_run)?;
+                
+                if !dry_run {
+                    let replaced = squashed.squash.as_ref().map_or(0, |info| info.replaces.len());
+                    print_success(&format!("Squashed {} migrations into {}", replaced, squashed.id));
+                }
+            },
+            MigrateCommands::Backups { operation } => {
+                self.run_backups(operation)?;
+            },
+            MigrateCommands::New { title, path } => {
+                let runner = MigrationRunner::new(&path);
+                let created = runner.create_migration(&title)?;
+                print_success(&format!("Created migration: {}", created.display()));
+            },
+            MigrateCommands::History { path, principles } => {
+                if self.verbose {
+                    print_info(&format!("Reading migration history in: {}", path));
+                }
+                
+                let runner = MigrationRunner::new(&path);
+                
+                if principles {
+                    let history = runner.principle_history()?;
+                    
+                    if history.is_empty() {
+                        print_info("No design principle changes recorded in migrations");
+                        return Ok(());
+                    }
+                    
+                    println!("Design principle evolution:");
+                    for (element_id, events) in &history {
+                        println!("\n  {}", element_id);
+                        for event in events {
+                            let marker = if event.applied { "" } else { " (pending)" };
+                            match &event.change {
+                                PrincipleChange::Added { rationale } => {
+                                    println!("    + {} [{}]{}", event.principle, event.migration_id, marker);
+                                    if let Some(rationale) = rationale {
+                                        println!("        Rationale: {}", rationale);
+                                    }
+                                },
+                                PrincipleChange::Removed { reason } => {
+                                    println!("    - {} [{}]{}", event.principle, event.migration_id, marker);
+                                    if let Some(reason) = reason {
+                                        println!("        Reason: {}", reason);
+                                    }
+                                },
+                                PrincipleChange::StrategyChanged { old_strategy, new_strategy } => {
+                                    println!("    ~ {} strategy [{}]{}", event.principle, event.migration_id, marker);
+                                    println!("        {} -> {}",
+                                        old_strategy.as_deref().unwrap_or("(none)"),
+                                        new_strategy.as_deref().unwrap_or("(none)"));
+                                },
+                            }
+                        }
+                    }
+                } else {
+                    let history = runner.migration_history()?;
+                    
+                    if history.is_empty() {
+                        print_info("No migrations found");
+                        return Ok(());
+                    }
+                    
+                    println!("Migration history:");
+                    for (migration, applied) in &history {
+                        let marker = if *applied { "✓" } else { "-" };
+                        println!("  {} {} ({})", marker, migration.id,
+                            migration.title.as_deref().unwrap_or("No title"));
                     }
                 }
             }
@@ -315,6 +666,67 @@ impl CommandRunner {
         Ok(())
     }
     
+    fn run_backups(&self, operation: BackupCommands) -> Result<()> {
+        match operation {
+            BackupCommands::List { path } => {
+                let backups = MigrationRunner::new(&path).list_backups()?;
+                
+                if backups.is_empty() {
+                    print_info("No backups found");
+                    return Ok(());
+                }
+                
+                println!("Backups:");
+                for backup in &backups {
+                    let run = match &backup.operation {
+                        Some(operation) if backup.migrations.is_empty() => operation.clone(),
+                        Some(operation) => format!("{} {}", operation, backup.migrations.join(", ")),
+                        None => "unknown run".to_string(),
+                    };
+                    println!("  📁 {} ({}) - {}", backup.name, backup.created_at, run);
+                }
+            },
+            BackupCommands::Restore { name, path, target } => {
+                let mut runner = MigrationRunner::new(&path);
+                if let Some(target_file) = target {
+                    runner = runner.with_target_file(&target_file);
+                }
+                
+                let backup = runner.restore_backup(&name)?;
+                if backup.state.is_none() {
+                    print_warning("Backup has no recorded migration state; .migration_state.json was left unchanged");
+                }
+                print_success(&format!("Restored backup {}", backup.name));
+            },
+            BackupCommands::Prune { keep, path } => {
+                let removed = MigrationRunner::new(&path).prune_backups(keep)?;
+                
+                for name in &removed {
+                    println!("  🗑️  Removed {}", name);
+                }
+                print_success(&format!("Pruned {} backups", removed.len()));
+            },
+        }
+        Ok(())
+    }
+    
+    /// Print the design principle changes recorded by a migration
+    fn print_design_impact(&self, impact: &DesignImpact) {
+        for added in &impact.principles_added {
+            println!("      + principle {} on {}", added.principle, added.element_id);
+        }
+        for removed in &impact.principles_removed {
+            println!("      - principle {} on {}", removed.principle, removed.element_id);
+        }
+        for changed in &impact.strategy_changes {
+            println!("      ~ {} strategy on {}: {} -> {}",
+                changed.principle,
+                changed.element_id,
+                changed.old_strategy.as_deref().unwrap_or("(none)"),
+                changed.new_strategy.as_deref().unwrap_or("(none)"));
+        }
+    }
+    
     fn run_trace(&self, operation: TraceCommands) -> Result<()> {
         match operation {
             TraceCommands::Validate { path } => {
@@ -351,6 +763,136 @@ impl CommandRunner {
         Ok(())
     }
     
+    fn run_principles(&self, operation: PrinciplesCommands) -> Result<()> {
+        match operation {
+            PrinciplesCommands::Report { file, format, output } => {
+                if self.verbose {
+                    print_info(&format!("Reporting design principle coverage in: {}", file));
+                }
+                
+                let content = fs::read_to_string(&file).map_err(|e| {
+                    crate::error::FdmlError::project_error(format!("Failed to read file '{}': {}", file, e))
+                })?;
+                let document = parse_fdml_yaml(&content)?;
+                let report = CoverageReport::build(&document);
+                
+                let rendered = match format.as_str() {
+                    "markdown" | "md" => report.to_markdown(),
+                    "html" => report.to_html(),
+                    "json" => format!("{}\n", serde_json::to_string_pretty(&report)?),
+                    other => {
+                        return Err(crate::error::FdmlError::project_error(format!(
+                            "Unsupported report format '{}' (expected markdown, html or json)",
+                            other
+                        )));
+                    },
+                };
+                
+                match output {
+                    Some(path) => {
+                        fs::write(&path, rendered)?;
+                        print_success(&format!(
+                            "Wrote design principle report with {} critical element(s) without principles to {}",
+                            report.uncovered.len(),
+                            path
+                        ));
+                    },
+                    None => print!("{}", rendered),
+                }
+            },
+        }
+        Ok(())
+    }
+    
+    fn run_constraints(&self, operation: ConstraintsCommands) -> Result<()> {
+        match operation {
+            ConstraintsCommands::Eval { file, records, constraint } => {
+                if self.verbose {
+                    print_info(&format!("Evaluating constraint conditions in {} against {}", file, records));
+                }
+                
+                let content = fs::read_to_string(&file).map_err(|e| {
+                    crate::error::FdmlError::project_error(format!("Failed to read file '{}': {}", file, e))
+                })?;
+                let document = parse_fdml_yaml(&content)?;
+                let samples = fs::read_to_string(&records).map_err(|e| {
+                    crate::error::FdmlError::project_error(format!("Failed to read records '{}': {}", records, e))
+                })?;
+                let samples: serde_json::Value = serde_json::from_str(&samples)?;
+                let Some(samples) = samples.as_object() else {
+                    return Err(crate::error::FdmlError::project_error(format!(
+                        "Records file '{}' must map entity IDs to lists of records",
+                        records
+                    )));
+                };
+                
+                let constraints: Vec<_> = document.constraints.iter()
+                    .filter(|candidate| constraint.as_ref().is_none_or(|id| candidate.id == *id))
+                    .collect();
+                if let (Some(id), true) = (&constraint, constraints.is_empty()) {
+                    return Err(crate::error::FdmlError::project_error(format!("Constraint '{}' not found", id)));
+                }
+                
+                let (mut evaluated, mut violations) = (0, 0);
+                for item in constraints {
+                    let condition_type = constraint.is_some() || CONDITION_TYPES.contains(&item.constraint_type.as_str());
+                    let condition = match parse_condition(&item.rule) {
+                        Ok(condition) if condition_type || condition.tests_values() => condition,
+                        Err(problem) if condition_type => {
+                            print_warning(&format!("Constraint '{}' rule is not a valid condition: {}", item.id, problem));
+                            continue;
+                        },
+                        _ => {
+                            if self.verbose {
+                                print_info(&format!("Skipping constraint '{}': its rule is not a condition", item.id));
+                            }
+                            continue;
+                        },
+                    };
+                    
+                    let evaluator = Evaluator::new(&condition).map_err(|problem| {
+                        crate::error::FdmlError::validation_error(format!("Constraint '{}': {}", item.id, problem))
+                    })?;
+                    
+                    for entity in item.entities.iter().flatten() {
+                        let Some(entity_records) = samples.get(entity).and_then(|value| value.as_array()) else {
+                            continue;
+                        };
+                        let results = evaluator.evaluate(entity_records).map_err(|problem| {
+                            crate::error::FdmlError::validation_error(format!("Constraint '{}' on '{}' records: {}", item.id, entity, problem))
+                        })?;
+                        let failed: Vec<usize> = results.iter().enumerate().filter(|(_, passed)| !**passed).map(|(index, _)| index).collect();
+                        evaluated += entity_records.len();
+                        violations += failed.len();
+                        
+                        if failed.is_empty() {
+                            print_success(&format!("✓ {} ({}): all {} '{}' record(s) pass", item.id, condition, entity_records.len(), entity));
+                        } else {
+                            print_warning(&format!(
+                                "{} ({}): {} of {} '{}' record(s) fail",
+                                item.id, condition, failed.len(), entity_records.len(), entity
+                            ));
+                            for index in failed {
+                                println!("  {}[{}] {}", entity, index, entity_records[index]);
+                            }
+                        }
+                    }
+                }
+                
+                if evaluated == 0 {
+                    print_warning("No sample records belong to the entities of a constraint with a condition");
+                }
+                if violations > 0 {
+                    return Err(crate::error::FdmlError::validation_error(format!(
+                        "{} record(s) violate constraint conditions",
+                        violations
+                    )));
+                }
+            },
+        }
+        Ok(())
+    }
+    
     fn run_add(&self, operation: AddCommands) -> Result<()> {
         match operation {
             AddCommands::Feature { id, title, description, target } => {
@@ -366,6 +908,9 @@ impl CommandRunner {
                     title: title.clone(),
                     description,
                     scenarios: None,
+                    entity: None,
+                    operation: None,
+                    roles: None,
                 };
                 
                 self.apply_single_operation(op, target)?;
@@ -601,17 +1146,21 @@ impl CommandRunner {
         let runner = MigrationRunner::new(&temp_dir).with_target_file(&target_file);
         
         // Validate the operation
-        runner.validate_operation(&operation)?;
+        runner.validate_new_operation(&operation)?;
         
         // Create a temporary migration file
         let migration_id = format!("direct_{}", timestamp);
         let migration = Migration {
             id: migration_id.clone(),
             title: Some("Direct CLI operation".to_string()),
+            author: None,
+            date: None,
             description: Some("Migration created by direct CLI command".to_string()),
             up: vec![operation],
             down: vec![], // We don't need rollback for direct operations
             dependencies: None,
+            design_impact: None,
+            squash: None,
         };
         
         // Write the temporary migration file
@@ -684,6 +1233,30 @@ impl CommandRunner {
     }
 }
 
+/// Collect `.fdml` files under `path`, skipping hidden directories such as backups
+fn collect_fdml_files(path: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+    if path.is_file() {
+        files.push(path.to_path_buf());
+        return Ok(());
+    }
+    
+    let mut entries: Vec<PathBuf> = fs::read_dir(path)
+        .map_err(|e| crate::error::FdmlError::project_error(format!("Failed to read '{}': {}", path.display(), e)))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .collect();
+    entries.sort();
+    
+    for entry in entries {
+        let hidden = entry.file_name().and_then(|name| name.to_str()).is_some_and(|name| name.starts_with('.'));
+        if entry.is_dir() && !hidden && !entry.ends_with("target") {
+            collect_fdml_files(&entry, files)?;
+        } else if entry.extension().and_then(|s| s.to_str()) == Some("fdml") {
+            files.push(entry);
+        }
+    }
+    Ok(())
+}
+
 impl Default for CommandRunner {
     fn default() -> Self {
         Self::new(false)
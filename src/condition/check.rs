@@ -0,0 +1,290 @@
+//! Type checks for conditions against the fields of the entities a constraint applies to.
+
+use crate::condition::parser::{CompareOp, Expr, Function, Literal};
+use crate::parser::ast::{Entity, FdmlDocument};
+use crate::validator::suggest::{closest_match, did_you_mean};
+use std::fmt;
+
+/// Value type of an operand; `Unknown` (custom and object fields) passes every check
+#[derive(Debug, Clone, PartialEq)]
+enum Type {
+    Number,
+    Text,
+    Boolean,
+    Date,
+    List,
+    Record(String),
+    Null,
+    Unknown,
+}
+
+impl Type {
+    fn of_field(field_type: &str, document: &FdmlDocument) -> Self {
+        match field_type {
+            "string" | "text" | "email" | "uuid" | "enum" => Type::Text,
+            "int" | "integer" | "float" | "double" | "decimal" => Type::Number,
+            "bool" | "boolean" => Type::Boolean,
+            "date" | "datetime" => Type::Date,
+            "array" => Type::List,
+            other if document.entities.iter().any(|entity| entity.id == other) => Type::Record(other.to_string()),
+            _ => Type::Unknown,
+        }
+    }
+
+    fn of_literal(literal: &Literal) -> Self {
+        match literal {
+            Literal::Number(_) => Type::Number,
+            Literal::String(_) => Type::Text,
+            Literal::Bool(_) => Type::Boolean,
+            Literal::Null => Type::Null,
+        }
+    }
+
+    /// Whether values of the two types can be compared; dates compare with date strings
+    fn comparable(&self, other: &Type, op: CompareOp) -> bool {
+        match (self, other) {
+            (Type::Unknown, _) | (_, Type::Unknown) => true,
+            (Type::Null, _) | (_, Type::Null) => !op.is_ordering(),
+            (Type::Boolean, Type::Boolean) => !op.is_ordering(),
+            (Type::Date, Type::Text) | (Type::Text, Type::Date) => true,
+            (Type::Number, Type::Number) | (Type::Text, Type::Text) | (Type::Date, Type::Date) => true,
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Type::Number => write!(f, "number"),
+            Type::Text => write!(f, "string"),
+            Type::Boolean => write!(f, "boolean"),
+            Type::Date => write!(f, "date"),
+            Type::List => write!(f, "list"),
+            Type::Record(entity) => write!(f, "entity '{}'", entity),
+            Type::Null => write!(f, "null"),
+            Type::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
+/// Problems with a parsed condition, worded to follow "Constraint 'x' rule ...".
+///
+/// Paths may start with one of the constraint's entities (`product.price`); otherwise
+/// they name a field of one of them. Without entities only entity-prefixed paths are checked.
+pub fn check_condition(condition: &Expr, document: &FdmlDocument, entities: &[String]) -> Vec<String> {
+    let mut checker = Checker {
+        document,
+        entities: document.entities.iter().filter(|entity| entities.contains(&entity.id)).collect(),
+        errors: Vec::new(),
+    };
+    checker.condition(condition);
+    checker.errors
+}
+
+struct Checker<'a> {
+    document: &'a FdmlDocument,
+    entities: Vec<&'a Entity>,
+    errors: Vec<String>,
+}
+
+impl<'a> Checker<'a> {
+    /// Check an expression used as true or false
+    fn condition(&mut self, expr: &Expr) {
+        let found = self.type_of(expr);
+        if !matches!(found, Type::Boolean | Type::Unknown) {
+            self.errors.push(format!("uses {} ({}) as a condition", expr, found));
+        }
+    }
+
+    fn type_of(&mut self, expr: &Expr) -> Type {
+        match expr {
+            Expr::Literal(literal) => Type::of_literal(literal),
+            Expr::Path(path) => self.resolve(path),
+            Expr::Call { function, argument } => {
+                let found = self.type_of(argument);
+                let accepted = match function {
+                    Function::Length => matches!(found, Type::Text | Type::List | Type::Unknown),
+                    Function::Count => matches!(found, Type::List | Type::Unknown),
+                };
+                if !accepted {
+                    let expected = if *function == Function::Length { "a string or list" } else { "a list" };
+                    self.errors.push(format!("calls {}() on {} ({}), which needs {}", function.name(), argument, found, expected));
+                }
+                Type::Number
+            },
+            Expr::Not(operand) => {
+                self.condition(operand);
+                Type::Boolean
+            },
+            Expr::And(left, right) | Expr::Or(left, right) => {
+                self.condition(left);
+                self.condition(right);
+                Type::Boolean
+            },
+            Expr::Compare { left, op, right } => {
+                let (left_type, right_type) = (self.type_of(left), self.type_of(right));
+                if !left_type.comparable(&right_type, *op) {
+                    self.errors.push(format!(
+                        "compares {} ({}) {} {} ({})",
+                        left, left_type, op.symbol(), right, right_type
+                    ));
+                }
+                Type::Boolean
+            },
+            Expr::IsUnique(path) => {
+                self.resolve(path);
+                Type::Boolean
+            },
+            Expr::IsNull { operand, .. } => {
+                self.type_of(operand);
+                Type::Boolean
+            },
+            Expr::In { operand, values, .. } => {
+                let found = self.type_of(operand);
+                for value in values {
+                    let value_type = Type::of_literal(value);
+                    if !found.comparable(&value_type, CompareOp::Eq) {
+                        self.errors.push(format!("lists {} ({}) as a value of {} ({})", value, value_type, operand, found));
+                    }
+                }
+                Type::Boolean
+            },
+            Expr::Matches { operand, pattern } => {
+                let found = self.type_of(operand);
+                if !matches!(found, Type::Text | Type::Date | Type::Unknown) {
+                    self.errors.push(format!("matches {} ({}) against a regex, which needs a string", operand, found));
+                }
+                if let Err(problem) = regex::Regex::new(pattern) {
+                    self.errors.push(format!("has an invalid regex /{}/: {}", pattern, problem));
+                }
+                Type::Boolean
+            },
+        }
+    }
+
+    fn resolve(&mut self, path: &[String]) -> Type {
+        let prefixed = |entity: &Entity| path.len() > 1 && entity.id == path[0];
+        let scope: Vec<&Entity> = if self.entities.is_empty() {
+            match self.document.entities.iter().find(|entity| prefixed(entity)) {
+                Some(entity) => vec![entity],
+                None => return Type::Unknown,
+            }
+        } else {
+            self.entities.clone()
+        };
+
+        let (entity, fields) = match scope.iter().find(|entity| prefixed(entity)) {
+            Some(entity) => (*entity, &path[1..]),
+            None => match scope.iter().find(|entity| entity.fields.iter().any(|field| field.name == path[0])) {
+                Some(entity) => (*entity, path),
+                None => {
+                    let candidates = scope.iter().flat_map(|entity| {
+                        entity.fields.iter().map(|field| field.name.as_str()).chain([entity.id.as_str()])
+                    });
+                    let owners: Vec<&str> = scope.iter().map(|entity| entity.id.as_str()).collect();
+                    self.errors.push(did_you_mean(
+                        format!("refers to unknown field '{}' of entity '{}'", path[0], owners.join("' or '")),
+                        closest_match(&path[0], candidates),
+                    ));
+                    return Type::Unknown;
+                },
+            },
+        };
+        self.walk(entity, fields)
+    }
+
+    /// Follow `fields` from `entity` through fields whose type is another entity
+    fn walk(&mut self, mut entity: &'a Entity, fields: &[String]) -> Type {
+        let document = self.document;
+        for (index, name) in fields.iter().enumerate() {
+            let Some(field) = entity.fields.iter().find(|field| field.name == *name) else {
+                self.errors.push(did_you_mean(
+                    format!("refers to unknown field '{}' of entity '{}'", name, entity.id),
+                    closest_match(name, entity.fields.iter().map(|field| field.name.as_str())),
+                ));
+                return Type::Unknown;
+            };
+            let found = Type::of_field(&field.field_type, document);
+            let Some(next) = fields.get(index + 1) else {
+                return found;
+            };
+            match found {
+                Type::Record(id) => match document.entities.iter().find(|candidate| candidate.id == id) {
+                    Some(target) => entity = target,
+                    None => return Type::Unknown,
+                },
+                Type::Unknown => return Type::Unknown,
+                other => {
+                    self.errors.push(format!(
+                        "refers to '{}' inside field '{}' of entity '{}', which is a {}",
+                        next, field.name, entity.id, other
+                    ));
+                    return Type::Unknown;
+                },
+            }
+        }
+        Type::Unknown
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::condition::parse_condition;
+
+    #[test]
+    fn test_check_condition() {
+        let spec = r#"
+entities:
+  - id: customer
+    fields:
+      - name: email
+        type: email
+  - id: order
+    fields:
+      - name: total
+        type: decimal
+      - name: status
+        type: enum
+      - name: items
+        type: array
+      - name: paid
+        type: boolean
+      - name: placed_on
+        type: date
+      - name: customer
+        type: customer
+"#;
+        let document: FdmlDocument = serde_yaml::from_str(spec).unwrap();
+        let order = vec!["order".to_string()];
+        let check = |condition: &str, entities: &[String]| {
+            check_condition(&parse_condition(condition).unwrap(), &document, entities)
+        };
+
+        assert!(check("order.total > 0 and count(items) >= 1 and status in ['open', 'paid'] and paid", &order).is_empty());
+        assert!(check("customer.email matches /@/ and placed_on >= '2024-01-01' and customer.email is unique", &order).is_empty());
+        assert!(check("customer.email is unique", &[]).is_empty());
+        assert!(check("anything > 1", &[]).is_empty());
+
+        assert_eq!(check("totl > 0 and order.stauts == 'open'", &order), vec![
+            "refers to unknown field 'totl' of entity 'order' (did you mean 'total'?)",
+            "refers to unknown field 'stauts' of entity 'order' (did you mean 'status'?)",
+        ]);
+        assert_eq!(check("customer.emial is null", &order), vec![
+            "refers to unknown field 'emial' of entity 'customer' (did you mean 'email'?)",
+        ]);
+        assert_eq!(check("total > 'abc' and paid < true and status in [1]", &order), vec![
+            "compares total (number) > \"abc\" (string)",
+            "compares paid (boolean) < true (boolean)",
+            "lists 1 (number) as a value of status (string)",
+        ]);
+        assert_eq!(check("total and length(paid) > 1 and total matches /[/ and total.amount > 1", &order), vec![
+            "uses total (number) as a condition",
+            "calls length() on paid (boolean), which needs a string or list",
+            "matches total (number) against a regex, which needs a string",
+            "has an invalid regex /[/: regex parse error:\n    [\n    ^\nerror: unclosed character class",
+            "refers to 'amount' inside field 'total' of entity 'order', which is a number",
+        ]);
+    }
+}
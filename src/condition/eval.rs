@@ -0,0 +1,183 @@
+//! Evaluate conditions against sample records given as JSON objects.
+
+use crate::condition::parser::{CompareOp, Expr, Function, Literal};
+use regex::Regex;
+use serde_json::Value;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+/// Whether each record satisfies the condition; see [`Evaluator::evaluate`]
+pub fn evaluate(condition: &Expr, records: &[Value]) -> Result<Vec<bool>, String> {
+    Evaluator::new(condition)?.evaluate(records)
+}
+
+/// A condition with its `matches` patterns compiled, for evaluating several sets of records
+pub struct Evaluator<'a> {
+    condition: &'a Expr,
+    patterns: HashMap<&'a str, Regex>,
+}
+
+impl<'a> Evaluator<'a> {
+    pub fn new(condition: &'a Expr) -> Result<Self, String> {
+        let mut patterns = HashMap::new();
+        for pattern in matched_patterns(condition) {
+            let regex = Regex::new(pattern).map_err(|e| format!("invalid regex /{}/: {}", pattern, e))?;
+            patterns.insert(pattern, regex);
+        }
+        Ok(Self { condition, patterns })
+    }
+
+    /// Whether each record satisfies the condition; `is unique` compares a record with the others.
+    ///
+    /// A missing field is `null`, which fails ordering comparisons and `matches`, and never
+    /// makes a value non-unique. A path may start with the entity ID, e.g. `product.price`.
+    pub fn evaluate(&self, records: &[Value]) -> Result<Vec<bool>, String> {
+        (0..records.len())
+            .map(|index| Evaluation { records, index, patterns: &self.patterns }.truth(self.condition))
+            .collect()
+    }
+}
+
+/// Patterns of every `matches` in the condition
+fn matched_patterns(expr: &Expr) -> Vec<&str> {
+    match expr {
+        Expr::Matches { operand, pattern } => {
+            let mut patterns = matched_patterns(operand);
+            patterns.push(pattern);
+            patterns
+        },
+        Expr::Not(operand) | Expr::IsNull { operand, .. } | Expr::In { operand, .. } => matched_patterns(operand),
+        Expr::Call { argument, .. } => matched_patterns(argument),
+        Expr::And(left, right) | Expr::Or(left, right) | Expr::Compare { left, right, .. } => {
+            let mut patterns = matched_patterns(left);
+            patterns.extend(matched_patterns(right));
+            patterns
+        },
+        Expr::Literal(_) | Expr::Path(_) | Expr::IsUnique(_) => Vec::new(),
+    }
+}
+
+struct Evaluation<'a> {
+    records: &'a [Value],
+    index: usize,
+    patterns: &'a HashMap<&'a str, Regex>,
+}
+
+impl Evaluation<'_> {
+    fn truth(&self, expr: &Expr) -> Result<bool, String> {
+        Ok(match expr {
+            Expr::Not(operand) => !self.truth(operand)?,
+            Expr::And(left, right) => self.truth(left)? && self.truth(right)?,
+            Expr::Or(left, right) => self.truth(left)? || self.truth(right)?,
+            Expr::Compare { left, op, right } => compare(&self.value(left)?, *op, &self.value(right)?)?,
+            Expr::IsUnique(path) => {
+                let value = lookup(&self.records[self.index], path);
+                value.is_null()
+                    || !self.records.iter().enumerate().any(|(index, other)| index != self.index && same(&lookup(other, path), &value))
+            },
+            Expr::IsNull { operand, negated } => self.value(operand)?.is_null() != *negated,
+            Expr::In { operand, values, negated } => {
+                let value = self.value(operand)?;
+                values.iter().any(|literal| same(&value, &to_json(literal))) != *negated
+            },
+            Expr::Matches { operand, pattern } => match self.value(operand)? {
+                Value::String(text) => self.patterns[pattern.as_str()].is_match(&text),
+                Value::Null => false,
+                other => return Err(format!("{} is {}, which can't match a regex", operand, other)),
+            },
+            Expr::Literal(_) | Expr::Path(_) | Expr::Call { .. } => match self.value(expr)? {
+                Value::Bool(value) => value,
+                Value::Null => false,
+                other => return Err(format!("{} is {}, not true or false", expr, other)),
+            },
+        })
+    }
+
+    fn value(&self, expr: &Expr) -> Result<Value, String> {
+        Ok(match expr {
+            Expr::Literal(literal) => to_json(literal),
+            Expr::Path(path) => lookup(&self.records[self.index], path),
+            Expr::Call { function, argument } => {
+                let length = match (function, self.value(argument)?) {
+                    (_, Value::Null) => 0,
+                    (_, Value::Array(items)) => items.len(),
+                    (Function::Length, Value::String(text)) => text.chars().count(),
+                    (_, other) => return Err(format!("{}() can't count {} ({})", function.name(), argument, other)),
+                };
+                Value::from(length)
+            },
+            _ => Value::Bool(self.truth(expr)?),
+        })
+    }
+}
+
+/// The value at `path`, skipping a leading entity ID the record doesn't have as a field
+fn lookup(record: &Value, path: &[String]) -> Value {
+    let skip = usize::from(path.len() > 1 && record.get(&path[0]).is_none());
+    path[skip..].iter()
+        .try_fold(record, |value, name| value.get(name))
+        .cloned()
+        .unwrap_or(Value::Null)
+}
+
+fn to_json(literal: &Literal) -> Value {
+    match literal {
+        Literal::Number(number) => serde_json::Number::from_f64(*number).map_or(Value::Null, Value::Number),
+        Literal::String(text) => Value::String(text.clone()),
+        Literal::Bool(value) => Value::Bool(*value),
+        Literal::Null => Value::Null,
+    }
+}
+
+/// Equality with `1` and `1.0` the same number
+fn same(left: &Value, right: &Value) -> bool {
+    match (left, right) {
+        (Value::Number(left), Value::Number(right)) => left.as_f64() == right.as_f64(),
+        _ => left == right,
+    }
+}
+
+fn compare(left: &Value, op: CompareOp, right: &Value) -> Result<bool, String> {
+    let ordering = match (left, right) {
+        _ if !op.is_ordering() => return Ok(same(left, right) == (op == CompareOp::Eq)),
+        (Value::Null, _) | (_, Value::Null) => return Ok(false),
+        (Value::Number(left), Value::Number(right)) => left.as_f64().partial_cmp(&right.as_f64()),
+        (Value::String(left), Value::String(right)) => Some(left.cmp(right)),
+        _ => return Err(format!("can't compare {} {} {}", left, op.symbol(), right)),
+    };
+    Ok(match op {
+        CompareOp::Lt => ordering == Some(Ordering::Less),
+        CompareOp::Le => matches!(ordering, Some(Ordering::Less | Ordering::Equal)),
+        CompareOp::Gt => ordering == Some(Ordering::Greater),
+        _ => matches!(ordering, Some(Ordering::Greater | Ordering::Equal)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::condition::parse_condition;
+    use serde_json::json;
+
+    #[test]
+    fn test_evaluate() {
+        let records = vec![
+            json!({"email": "ann@example.com", "total": 10, "tags": ["new"], "status": "open"}),
+            json!({"email": "bob@example", "total": 0, "tags": [], "status": "paid"}),
+            json!({"email": "ann@example.com", "total": 2.5, "status": "void"}),
+            json!({"total": -1, "status": "open"}),
+        ];
+        let run = |condition: &str| evaluate(&parse_condition(condition).unwrap(), &records);
+
+        assert_eq!(run("order.total > 0"), Ok(vec![true, false, true, false]));
+        assert_eq!(run("email is unique"), Ok(vec![false, true, false, true]));
+        assert_eq!(run("email matches /^[^@]+@[^@]+\\.[a-z]+$/"), Ok(vec![true, false, true, false]));
+        assert_eq!(run("status not in ['void'] and (count(tags) >= 1 or email is null)"), Ok(vec![true, false, false, true]));
+        assert_eq!(run("length(email) = 15 and not total == 2.5"), Ok(vec![true, false, false, false]));
+        assert_eq!(run("email matches /(?i)^ANN\\b/ or status matches /^\\p{L}+$/"), Ok(vec![true, true, true, true]));
+        assert_eq!(run("status matches /^(a|a)*$/"), Ok(vec![false, false, false, false]));
+        assert!(run("email matches /(/").unwrap_err().starts_with("invalid regex /(/: "));
+        assert_eq!(run("total > 'a'"), Err("can't compare 10 > \"a\"".to_string()));
+        assert_eq!(run("status"), Err("status is \"open\", not true or false".to_string()));
+    }
+}
@@ -0,0 +1,7 @@
+pub mod check;
+pub mod eval;
+pub mod parser;
+
+pub use check::check_condition;
+pub use eval::{evaluate, Evaluator};
+pub use parser::{parse_condition, CompareOp, Expr, Function, Literal};
@@ -0,0 +1,498 @@
+//! Lexer and recursive-descent parser for constraint conditions.
+//!
+//! ```text
+//! condition  := or
+//! or         := and (("or" | "||") and)*
+//! and        := not (("and" | "&&") not)*
+//! not        := ("not" | "!") not | test
+//! test       := operand [ compare operand
+//!                       | "is" ["not"] ("unique" | "null")
+//!                       | ["not"] "in" "[" literal ("," literal)* "]"
+//!                       | "matches" /regex/ ]
+//! operand    := literal | path | function "(" operand ")" | "(" condition ")"
+//! ```
+
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    Literal(Literal),
+    /// Dotted field path, e.g. `order.customer.email`
+    Path(Vec<String>),
+    Call { function: Function, argument: Box<Expr> },
+    Not(Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Compare { left: Box<Expr>, op: CompareOp, right: Box<Expr> },
+    /// `path is unique`, also written `unique(path)`
+    IsUnique(Vec<String>),
+    IsNull { operand: Box<Expr>, negated: bool },
+    In { operand: Box<Expr>, values: Vec<Literal>, negated: bool },
+    Matches { operand: Box<Expr>, pattern: String },
+}
+
+impl Expr {
+    /// Whether the condition compares or tests a value anywhere, rather than only joining
+    /// bare words with `and`/`or`/`not` the way prose like "admin or owner" parses
+    pub fn tests_values(&self) -> bool {
+        match self {
+            Expr::Literal(_) | Expr::Path(_) => false,
+            Expr::Not(operand) => operand.tests_values(),
+            Expr::And(left, right) | Expr::Or(left, right) => left.tests_values() || right.tests_values(),
+            _ => true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Literal {
+    Number(f64),
+    String(String),
+    Bool(bool),
+    Null,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CompareOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl CompareOp {
+    pub fn symbol(self) -> &'static str {
+        match self {
+            CompareOp::Eq => "==",
+            CompareOp::Ne => "!=",
+            CompareOp::Lt => "<",
+            CompareOp::Le => "<=",
+            CompareOp::Gt => ">",
+            CompareOp::Ge => ">=",
+        }
+    }
+
+    /// Whether the operator orders its operands rather than testing equality
+    pub fn is_ordering(self) -> bool {
+        !matches!(self, CompareOp::Eq | CompareOp::Ne)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Function {
+    /// Characters in a string or items in a list
+    Length,
+    /// Items in a list
+    Count,
+}
+
+impl Function {
+    pub const NAMES: &'static [&'static str] = &["length", "count", "unique"];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Function::Length => "length",
+            Function::Count => "count",
+        }
+    }
+}
+
+/// Parse a condition, e.g. `total > 0 and status in ["open", "paid"]`
+pub fn parse_condition(text: &str) -> Result<Expr, String> {
+    let tokens = tokenize(text)?;
+    let mut parser = Parser { tokens, position: 0 };
+    let expr = parser.or()?;
+    match parser.peek() {
+        None => Ok(expr),
+        Some(token) => Err(format!("unexpected {} at column {}", token.kind, token.column)),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum TokenKind {
+    Ident(String),
+    Number(f64),
+    String(String),
+    Regex(String),
+    Symbol(&'static str),
+}
+
+impl fmt::Display for TokenKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TokenKind::Ident(word) => write!(f, "'{}'", word),
+            TokenKind::Number(number) => write!(f, "'{}'", number),
+            TokenKind::String(text) => write!(f, "{:?}", text),
+            TokenKind::Regex(pattern) => write!(f, "/{}/", pattern),
+            TokenKind::Symbol(symbol) => write!(f, "'{}'", symbol),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Token {
+    kind: TokenKind,
+    /// 1-based character column, for error messages
+    column: usize,
+}
+
+const SYMBOLS: &[&str] = &["==", "!=", "<>", "<=", ">=", "&&", "||", "=", "<", ">", "!", "(", ")", "[", "]", ",", ".", "-"];
+
+fn tokenize(text: &str) -> Result<Vec<Token>, String> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut tokens: Vec<Token> = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let column = i + 1;
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+
+        let kind = if c.is_alphabetic() || c == '_' {
+            let end = chars[i..].iter().position(|&c| !(c.is_alphanumeric() || c == '_')).map_or(chars.len(), |offset| i + offset);
+            let word: String = chars[i..end].iter().collect();
+            i = end;
+            TokenKind::Ident(word)
+        } else if c.is_ascii_digit() {
+            let end = chars[i..].iter().position(|&c| !(c.is_ascii_digit() || c == '.')).map_or(chars.len(), |offset| i + offset);
+            let number: String = chars[i..end].iter().collect();
+            i = end;
+            TokenKind::Number(number.parse().map_err(|_| format!("invalid number '{}' at column {}", number, column))?)
+        } else if c == '"' || c == '\'' {
+            let (literal, end) = delimited(&chars, i, c).ok_or_else(|| format!("unterminated string at column {}", column))?;
+            i = end;
+            TokenKind::String(literal)
+        } else if c == '/' && matches!(tokens.last(), Some(Token { kind: TokenKind::Ident(word), .. }) if word.eq_ignore_ascii_case("matches")) {
+            let (pattern, end) = delimited(&chars, i, '/').ok_or_else(|| format!("unterminated regex at column {}", column))?;
+            i = end;
+            TokenKind::Regex(pattern)
+        } else {
+            let rest: String = chars[i..chars.len().min(i + 2)].iter().collect();
+            let symbol = SYMBOLS.iter().find(|symbol| rest.starts_with(**symbol))
+                .ok_or_else(|| format!("unexpected '{}' at column {}", c, column))?;
+            i += symbol.chars().count();
+            TokenKind::Symbol(symbol)
+        };
+        tokens.push(Token { kind, column });
+    }
+
+    Ok(tokens)
+}
+
+/// Text between `delimiter` at `start` and the next unescaped one, and the index after it.
+/// Strings unescape `\x`; a regex keeps its escapes apart from `\/`.
+fn delimited(chars: &[char], start: usize, delimiter: char) -> Option<(String, usize)> {
+    let mut text = String::new();
+    let mut i = start + 1;
+    while i < chars.len() {
+        match chars[i] {
+            '\\' if i + 1 < chars.len() => {
+                let escaped = chars[i + 1];
+                if delimiter == '/' && escaped != '/' {
+                    text.push('\\');
+                }
+                text.push(escaped);
+                i += 2;
+            },
+            c if c == delimiter => return Some((text, i + 1)),
+            c => {
+                text.push(c);
+                i += 1;
+            },
+        }
+    }
+    None
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += usize::from(token.is_some());
+        token
+    }
+
+    /// Consume the next token if it's one of the given keywords (any case) or symbols
+    fn accept(&mut self, words: &[&str]) -> bool {
+        let matched = self.peek().is_some_and(|token| match &token.kind {
+            TokenKind::Ident(word) => words.iter().any(|expected| word.eq_ignore_ascii_case(expected)),
+            TokenKind::Symbol(symbol) => words.contains(symbol),
+            _ => false,
+        });
+        self.position += usize::from(matched);
+        matched
+    }
+
+    fn expect(&mut self, words: &[&str], after: &str) -> Result<(), String> {
+        if self.accept(words) {
+            return Ok(());
+        }
+        Err(format!("expected '{}' {}", words[0], self.position_text(after)))
+    }
+
+    /// `after 'x' at column n` or `at the end` for error messages
+    fn position_text(&self, after: &str) -> String {
+        match self.peek() {
+            Some(token) => format!("{}, found {} at column {}", after, token.kind, token.column),
+            None => format!("{} at the end", after),
+        }
+    }
+
+    fn or(&mut self) -> Result<Expr, String> {
+        let mut expr = self.and()?;
+        while self.accept(&["or", "||"]) {
+            expr = Expr::Or(Box::new(expr), Box::new(self.and()?));
+        }
+        Ok(expr)
+    }
+
+    fn and(&mut self) -> Result<Expr, String> {
+        let mut expr = self.not()?;
+        while self.accept(&["and", "&&"]) {
+            expr = Expr::And(Box::new(expr), Box::new(self.not()?));
+        }
+        Ok(expr)
+    }
+
+    fn not(&mut self) -> Result<Expr, String> {
+        if self.accept(&["not", "!"]) {
+            return Ok(Expr::Not(Box::new(self.not()?)));
+        }
+        self.test()
+    }
+
+    fn test(&mut self) -> Result<Expr, String> {
+        let operand = self.operand()?;
+
+        let op = match self.peek().map(|token| &token.kind) {
+            Some(TokenKind::Symbol("==" | "=")) => Some(CompareOp::Eq),
+            Some(TokenKind::Symbol("!=" | "<>")) => Some(CompareOp::Ne),
+            Some(TokenKind::Symbol("<")) => Some(CompareOp::Lt),
+            Some(TokenKind::Symbol("<=")) => Some(CompareOp::Le),
+            Some(TokenKind::Symbol(">")) => Some(CompareOp::Gt),
+            Some(TokenKind::Symbol(">=")) => Some(CompareOp::Ge),
+            _ => None,
+        };
+        if let Some(op) = op {
+            self.position += 1;
+            let right = self.operand()?;
+            return Ok(Expr::Compare { left: Box::new(operand), op, right: Box::new(right) });
+        }
+
+        if self.accept(&["is"]) {
+            let negated = self.accept(&["not"]);
+            if self.accept(&["null"]) {
+                return Ok(Expr::IsNull { operand: Box::new(operand), negated });
+            }
+            if !negated && self.accept(&["unique"]) {
+                return unique(operand);
+            }
+            return Err(format!("expected 'unique' or 'null' {}", self.position_text("after 'is'")));
+        }
+
+        let negated = self.accept(&["not"]);
+        if self.accept(&["in"]) {
+            self.expect(&["["], "after 'in'")?;
+            let mut values = vec![self.literal()?];
+            while self.accept(&[","]) {
+                values.push(self.literal()?);
+            }
+            self.expect(&["]"], "after the list")?;
+            return Ok(Expr::In { operand: Box::new(operand), values, negated });
+        }
+        if negated {
+            return Err(format!("expected 'in' {}", self.position_text("after 'not'")));
+        }
+
+        if self.accept(&["matches"]) {
+            return match self.next() {
+                Some(Token { kind: TokenKind::Regex(pattern), .. }) => Ok(Expr::Matches { operand: Box::new(operand), pattern }),
+                _ => Err("expected /regex/ after 'matches'".to_string()),
+            };
+        }
+
+        Ok(operand)
+    }
+
+    fn operand(&mut self) -> Result<Expr, String> {
+        if self.accept(&["("]) {
+            let expr = self.or()?;
+            self.expect(&[")"], "to close '('")?;
+            return Ok(expr);
+        }
+
+        let calls_unique = matches!(self.tokens.get(self.position + 1), Some(Token { kind: TokenKind::Symbol("("), .. }));
+        let is_name = matches!(self.peek(), Some(Token { kind: TokenKind::Ident(word), .. })
+            if !is_keyword(word) || (calls_unique && word.eq_ignore_ascii_case("unique")));
+        if !is_name {
+            return self.literal().map(Expr::Literal);
+        }
+
+        let path = self.path()?;
+        if path.len() > 1 || !self.accept(&["("]) {
+            return Ok(Expr::Path(path));
+        }
+
+        let argument = self.operand()?;
+        self.expect(&[")"], &format!("after the argument of '{}'", path[0]))?;
+        match path[0].to_lowercase().as_str() {
+            "length" => Ok(Expr::Call { function: Function::Length, argument: Box::new(argument) }),
+            "count" => Ok(Expr::Call { function: Function::Count, argument: Box::new(argument) }),
+            "unique" => unique(argument),
+            _ => Err(crate::validator::suggest::did_you_mean(
+                format!("unknown function '{}'", path[0]),
+                crate::validator::suggest::closest_match(&path[0], Function::NAMES.iter().copied()),
+            )),
+        }
+    }
+
+    fn path(&mut self) -> Result<Vec<String>, String> {
+        let mut path = Vec::new();
+        loop {
+            match self.next() {
+                Some(Token { kind: TokenKind::Ident(word), .. }) => path.push(word),
+                _ => return Err(format!("expected a field name {}", self.position_text("after '.'"))),
+            }
+            if !self.accept(&["."]) {
+                return Ok(path);
+            }
+        }
+    }
+
+    fn literal(&mut self) -> Result<Literal, String> {
+        let negative = self.accept(&["-"]);
+        let token = self.next();
+        match token.as_ref().map(|token| &token.kind) {
+            Some(TokenKind::Number(number)) => Ok(Literal::Number(if negative { -number } else { *number })),
+            _ if negative => Err("expected a number after '-'".to_string()),
+            Some(TokenKind::String(text)) => Ok(Literal::String(text.clone())),
+            Some(TokenKind::Ident(word)) if word.eq_ignore_ascii_case("true") => Ok(Literal::Bool(true)),
+            Some(TokenKind::Ident(word)) if word.eq_ignore_ascii_case("false") => Ok(Literal::Bool(false)),
+            Some(TokenKind::Ident(word)) if word.eq_ignore_ascii_case("null") => Ok(Literal::Null),
+            Some(kind) => Err(format!("expected a value, found {} at column {}", kind, token.as_ref().map_or(0, |token| token.column))),
+            None => Err("expected a value at the end".to_string()),
+        }
+    }
+}
+
+const KEYWORDS: &[&str] = &["and", "or", "not", "is", "in", "matches", "unique", "true", "false", "null"];
+
+fn is_keyword(word: &str) -> bool {
+    KEYWORDS.iter().any(|keyword| word.eq_ignore_ascii_case(keyword))
+}
+
+fn unique(operand: Expr) -> Result<Expr, String> {
+    match operand {
+        Expr::Path(path) => Ok(Expr::IsUnique(path)),
+        other => Err(format!("only a field can be unique, not {}", other)),
+    }
+}
+
+impl fmt::Display for Literal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Literal::Number(number) => write!(f, "{}", number),
+            Literal::String(text) => write!(f, "{:?}", text),
+            Literal::Bool(value) => write!(f, "{}", value),
+            Literal::Null => write!(f, "null"),
+        }
+    }
+}
+
+/// Canonical text of a condition, with the minimal parentheses
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let nested = |expr: &Expr, f: &mut fmt::Formatter<'_>, own: u8| {
+            if precedence(expr) < own {
+                write!(f, "({})", expr)
+            } else {
+                write!(f, "{}", expr)
+            }
+        };
+        match self {
+            Expr::Literal(literal) => write!(f, "{}", literal),
+            Expr::Path(path) => write!(f, "{}", path.join(".")),
+            Expr::Call { function, argument } => write!(f, "{}({})", function.name(), argument),
+            Expr::Not(operand) => {
+                write!(f, "not ")?;
+                nested(operand, f, 2)
+            },
+            Expr::And(left, right) => {
+                nested(left, f, 1)?;
+                write!(f, " and ")?;
+                nested(right, f, 2)
+            },
+            Expr::Or(left, right) => {
+                nested(left, f, 0)?;
+                write!(f, " or ")?;
+                nested(right, f, 1)
+            },
+            Expr::Compare { left, op, right } => {
+                nested(left, f, 4)?;
+                write!(f, " {} ", op.symbol())?;
+                nested(right, f, 4)
+            },
+            Expr::IsUnique(path) => write!(f, "{} is unique", path.join(".")),
+            Expr::IsNull { operand, negated } => {
+                nested(operand, f, 4)?;
+                write!(f, " is {}null", if *negated { "not " } else { "" })
+            },
+            Expr::In { operand, values, negated } => {
+                nested(operand, f, 4)?;
+                let values: Vec<String> = values.iter().map(ToString::to_string).collect();
+                write!(f, " {}in [{}]", if *negated { "not " } else { "" }, values.join(", "))
+            },
+            Expr::Matches { operand, pattern } => {
+                nested(operand, f, 4)?;
+                write!(f, " matches /{}/", pattern.replace('/', "\\/"))
+            },
+        }
+    }
+}
+
+fn precedence(expr: &Expr) -> u8 {
+    match expr {
+        Expr::Or(..) => 0,
+        Expr::And(..) => 1,
+        Expr::Not(_) => 2,
+        Expr::Compare { .. } | Expr::IsUnique(_) | Expr::IsNull { .. } | Expr::In { .. } | Expr::Matches { .. } => 3,
+        Expr::Literal(_) | Expr::Path(_) | Expr::Call { .. } => 4,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_condition() {
+        let condition = parse_condition("order.total > 0 AND (status in ['open', \"paid\"] || !unique(email)) and length(name) >= 2").unwrap();
+        assert_eq!(condition.to_string(), "order.total > 0 and (status in [\"open\", \"paid\"] or not email is unique) and length(name) >= 2");
+
+        let condition = parse_condition(r"code matches /^[A-Z]\/\d+$/ and discount is not null and tier not in [-1, 2.5]").unwrap();
+        assert_eq!(condition.to_string(), r"code matches /^[A-Z]\/\d+$/ and discount is not null and tier not in [-1, 2.5]");
+        assert!(matches!(parse_condition("email is unique"), Ok(Expr::IsUnique(path)) if path == ["email"]));
+        assert!(parse_condition("not active or length(name) > 0").unwrap().tests_values());
+        assert!(!parse_condition("admin or not owner").unwrap().tests_values());
+
+        assert_eq!(parse_condition("total >").unwrap_err(), "expected a value at the end");
+        assert_eq!(parse_condition("status in ['open'").unwrap_err(), "expected ']' after the list at the end");
+        assert_eq!(parse_condition("lenght(name) > 2").unwrap_err(), "unknown function 'lenght' (did you mean 'length'?)");
+        assert_eq!(parse_condition("5 is unique").unwrap_err(), "only a field can be unique, not 5");
+        assert_eq!(parse_condition("emails are unique").unwrap_err(), "unexpected 'are' at column 8");
+        assert_eq!(parse_condition("order.status transitions: pending -> shipped").unwrap_err(), "unexpected ':' at column 25");
+    }
+}
@@ -5,6 +5,9 @@ pub mod project;
 pub mod validator;
 pub mod generators;
 pub mod migration;
+pub mod principles;
+pub mod condition;
+pub mod lsp;
 
 pub use cli::{Cli, CommandRunner};
 pub use error::{FdmlError, Result};
@@ -0,0 +1,473 @@
+//! Language server for FDML files, speaking LSP (JSON-RPC) over stdio.
+
+use std::collections::HashMap;
+use std::io::{self, BufRead, Write};
+use serde_json::{json, Value};
+use crate::error::{FdmlError, Result};
+use crate::parser::ast::FdmlDocument;
+use crate::parser::index::{DocumentIndex, Symbol};
+use crate::parser::outline::Segment;
+use crate::validator::suggest::{split_suggestion, FIELD_TYPES};
+use crate::validator::Validator;
+
+struct OpenDocument {
+    text: String,
+    index: DocumentIndex,
+    /// Last version that parsed, for hover details
+    parsed: Option<FdmlDocument>,
+}
+
+impl OpenDocument {
+    fn new(text: String, previous: Option<FdmlDocument>) -> Self {
+        let parsed = serde_yaml::from_str::<FdmlDocument>(&text).ok().or(previous);
+        Self { index: DocumentIndex::build(&text), text, parsed }
+    }
+
+    fn line(&self, line: usize) -> &str {
+        self.text.lines().nth(line).unwrap_or_default()
+    }
+
+    /// LSP position (UTF-16 column) to line and byte column
+    fn offset(&self, position: &Value) -> (usize, usize) {
+        let line = position["line"].as_u64().unwrap_or(0) as usize;
+        let character = position["character"].as_u64().unwrap_or(0) as usize;
+        let text = self.line(line);
+        let mut units = 0;
+        for (byte, ch) in text.char_indices() {
+            if units >= character {
+                return (line, byte);
+            }
+            units += ch.len_utf16();
+        }
+        (line, text.len())
+    }
+
+    fn range(&self, line: usize, (from, to): (usize, usize)) -> Value {
+        let text = self.line(line);
+        let column = |byte: usize| text.get(..byte).map_or(0, |prefix| prefix.encode_utf16().count());
+        json!({
+            "start": { "line": line, "character": column(from) },
+            "end": { "line": line, "character": column(to) },
+        })
+    }
+
+    fn line_range(&self, (start, end): (usize, usize)) -> Value {
+        json!({
+            "start": { "line": start, "character": 0 },
+            "end": { "line": end, "character": self.line(end).encode_utf16().count() },
+        })
+    }
+}
+
+#[derive(Default)]
+pub struct LanguageServer {
+    documents: HashMap<String, OpenDocument>,
+}
+
+/// Serve LSP requests on stdin and stdout until the client exits
+pub fn run_stdio() -> Result<()> {
+    let stdin = io::stdin();
+    let mut reader = stdin.lock();
+    let mut writer = io::stdout().lock();
+    LanguageServer::default().serve(&mut reader, &mut writer)
+}
+
+impl LanguageServer {
+    pub fn serve(&mut self, reader: &mut impl BufRead, writer: &mut impl Write) -> Result<()> {
+        while let Some(body) = read_message(reader)? {
+            let message: Value = match serde_json::from_slice(&body) {
+                Ok(message) => message,
+                Err(e) => {
+                    let error = json!({ "code": -32700, "message": format!("Parse error: {}", e) });
+                    write_message(writer, &json!({ "jsonrpc": "2.0", "id": null, "error": error }))?;
+                    continue;
+                },
+            };
+            for reply in self.handle(&message) {
+                write_message(writer, &reply)?;
+            }
+            if message["method"] == "exit" {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// Handle one message, returning responses and notifications to send
+    pub fn handle(&mut self, message: &Value) -> Vec<Value> {
+        let params = &message["params"];
+        let id = message.get("id").cloned();
+        let respond = |result: Value| vec![json!({ "jsonrpc": "2.0", "id": id, "result": result })];
+
+        match message["method"].as_str().unwrap_or_default() {
+            "initialize" => respond(json!({
+                "capabilities": {
+                    "textDocumentSync": 1,
+                    "definitionProvider": true,
+                    "referencesProvider": true,
+                    "hoverProvider": true,
+                    "documentSymbolProvider": true,
+                    "codeActionProvider": true,
+                    "completionProvider": { "triggerCharacters": [":", " "] },
+                },
+                "serverInfo": { "name": "fdml", "version": env!("CARGO_PKG_VERSION") },
+            })),
+            "shutdown" => respond(Value::Null),
+            "textDocument/didOpen" => {
+                let uri = params["textDocument"]["uri"].as_str().unwrap_or_default();
+                let text = params["textDocument"]["text"].as_str().unwrap_or_default();
+                self.open(uri, text.to_string())
+            },
+            "textDocument/didChange" => {
+                let uri = params["textDocument"]["uri"].as_str().unwrap_or_default();
+                let changes = params["contentChanges"].as_array().cloned().unwrap_or_default();
+                match changes.last().and_then(|change| change["text"].as_str()) {
+                    Some(text) => self.open(uri, text.to_string()),
+                    None => Vec::new(),
+                }
+            },
+            "textDocument/didClose" => {
+                let uri = params["textDocument"]["uri"].as_str().unwrap_or_default();
+                self.documents.remove(uri);
+                vec![publish_diagnostics(uri, Vec::new())]
+            },
+            "textDocument/codeAction" => {
+                let uri = params["textDocument"]["uri"].as_str().unwrap_or_default();
+                respond(code_actions(uri, &params["context"]["diagnostics"]))
+            },
+            method @ ("textDocument/definition"
+            | "textDocument/references"
+            | "textDocument/hover"
+            | "textDocument/completion"
+            | "textDocument/documentSymbol") => {
+                let uri = params["textDocument"]["uri"].as_str().unwrap_or_default();
+                let Some(document) = self.documents.get(uri) else {
+                    return respond(Value::Null);
+                };
+                respond(match method {
+                    "textDocument/definition" => definition(uri, document, &params["position"]),
+                    "textDocument/references" => references(uri, document, params),
+                    "textDocument/hover" => hover(document, &params["position"]),
+                    "textDocument/completion" => completion(document, &params["position"]),
+                    _ => document_symbols(document),
+                })
+            },
+            _ if id.is_some() => vec![json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "error": { "code": -32601, "message": format!("Method not found: {}", message["method"]) },
+            })],
+            // Other notifications (initialized, exit, $/...) need no reply
+            _ => Vec::new(),
+        }
+    }
+
+    fn open(&mut self, uri: &str, text: String) -> Vec<Value> {
+        let previous = self.documents.remove(uri).and_then(|document| document.parsed);
+        let document = OpenDocument::new(text, previous);
+        let notification = publish_diagnostics(uri, diagnostics(&document));
+        self.documents.insert(uri.to_string(), document);
+        vec![notification]
+    }
+}
+
+fn publish_diagnostics(uri: &str, diagnostics: Vec<Value>) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/publishDiagnostics",
+        "params": { "uri": uri, "diagnostics": diagnostics },
+    })
+}
+
+/// Parse errors, or validation findings placed on the element they mention
+fn diagnostics(document: &OpenDocument) -> Vec<Value> {
+    let diagnostic = |range: Value, severity: u8, message: String| {
+        json!({ "range": range, "severity": severity, "source": "fdml", "message": message })
+    };
+
+    let parsed = match serde_yaml::from_str::<FdmlDocument>(&document.text) {
+        Ok(parsed) => parsed,
+        Err(error) => {
+            let (line, column) = error.location().map_or((0, 0), |l| (l.line().saturating_sub(1), l.column().saturating_sub(1)));
+            let range = document.range(line, (column, document.line(line).len().max(column)));
+            return vec![diagnostic(range, 1, format!("YAML parsing failed: {}", error))];
+        },
+    };
+
+    let findings = Validator::new().validate(&parsed).unwrap_or_default();
+    findings
+        .into_iter()
+        .map(|message| {
+            let located = document.index.locate(&message);
+            let range = located.map_or_else(|| document.range(0, (0, 0)), |(line, columns)| document.range(line, columns));
+            let suggestion = suggestion_at(document, &message, located);
+            let mut diagnostic = diagnostic(range, 2, message);
+            if let Some(suggestion) = suggestion {
+                diagnostic["data"] = json!({ "suggestion": suggestion });
+            }
+            diagnostic
+        })
+        .collect()
+}
+
+/// The message's suggestion, when the diagnostic covers exactly the value it replaces
+fn suggestion_at(document: &OpenDocument, message: &str, located: Option<(usize, (usize, usize))>) -> Option<String> {
+    let (text, suggestion) = split_suggestion(message);
+    let unknown = text.rsplit('\'').nth(1)?;
+    let (line, (from, to)) = located?;
+    if document.line(line).get(from..to) != Some(unknown) {
+        return None;
+    }
+    suggestion.map(str::to_string)
+}
+
+/// Quick fixes applying the suggestions attached to diagnostics
+fn code_actions(uri: &str, diagnostics: &Value) -> Value {
+    let actions: Vec<Value> = diagnostics
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|diagnostic| {
+            let suggestion = diagnostic["data"]["suggestion"].as_str()?;
+            Some(json!({
+                "title": format!("Replace with '{}'", suggestion),
+                "kind": "quickfix",
+                "diagnostics": [diagnostic],
+                "isPreferred": true,
+                "edit": { "changes": { uri: [{ "range": diagnostic["range"], "newText": suggestion }] } },
+            }))
+        })
+        .collect();
+    Value::Array(actions)
+}
+
+fn location(uri: &str, document: &OpenDocument, line: usize, columns: (usize, usize)) -> Value {
+    json!({ "uri": uri, "range": document.range(line, columns) })
+}
+
+fn definition(uri: &str, document: &OpenDocument, position: &Value) -> Value {
+    let (line, column) = document.offset(position);
+    document
+        .index
+        .id_at(line, column)
+        .and_then(|id| document.index.definition(id))
+        .map_or(Value::Null, |symbol| location(uri, document, symbol.line, symbol.columns))
+}
+
+fn references(uri: &str, document: &OpenDocument, params: &Value) -> Value {
+    let (line, column) = document.offset(&params["position"]);
+    let Some(id) = document.index.id_at(line, column) else {
+        return json!([]);
+    };
+
+    let mut locations = Vec::new();
+    if params["context"]["includeDeclaration"].as_bool().unwrap_or(false) {
+        if let Some(symbol) = document.index.definition(id) {
+            locations.push(location(uri, document, symbol.line, symbol.columns));
+        }
+    }
+    locations.extend(
+        document
+            .index
+            .references_to(id)
+            .map(|reference| location(uri, document, reference.line, reference.columns)),
+    );
+    Value::Array(locations)
+}
+
+fn hover(document: &OpenDocument, position: &Value) -> Value {
+    let (line, column) = document.offset(position);
+    let Some(symbol) = document.index.id_at(line, column).and_then(|id| document.index.definition(id)) else {
+        return Value::Null;
+    };
+
+    let mut text = format!("**{}** `{}`", symbol.kind.replace('_', " "), symbol.id);
+    if let Some(parsed) = &document.parsed {
+        text.push_str(&describe(parsed, symbol));
+    }
+    json!({
+        "contents": { "kind": "markdown", "value": text },
+        "range": document.range(symbol.line, symbol.columns),
+    })
+}
+
+/// Name, description and fields of an element, as markdown
+fn describe(parsed: &FdmlDocument, symbol: &Symbol) -> String {
+    let id = symbol.id.as_str();
+    let (name, description) = match symbol.kind {
+        "entity" => match parsed.entities.iter().find(|e| e.id == id) {
+            Some(entity) => {
+                let mut text = details(entity.name.as_deref(), entity.description.as_deref());
+                if !entity.fields.is_empty() {
+                    text.push_str("\n\n**Fields**\n");
+                    for field in &entity.fields {
+                        let required = if field.required == Some(true) { " (required)" } else { "" };
+                        text.push_str(&format!("\n- `{}`: {}{}", field.name, field.field_type, required));
+                    }
+                }
+                return text;
+            },
+            None => (None, None),
+        },
+        "action" => parsed.actions.iter().find(|a| a.id == id)
+            .map_or((None, None), |a| (a.name.as_deref(), a.description.as_deref())),
+        "feature" => parsed.features.iter().find(|f| f.id == id)
+            .map_or((None, None), |f| (Some(f.title.as_str()), f.description.as_deref())),
+        "scenario" => parsed.features.iter().flat_map(|f| &f.scenarios).find(|s| s.id == id)
+            .map_or((None, None), |s| (Some(s.title.as_str()), s.description.as_deref())),
+        "flow" => parsed.flows.iter().find(|f| f.id == id)
+            .map_or((None, None), |f| (Some(f.name.as_str()), f.description.as_deref())),
+        "constraint" => parsed.constraints.iter().find(|c| c.id == id)
+            .map_or((None, None), |c| (Some(c.name.as_str()), c.description.as_deref())),
+        "generation_rule" => parsed.generation_rules.iter().find(|r| r.id == id)
+            .map_or((None, None), |r| (Some(r.name.as_str()), r.description.as_deref())),
+        _ => (None, None),
+    };
+    details(name, description)
+}
+
+fn details(name: Option<&str>, description: Option<&str>) -> String {
+    let mut text = String::new();
+    if let Some(name) = name.filter(|name| !name.is_empty()) {
+        text.push_str(&format!(" — {}", name));
+    }
+    if let Some(description) = description {
+        text.push_str(&format!("\n\n{}", description.trim()));
+    }
+    text
+}
+
+fn completion(document: &OpenDocument, position: &Value) -> Value {
+    let (line, column) = document.offset(position);
+    let before = &document.line(line)[..column];
+    let key = before.trim_start().trim_start_matches("- ").split(':').next().unwrap_or_default().trim();
+
+    if key == "type" && before.contains(':') {
+        let items: Vec<Value> = FIELD_TYPES
+            .iter()
+            .map(|field_type| json!({ "label": field_type, "kind": 25, "detail": "field type" }))
+            .collect();
+        return Value::Array(items);
+    }
+
+    let expects = document.index.expected_kind(line).or(match key {
+        "entity" => Some("entity"),
+        "action" => Some("action"),
+        _ => None,
+    });
+    let items: Vec<Value> = document
+        .index
+        .symbols
+        .iter()
+        .filter(|symbol| symbol.is_global() && expects.is_none_or(|kind| symbol.kind == kind))
+        .map(|symbol| json!({ "label": symbol.id, "kind": completion_kind(symbol.kind), "detail": symbol.kind }))
+        .collect();
+    Value::Array(items)
+}
+
+fn completion_kind(kind: &str) -> u8 {
+    match kind {
+        "entity" => 22,
+        "action" => 3,
+        "feature" => 7,
+        "flow" => 9,
+        _ => 18,
+    }
+}
+
+fn symbol_kind(kind: &str) -> u8 {
+    match kind {
+        "entity" => 23,
+        "field" => 8,
+        "action" => 12,
+        "feature" => 5,
+        "scenario" => 6,
+        "flow" => 11,
+        "step" => 13,
+        "constraint" => 14,
+        "traceability" => 20,
+        _ => 24,
+    }
+}
+
+/// Sections with their elements, and elements with their scenarios, steps and fields
+fn document_symbols(document: &OpenDocument) -> Value {
+    let symbol = |symbol: &Symbol, children: Vec<Value>| {
+        json!({
+            "name": symbol.id,
+            "detail": symbol.kind,
+            "kind": symbol_kind(symbol.kind),
+            "range": document.line_range(symbol.lines),
+            "selectionRange": document.range(symbol.line, symbol.columns),
+            "children": children,
+        })
+    };
+
+    let sections: Vec<Value> = document
+        .index
+        .sections
+        .iter()
+        .map(|section| {
+            let in_section = |s: &&Symbol| matches!(s.path.first(), Some(Segment::Key(name)) if *name == section.name);
+            let elements: Vec<Value> = document
+                .index
+                .symbols
+                .iter()
+                .filter(|s| in_section(s) && s.path.len() == 2)
+                .map(|element| {
+                    let children = document
+                        .index
+                        .symbols
+                        .iter()
+                        .filter(|s| s.path.len() > 2 && s.path.starts_with(&element.path))
+                        .map(|child| symbol(child, Vec::new()))
+                        .collect();
+                    symbol(element, children)
+                })
+                .collect();
+            let section_line = section.lines.0;
+            json!({
+                "name": section.name,
+                "kind": 3,
+                "range": document.line_range(section.lines),
+                "selectionRange": document.range(section_line, (0, section.name.len())),
+                "children": elements,
+            })
+        })
+        .collect();
+    Value::Array(sections)
+}
+
+/// Read the body of one `Content-Length` framed message, or `None` at end of input
+pub fn read_message(reader: &mut impl BufRead) -> Result<Option<Vec<u8>>> {
+    let mut length = None;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 {
+            return Ok(None);
+        }
+        let header = header.trim_end();
+        if header.is_empty() {
+            if length.is_some() {
+                break;
+            }
+            continue;
+        }
+        if let Some(value) = header.strip_prefix("Content-Length:") {
+            length = Some(value.trim().parse::<usize>().map_err(|_| {
+                FdmlError::simple_parser_error(format!("Invalid Content-Length header: {}", header))
+            })?);
+        }
+    }
+
+    let mut body = vec![0; length.unwrap_or_default()];
+    reader.read_exact(&mut body)?;
+    Ok(Some(body))
+}
+
+pub fn write_message(writer: &mut impl Write, message: &Value) -> Result<()> {
+    let body = serde_json::to_string(message)?;
+    write!(writer, "Content-Length: {}\r\n\r\n{}", body.len(), body)?;
+    writer.flush()?;
+    Ok(())
+}
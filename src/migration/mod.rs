@@ -1,6 +1,7 @@
 pub mod runner;
+pub mod squash;
 
 #[cfg(test)]
 mod tests;
 
-pub use runner::*;
\ No newline at end of file
+pub use runner::*;
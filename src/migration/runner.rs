@@ -1,20 +1,88 @@
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
 use std::path::{Path, PathBuf};
 use crate::error::Result;
-use crate::parser::{parse_fdml_yaml};
-use crate::parser::ast::{FdmlDocument, Feature, Scenario, Field, Value, Entity, Action, Constraint};
+use crate::parser::{parse_fdml_yaml, yaml_edit};
+use crate::principles;
+use crate::parser::ast::{FdmlDocument, Feature, Scenario, Field, Value, Entity, Action, Constraint, Traceability};
 use std::collections::HashSet;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Migration {
     pub id: String,
     pub title: Option<String>,
+    pub author: Option<String>,
+    /// Creation date (ISO 8601)
+    pub date: Option<String>,
     pub description: Option<String>,
+    #[serde(deserialize_with = "deserialize_operations")]
     pub up: Vec<MigrationOperation>,
+    #[serde(deserialize_with = "deserialize_operations")]
     pub down: Vec<MigrationOperation>,
     pub dependencies: Option<Vec<String>>,
+    pub design_impact: Option<DesignImpact>,
+    /// Set on migrations created by `migrate squash`
+    pub squash: Option<SquashInfo>,
+}
+
+/// Migrations folded into a squashed migration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SquashInfo {
+    /// Ids of the migrations this one replaces, in apply order
+    pub replaces: Vec<String>,
+    /// Later migrations whose dependencies were rewritten by the squash
+    #[serde(default)]
+    pub rewritten: Vec<RewrittenMigration>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RewrittenMigration {
+    pub id: String,
+    pub previous_checksum: String,
+    pub checksum: String,
+}
+
+/// Design principle changes recorded by a migration (FDML 1.3.3, section 16.4)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DesignImpact {
+    #[serde(default)]
+    pub principles_added: Vec<PrincipleAddition>,
+    #[serde(default)]
+    pub principles_removed: Vec<PrincipleRemoval>,
+    #[serde(default)]
+    pub strategy_changes: Vec<StrategyChange>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrincipleAddition {
+    pub element_id: String,
+    pub principle: String,
+    pub rationale: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrincipleRemoval {
+    pub element_id: String,
+    pub principle: String,
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StrategyChange {
+    pub element_id: String,
+    pub principle: String,
+    pub old_strategy: Option<String>,
+    pub new_strategy: Option<String>,
+}
+
+impl DesignImpact {
+    pub fn is_empty(&self) -> bool {
+        self.principles_added.is_empty()
+            && self.principles_removed.is_empty()
+            && self.strategy_changes.is_empty()
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -26,6 +94,9 @@ pub enum MigrationOperation {
         title: String,
         description: Option<String>,
         scenarios: Option<Vec<String>>,
+        entity: Option<String>,
+        operation: Option<String>,
+        roles: Option<Vec<String>>,
     },
     #[serde(rename = "remove_feature")]
     RemoveFeature {
@@ -93,6 +164,25 @@ pub enum MigrationOperation {
         target_type: String, // "entity", "action", "field"
         validation_rules: Vec<String>,
     },
+    #[serde(rename = "add_traceability_link")]
+    AddTraceabilityLink {
+        link_id: Option<String>,
+        from: String,
+        to: String,
+        relation: String,
+        description: Option<String>,
+        confidence: Option<f64>,
+        verified: Option<bool>,
+    },
+    #[serde(rename = "remove_traceability_link")]
+    RemoveTraceabilityLink {
+        link_id: String,
+    },
+    #[serde(rename = "update_traceability_link")]
+    UpdateTraceabilityLink {
+        link_id: String,
+        changes: TraceabilityChanges,
+    },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -111,18 +201,132 @@ pub struct ActionChanges {
     pub output_changes: Option<HashMap<String, String>>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TraceabilityChanges {
+    pub relation: Option<String>,
+    pub confidence: Option<f64>,
+    pub verified: Option<bool>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MigrationState {
     pub applied_migrations: Vec<String>,
+    #[serde(default)]
+    pub applied_records: Vec<AppliedMigration>,
     pub last_migration: Option<String>,
     pub created_at: String,
     pub updated_at: String,
 }
 
+/// Backup of the target file taken before a migration run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupRecord {
+    /// Backup name, the file name without extension
+    pub name: String,
+    pub created_at: String,
+    /// "apply", "rollback" or "restore"; None for backups taken before runs were recorded
+    pub operation: Option<String>,
+    /// Migrations the run applied or rolled back
+    #[serde(default)]
+    pub migrations: Vec<String>,
+    pub target_file: Option<String>,
+    /// Migration state at the time of the backup
+    pub state: Option<MigrationState>,
+}
+
+/// Audit record kept in the state file for each applied migration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppliedMigration {
+    pub id: String,
+    /// SHA-256 of the migration file content at the time it was applied
+    pub checksum: String,
+    pub applied_at: String,
+    /// Who applied the migration
+    pub author: Option<String>,
+    pub target_file: Option<String>,
+}
+
+impl MigrationState {
+    pub fn applied_record(&self, migration_id: &str) -> Option<&AppliedMigration> {
+        self.applied_records.iter().find(|r| r.id == migration_id)
+    }
+}
+
+/// A migration together with the file it was loaded from
+#[derive(Debug, Clone)]
+pub struct MigrationFile {
+    pub path: PathBuf,
+    pub checksum: String,
+    pub migration: Migration,
+}
+
+/// Mismatch between the recorded migration history and the migration files
+#[derive(Debug, Clone, PartialEq)]
+pub enum IntegrityIssue {
+    /// An applied migration file changed after it was applied
+    Modified { id: String, expected: String, actual: String },
+    /// An applied migration file no longer exists
+    Missing { id: String },
+    /// An applied migration depends on one that was applied after it, or not at all
+    Reordered { id: String, dependency: String },
+}
+
+impl std::fmt::Display for IntegrityIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IntegrityIssue::Modified { id, .. } => {
+                write!(f, "Migration '{}' was modified after it was applied (checksum mismatch)", id)
+            }
+            IntegrityIssue::Missing { id } => {
+                write!(f, "Applied migration '{}' is missing from the migration directory", id)
+            }
+            IntegrityIssue::Reordered { id, dependency } => {
+                write!(f, "Applied migration '{}' depends on '{}', which was not applied before it", id, dependency)
+            }
+        }
+    }
+}
+
+/// Difference between the spec produced by replaying migrations and the committed spec
+#[derive(Debug, Clone, PartialEq)]
+pub enum SpecDrift {
+    /// The element exists in the spec but no migration creates it
+    Unexpected { kind: &'static str, id: String },
+    /// Migrations create the element but the spec does not contain it
+    Missing { kind: &'static str, id: String },
+    /// The element in the spec differs from the migrated one
+    Modified { kind: &'static str, id: String },
+}
+
+impl std::fmt::Display for SpecDrift {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SpecDrift::Unexpected { kind, id } => {
+                write!(f, "{} '{}' is in the spec but no migration creates it", kind, id)
+            }
+            SpecDrift::Missing { kind, id } => {
+                write!(f, "{} '{}' is created by migrations but missing from the spec", kind, id)
+            }
+            SpecDrift::Modified { kind, id } => {
+                write!(f, "{} '{}' was edited without a migration", kind, id)
+            }
+        }
+    }
+}
+
+/// Result of replaying all migrations and comparing with the target file
+#[derive(Debug)]
+pub struct SpecVerification {
+    pub replayed: Vec<String>,
+    pub pending: Vec<String>,
+    pub drift: Vec<SpecDrift>,
+}
+
 pub struct MigrationRunner {
     migration_dir: PathBuf,
     state_file: PathBuf,
     backup_dir: PathBuf,
+    backup_index_file: PathBuf,
     target_fdml_file: Option<PathBuf>,
 }
 
@@ -131,11 +335,13 @@ impl MigrationRunner {
         let migration_dir = migration_dir.as_ref().to_path_buf();
         let state_file = migration_dir.join(".migration_state.json");
         let backup_dir = migration_dir.join(".backups");
+        let backup_index_file = migration_dir.join(".backup_index.json");
         
         Self {
             migration_dir,
             state_file,
             backup_dir,
+            backup_index_file,
             target_fdml_file: None,
         }
     }
@@ -145,15 +351,16 @@ impl MigrationRunner {
         self
     }
 
-    /// Create a backup of the current FDML file before applying migrations
-    fn create_backup(&self) -> Result<Option<PathBuf>> {
+    /// Create a backup of the current FDML file before a migration run, recording
+    /// the run and the migration state in the backup index
+    fn create_backup(&self, operation: &str, migrations: &[String]) -> Result<Option<PathBuf>> {
         if let Some(target_file) = &self.target_fdml_file {
             if target_file.exists() {
                 fs::create_dir_all(&self.backup_dir)?;
                 
-                let timestamp = chrono::Utc::now().format("%Y%m%d_%H%M%S");
-                let backup_filename = format!("backup_{}.fdml", timestamp);
-                let backup_path = self.backup_dir.join(backup_filename);
+                let now = chrono::Utc::now();
+                let name = format!("backup_{}", now.format("%Y%m%d_%H%M%S_%3f"));
+                let backup_path = self.backup_dir.join(format!("{}.fdml", name));
                 
                 // Use a more explicit approach to file copying that ensures completion
                 {
@@ -161,6 +368,17 @@ impl MigrationRunner {
                     fs::write(&backup_path, content)?;
                 }
                 
+                let mut index = self.load_backup_index()?;
+                index.push(BackupRecord {
+                    name,
+                    created_at: now.to_rfc3339(),
+                    operation: Some(operation.to_string()),
+                    migrations: migrations.to_vec(),
+                    target_file: Some(target_file.display().to_string()),
+                    state: Some(self.load_state().unwrap_or_default()),
+                });
+                self.save_backup_index(&index)?;
+                
                 println!("  📁 Created backup: {}", backup_path.display());
                 return Ok(Some(backup_path));
             }
@@ -168,6 +386,190 @@ impl MigrationRunner {
         Ok(None)
     }
 
+    fn load_backup_index(&self) -> Result<Vec<BackupRecord>> {
+        if !self.backup_index_file.exists() {
+            return Ok(Vec::new());
+        }
+        
+        let content = fs::read_to_string(&self.backup_index_file)?;
+        serde_json::from_str(&content).map_err(|e| crate::error::FdmlError::simple_parser_error(format!(
+            "Failed to parse backup index: {}", e
+        )))
+    }
+
+    fn save_backup_index(&self, index: &[BackupRecord]) -> Result<()> {
+        fs::write(&self.backup_index_file, serde_json::to_string_pretty(index)?)?;
+        Ok(())
+    }
+
+    /// All backups, oldest first. Backups missing from the index are listed without run details.
+    pub fn list_backups(&self) -> Result<Vec<BackupRecord>> {
+        let mut backups: Vec<_> = self.load_backup_index()?
+            .into_iter()
+            .filter(|record| self.backup_dir.join(format!("{}.fdml", record.name)).exists())
+            .collect();
+        
+        if self.backup_dir.exists() {
+            for entry in fs::read_dir(&self.backup_dir)? {
+                let path = entry?.path();
+                if path.extension().and_then(|s| s.to_str()) != Some("fdml") {
+                    continue;
+                }
+                let Some(name) = path.file_stem().and_then(|s| s.to_str()) else {
+                    continue;
+                };
+                if backups.iter().any(|record| record.name == name) {
+                    continue;
+                }
+                let created_at = fs::metadata(&path)?
+                    .modified()
+                    .map(|time| chrono::DateTime::<chrono::Utc>::from(time).to_rfc3339())
+                    .unwrap_or_default();
+                backups.push(BackupRecord {
+                    name: name.to_string(),
+                    created_at,
+                    operation: None,
+                    migrations: Vec::new(),
+                    target_file: None,
+                    state: None,
+                });
+            }
+        }
+        
+        // Names embed the timestamp, so they sort chronologically
+        backups.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(backups)
+    }
+
+    /// Restore the target file from a backup and roll the migration state back to
+    /// what it was when the backup was taken
+    pub fn restore_backup(&self, name: &str) -> Result<BackupRecord> {
+        let name = name.trim_end_matches(".fdml");
+        let record = self.list_backups()?
+            .into_iter()
+            .find(|record| record.name == name)
+            .ok_or_else(|| crate::error::FdmlError::migration_error(format!("Backup '{}' not found", name)))?;
+        
+        let target_file = self.target_fdml_file.clone()
+            .or_else(|| record.target_file.as_ref().map(PathBuf::from))
+            .ok_or_else(|| crate::error::FdmlError::migration_error(format!(
+                "Backup '{}' does not record its target file; specify one with --target", name
+            )))?;
+        let runner = MigrationRunner::new(&self.migration_dir).with_target_file(&target_file);
+        
+        // Keep the current spec so the restore itself can be undone
+        runner.create_backup("restore", &[])?;
+        
+        let content = fs::read_to_string(self.backup_dir.join(format!("{}.fdml", name)))?;
+        fs::write(&target_file, content)?;
+        println!("  💾 Restored {} from {}", target_file.display(), name);
+        
+        if let Some(state) = &record.state {
+            fs::write(&self.state_file, serde_json::to_string_pretty(state)?)?;
+            println!("  📋 Restored migration state ({} applied)", state.applied_migrations.len());
+        }
+        
+        Ok(record)
+    }
+
+    /// Delete all but the newest `keep` backups, returning the names of the deleted ones
+    pub fn prune_backups(&self, keep: usize) -> Result<Vec<String>> {
+        let backups = self.list_backups()?;
+        let remove_count = backups.len().saturating_sub(keep);
+        let removed: Vec<String> = backups.into_iter().take(remove_count).map(|record| record.name).collect();
+        
+        for name in &removed {
+            fs::remove_file(self.backup_dir.join(format!("{}.fdml", name)))?;
+        }
+        if !removed.is_empty() && self.backup_index_file.exists() {
+            let index: Vec<_> = self.load_backup_index()?
+                .into_iter()
+                .filter(|record| !removed.contains(&record.name))
+                .collect();
+            self.save_backup_index(&index)?;
+        }
+        
+        Ok(removed)
+    }
+
+    /// Refuse to roll back a migration that a migration staying applied depends on
+    fn verify_no_applied_dependents(
+        &self,
+        to_rollback: &[String],
+        migrations: &HashMap<String, Migration>,
+        state: &MigrationState
+    ) -> Result<()> {
+        let rolling_back: HashSet<_> = to_rollback.iter().collect();
+        
+        for remaining_id in state.applied_migrations.iter().filter(|id| !rolling_back.contains(id)) {
+            let Some(remaining) = migrations.get(remaining_id) else {
+                continue;
+            };
+            for dependency in remaining.dependencies.iter().flatten() {
+                if rolling_back.contains(dependency) {
+                    return Err(crate::error::FdmlError::migration_error(format!(
+                        "Cannot roll back '{}': applied migration '{}' depends on it",
+                        dependency, remaining_id
+                    )));
+                }
+            }
+        }
+        
+        Ok(())
+    }
+
+    /// Minimal ordered set of pending migrations needed to apply the target
+    fn plan_upgrade(
+        &self,
+        target: &str,
+        migrations: &HashMap<String, Migration>,
+        state: &MigrationState
+    ) -> Result<Vec<String>> {
+        if !migrations.contains_key(target) {
+            return Err(crate::error::FdmlError::migration_error(format!(
+                "Target migration '{}' not found", target
+            )));
+        }
+        
+        let mut needed = Vec::new();
+        let mut stack = vec![target.to_string()];
+        while let Some(migration_id) = stack.pop() {
+            if needed.contains(&migration_id) || state.applied_migrations.contains(&migration_id) {
+                continue;
+            }
+            let migration = migrations.get(&migration_id).ok_or_else(|| {
+                crate::error::FdmlError::migration_error(format!(
+                    "Migration '{}' required by '{}' not found", migration_id, target
+                ))
+            })?;
+            stack.extend(migration.dependencies.iter().flatten().cloned());
+            needed.push(migration_id);
+        }
+        
+        needed.sort();
+        self.resolve_migration_dependencies(&needed, migrations)
+    }
+
+    /// Applied migrations to roll back so that the target becomes the last applied one,
+    /// with dependents ordered before their dependencies
+    fn plan_downgrade(
+        &self,
+        target: &str,
+        migrations: &HashMap<String, Migration>,
+        state: &MigrationState
+    ) -> Result<Vec<String>> {
+        let position = state.applied_migrations.iter().position(|id| id == target).ok_or_else(|| {
+            crate::error::FdmlError::migration_error(format!(
+                "Target migration '{}' is not applied", target
+            ))
+        })?;
+        
+        let after_target = &state.applied_migrations[position + 1..];
+        let mut ordered = self.resolve_migration_dependencies(after_target, migrations)?;
+        ordered.reverse();
+        Ok(ordered)
+    }
+
     /// Verify that a rollback can be safely performed
     fn verify_rollback_safety(&self, migrations: &[String]) -> Result<()> {
         let migration_map = self.load_migrations()?;
@@ -198,16 +600,34 @@ impl MigrationRunner {
     }
 
     pub fn apply_migrations(&self, dry_run: bool) -> Result<Vec<String>> {
-        let migrations = self.load_migrations()?;
+        self.apply_migrations_to(None, dry_run)
+    }
+
+    /// Apply pending migrations. With a target, only the target and the pending
+    /// migrations it depends on are applied.
+    pub fn apply_migrations_to(&self, target: Option<&str>, dry_run: bool) -> Result<Vec<String>> {
+        self.ensure_integrity()?;
+        
+        let migration_files = self.load_migration_files()?;
+        let migrations: HashMap<String, Migration> = migration_files
+            .iter()
+            .map(|(id, file)| (id.clone(), file.migration.clone()))
+            .collect();
         let state = self.load_state().unwrap_or_default();
         
-        let pending_migrations = self.get_pending_migrations(&migrations, &state)?;
+        let pending_migrations = match target {
+            Some(target_id) => self.plan_upgrade(target_id, &migrations, &state)?,
+            None => self.get_pending_migrations(&migrations, &state)?,
+        };
         
         if pending_migrations.is_empty() {
             println!("No pending migrations to apply");
             return Ok(Vec::new());
         }
 
+        if let Some(target_id) = target {
+            println!("Migration plan to reach '{}':", target_id);
+        }
         println!("Found {} pending migrations to apply:", pending_migrations.len());
         for migration_id in &pending_migrations {
             if let Some(migration) = migrations.get(migration_id) {
@@ -216,6 +636,12 @@ impl MigrationRunner {
             }
         }
 
+        for migration in pending_migrations.iter().filter_map(|id| migrations.get(id)) {
+            for operation in &migration.up {
+                self.validate_new_operation(operation)?;
+            }
+        }
+
         if dry_run {
             println!("\n🔍 DRY RUN MODE - No changes will be applied");
             for migration_id in &pending_migrations {
@@ -232,7 +658,7 @@ impl MigrationRunner {
         }
 
         // Create backup before applying migrations
-        let backup_path = self.create_backup()?;
+        let backup_path = self.create_backup("apply", &pending_migrations)?;
         
         let mut applied = Vec::new();
         let mut fdml_document = self.load_target_document()?;
@@ -250,7 +676,7 @@ impl MigrationRunner {
 
         if !applied.is_empty() {
             self.save_target_document(&fdml_document)?;
-            self.update_state(&applied)?;
+            self.update_state(&applied, &migration_files)?;
             
             println!("\n✅ Successfully applied {} migrations", applied.len());
             if let Some(backup_path) = backup_path {
@@ -272,6 +698,29 @@ impl MigrationRunner {
             .cloned()
             .collect();
 
+        self.execute_rollback(to_rollback, &migrations, &state, dry_run)
+    }
+
+    /// Roll back every migration applied after the target, leaving the target applied
+    pub fn rollback_migrations_to(&self, target: &str, dry_run: bool) -> Result<Vec<String>> {
+        let migrations = self.load_migrations()?;
+        let state = self.load_state().unwrap_or_default();
+        
+        let to_rollback = self.plan_downgrade(target, &migrations, &state)?;
+        if !to_rollback.is_empty() {
+            println!("Migration plan to return to '{}':", target);
+        }
+
+        self.execute_rollback(to_rollback, &migrations, &state, dry_run)
+    }
+
+    fn execute_rollback(
+        &self,
+        to_rollback: Vec<String>,
+        migrations: &HashMap<String, Migration>,
+        state: &MigrationState,
+        dry_run: bool
+    ) -> Result<Vec<String>> {
         if to_rollback.is_empty() {
             println!("No migrations to rollback");
             return Ok(Vec::new());
@@ -286,6 +735,7 @@ impl MigrationRunner {
         }
 
         // Verify rollback safety
+        self.verify_no_applied_dependents(&to_rollback, migrations, state)?;
         self.verify_rollback_safety(&to_rollback)?;
 
         if dry_run {
@@ -304,7 +754,7 @@ impl MigrationRunner {
         }
 
         // Create backup before rollback
-        let backup_path = self.create_backup()?;
+        let backup_path = self.create_backup("rollback", &to_rollback)?;
 
         let mut rolled_back = Vec::new();
         let mut fdml_document = self.load_target_document()?;
@@ -333,23 +783,102 @@ impl MigrationRunner {
         Ok(rolled_back)
     }
 
+    /// Replay every migration in memory on top of the baseline document (an empty
+    /// document by default) and compare the result with the target file
+    pub fn verify_migrations(&self, baseline: Option<&Path>) -> Result<SpecVerification> {
+        let target_file = self.target_fdml_file.as_ref().ok_or_else(|| {
+            crate::error::FdmlError::migration_error("A target FDML file is required to verify migrations".to_string())
+        })?;
+        let actual = parse_fdml_yaml(&fs::read_to_string(target_file)?)?;
+
+        let mut document = match baseline {
+            Some(path) => parse_fdml_yaml(&fs::read_to_string(path)?)?,
+            None => FdmlDocument::default(),
+        };
+
+        let mut replayed = Vec::new();
+        let mut pending = Vec::new();
+        for (migration, applied) in self.migration_history()? {
+            self.apply_migration(&migration, &mut document).map_err(|e| {
+                crate::error::FdmlError::migration_error(format!(
+                    "Failed to replay migration '{}': {}", migration.id, e
+                ))
+            })?;
+            if !applied {
+                pending.push(migration.id.clone());
+            }
+            replayed.push(migration.id);
+        }
+
+        // Round-trip through YAML so the comparison sees what a saved file would contain
+        let expected = parse_fdml_yaml(&serde_yaml::to_string(&document)?)?;
+
+        let mut drift = Vec::new();
+        if expected.system != actual.system {
+            let kind = "system";
+            match (&expected.system, &actual.system) {
+                (Some(system), None) => drift.push(SpecDrift::Missing { kind, id: system.id.clone() }),
+                (None, Some(system)) => drift.push(SpecDrift::Unexpected { kind, id: system.id.clone() }),
+                (_, Some(system)) => drift.push(SpecDrift::Modified { kind, id: system.id.clone() }),
+                (None, None) => {}
+            }
+        }
+        compare_elements("entity", &expected.entities, &actual.entities, |e| e.id.clone(), &mut drift);
+        compare_elements("action", &expected.actions, &actual.actions, |a| a.id.clone(), &mut drift);
+        compare_elements("feature", &expected.features, &actual.features, |f| f.id.clone(), &mut drift);
+        compare_elements("flow", &expected.flows, &actual.flows, |f| f.id.clone(), &mut drift);
+        compare_elements("constraint", &expected.constraints, &actual.constraints, |c| c.id.clone(), &mut drift);
+        compare_elements("traceability link", &expected.traceability, &actual.traceability, |t| t.link_id(), &mut drift);
+        compare_elements("generation rule", &expected.generation_rules, &actual.generation_rules, |r| r.id.clone(), &mut drift);
+
+        Ok(SpecVerification { replayed, pending, drift })
+    }
+
     pub fn migration_status(&self) -> Result<MigrationStatus> {
         let migrations = self.load_migrations()?;
         let state = self.load_state().unwrap_or_default();
         
         let pending = self.get_pending_migrations(&migrations, &state)?;
         
+        let design_impacts = state.applied_migrations
+            .iter()
+            .chain(pending.iter())
+            .filter_map(|id| {
+                let impact = migrations.get(id)?.design_impact.as_ref()?;
+                (!impact.is_empty()).then(|| (id.clone(), impact.clone()))
+            })
+            .collect();
+        
+        let integrity_issues = self.validate_integrity()?;
+        
+        let authorship = migrations
+            .values()
+            .filter(|m| m.author.is_some() || m.date.is_some())
+            .map(|m| (m.id.clone(), MigrationAuthorship { author: m.author.clone(), date: m.date.clone() }))
+            .collect();
+        
         Ok(MigrationStatus {
             total_migrations: migrations.len(),
             applied_count: state.applied_migrations.len(),
             pending_count: pending.len(),
             applied_migrations: state.applied_migrations,
             pending_migrations: pending,
+            design_impacts,
+            integrity_issues,
+            authorship,
         })
     }
 
     /// Load all migration files from the migration directory
     pub fn load_migrations(&self) -> Result<HashMap<String, Migration>> {
+        Ok(self.load_migration_files()?
+            .into_iter()
+            .map(|(id, file)| (id, file.migration))
+            .collect())
+    }
+
+    /// Load all migration files together with their location and content checksum
+    pub fn load_migration_files(&self) -> Result<HashMap<String, MigrationFile>> {
         let mut migrations = HashMap::new();
         
         if !self.migration_dir.exists() {
@@ -368,106 +897,298 @@ impl MigrationRunner {
                         "Failed to parse migration file {}: {}", path.display(), e
                     )))?;
                 
-                migrations.insert(migration.id.clone(), migration);
+                if let Some(design_impact) = &migration.design_impact {
+                    self.validate_design_impact(design_impact).map_err(|e| {
+                        crate::error::FdmlError::migration_error(format!(
+                            "Invalid design_impact in migration file {}: {}", path.display(), e
+                        ))
+                    })?;
+                }
+                
+                migrations.insert(migration.id.clone(), MigrationFile {
+                    checksum: migration_checksum(&content),
+                    path,
+                    migration,
+                });
             }
         }
         
         Ok(migrations)
     }
 
+    /// Compare applied migrations recorded in the state file against the migration
+    /// files on disk, reporting modified, missing and reordered migrations
+    pub fn validate_integrity(&self) -> Result<Vec<IntegrityIssue>> {
+        let files = self.load_migration_files()?;
+        let state = self.load_state().unwrap_or_default();
+        let mut issues = Vec::new();
+
+        for (position, migration_id) in state.applied_migrations.iter().enumerate() {
+            let Some(file) = files.get(migration_id) else {
+                issues.push(IntegrityIssue::Missing { id: migration_id.clone() });
+                continue;
+            };
+
+            // Migrations applied before checksums were recorded cannot be verified
+            if let Some(record) = state.applied_record(migration_id) {
+                if record.checksum != file.checksum {
+                    issues.push(IntegrityIssue::Modified {
+                        id: migration_id.clone(),
+                        expected: record.checksum.clone(),
+                        actual: file.checksum.clone(),
+                    });
+                }
+            }
+
+            for dependency in file.migration.dependencies.iter().flatten() {
+                let dependency_position = state.applied_migrations.iter().position(|id| id == dependency);
+                if dependency_position.is_none_or(|p| p > position) {
+                    issues.push(IntegrityIssue::Reordered {
+                        id: migration_id.clone(),
+                        dependency: dependency.clone(),
+                    });
+                }
+            }
+        }
+
+        Ok(issues)
+    }
+
+    /// Fail when applied migrations no longer match their files
+    fn ensure_integrity(&self) -> Result<()> {
+        let issues = self.validate_integrity()?;
+        if issues.is_empty() {
+            return Ok(());
+        }
+
+        let details: Vec<String> = issues.iter().map(|issue| format!("  - {}", issue)).collect();
+        Err(crate::error::FdmlError::migration_error(format!(
+            "Migration history validation failed with {} issue(s):\n{}",
+            issues.len(),
+            details.join("\n")
+        )))
+    }
+
     fn load_state(&self) -> Result<MigrationState> {
         if !self.state_file.exists() {
             return Ok(MigrationState::default());
         }
         
         let content = fs::read_to_string(&self.state_file)?;
-        let state: MigrationState = serde_json::from_str(&content)
+        let mut state: MigrationState = serde_json::from_str(&content)
             .map_err(|e| crate::error::FdmlError::simple_parser_error(format!(
                 "Failed to parse migration state: {}", e
             )))?;
         
+        // Unreadable migration files are reported by whoever loads them next
+        if let Ok(files) = self.load_migration_files() {
+            collapse_squashed_state(&mut state, &files);
+        }
+        
         Ok(state)
     }
 
+    /// Replace the contiguous range of migrations from `from` to `to` (in apply order)
+    /// with a single migration whose operations are folded into an equivalent minimal set
+    pub fn squash_migrations(&self, from: &str, to: &str, dry_run: bool) -> Result<Migration> {
+        self.ensure_integrity()?;
+        
+        let files = self.load_migration_files()?;
+        let migrations: HashMap<String, Migration> = files
+            .iter()
+            .map(|(id, file)| (id.clone(), file.migration.clone()))
+            .collect();
+        let state = self.load_state().unwrap_or_default();
+        
+        let mut all_ids: Vec<_> = migrations.keys().cloned().collect();
+        all_ids.sort();
+        let order = self.resolve_migration_dependencies(&all_ids, &migrations)?;
+        
+        let position = |id: &str| order.iter().position(|m| m == id).ok_or_else(|| {
+            crate::error::FdmlError::migration_error(format!("Migration '{}' not found", id))
+        });
+        let (start, end) = (position(from)?, position(to)?);
+        if start >= end {
+            return Err(crate::error::FdmlError::migration_error(format!(
+                "Migration '{}' must come before '{}' to squash the range between them", from, to
+            )));
+        }
+        let range = &order[start..=end];
+        
+        let applied_count = range.iter().filter(|id| state.applied_migrations.contains(id)).count();
+        if applied_count != 0 && applied_count != range.len() {
+            return Err(crate::error::FdmlError::migration_error(format!(
+                "Cannot squash a partially applied range ({} of {} migrations applied)",
+                applied_count, range.len()
+            )));
+        }
+        
+        let squashed_id = format!("{}_squashed", to);
+        if migrations.contains_key(&squashed_id) {
+            return Err(crate::error::FdmlError::migration_error(format!(
+                "Migration '{}' already exists", squashed_id
+            )));
+        }
+        
+        let mut up = Vec::new();
+        let mut down = Vec::new();
+        let mut dependencies = Vec::new();
+        let mut design_impact = DesignImpact::default();
+        for migration in range.iter().map(|id| &migrations[id]) {
+            up.extend(migration.up.iter().cloned());
+            down.extend(migration.down.iter().cloned());
+            for dependency in migration.dependencies.iter().flatten() {
+                if !range.contains(dependency) && !dependencies.contains(dependency) {
+                    dependencies.push(dependency.clone());
+                }
+            }
+            if let Some(impact) = &migration.design_impact {
+                design_impact.principles_added.extend(impact.principles_added.iter().cloned());
+                design_impact.principles_removed.extend(impact.principles_removed.iter().cloned());
+                design_impact.strategy_changes.extend(impact.strategy_changes.iter().cloned());
+            }
+        }
+        
+        let folded_up = super::squash::fold_operations(&up);
+        let folded_down = super::squash::fold_down_operations(&down);
+        
+        println!("Squashing {} migrations into '{}':", range.len(), squashed_id);
+        for migration_id in range {
+            println!("  - {}", migration_id);
+        }
+        println!("  up: {} operations (from {})", folded_up.len(), up.len());
+        println!("  down: {} operations (from {})", folded_down.len(), down.len());
+        
+        // Later migrations that depend on a squashed one now depend on the squashed migration
+        let mut dependents: Vec<_> = order[end + 1..]
+            .iter()
+            .filter(|id| migrations[*id].dependencies.iter().flatten().any(|dep| range.contains(dep)))
+            .collect();
+        dependents.sort();
+        for migration_id in &dependents {
+            println!("  ~ Rewriting dependencies of {}", migration_id);
+        }
+        
+        let mut squashed = Migration {
+            id: squashed_id.clone(),
+            title: Some(format!("Squashed {} to {}", from, to)),
+            author: current_author(),
+            date: Some(chrono::Utc::now().to_rfc3339()),
+            description: None,
+            up: folded_up,
+            down: folded_down,
+            dependencies: if dependencies.is_empty() { None } else { Some(dependencies) },
+            design_impact: if design_impact.is_empty() { None } else { Some(design_impact) },
+            squash: None,
+        };
+        
+        if dry_run {
+            squashed.squash = Some(SquashInfo { replaces: range.to_vec(), rewritten: Vec::new() });
+            return Ok(squashed);
+        }
+        
+        // Prepare every rewrite before touching any file
+        let mut rewrites = Vec::new();
+        for migration_id in dependents {
+            let file = &files[migration_id];
+            let mut new_dependencies: Vec<String> = Vec::new();
+            for dependency in file.migration.dependencies.iter().flatten() {
+                let dependency = if range.contains(dependency) { &squashed_id } else { dependency };
+                if !new_dependencies.contains(dependency) {
+                    new_dependencies.push(dependency.clone());
+                }
+            }
+            
+            // Only the dependencies node changes; comments, layout and operations stay as written
+            let original = fs::read_to_string(&file.path)?;
+            let mut value: serde_yaml::Value = serde_yaml::from_str(&original)?;
+            if let Some(mapping) = value.as_mapping_mut() {
+                mapping.insert("dependencies".into(), serde_yaml::to_value(&new_dependencies)?);
+            }
+            let content = yaml_edit::update_yaml(&original, &value).map_err(|e| {
+                crate::error::FdmlError::migration_error(format!(
+                    "Cannot rewrite the dependencies of {}: {}", file.path.display(), e
+                ))
+            })?;
+            rewrites.push((migration_id, file, content));
+        }
+        
+        let mut rewritten = Vec::new();
+        for (migration_id, file, content) in rewrites {
+            fs::write(&file.path, &content)?;
+            rewritten.push(RewrittenMigration {
+                id: migration_id.clone(),
+                previous_checksum: file.checksum.clone(),
+                checksum: migration_checksum(&content),
+            });
+        }
+        
+        squashed.squash = Some(SquashInfo { replaces: range.to_vec(), rewritten });
+        fs::write(
+            self.migration_dir.join(format!("{}.yaml", squashed_id)),
+            serde_yaml::to_string(&squashed)?
+        )?;
+        for migration_id in range {
+            fs::remove_file(&files[migration_id].path)?;
+        }
+        
+        // Loading the state collapses the squashed range; persist it
+        if self.state_file.exists() {
+            let mut state = self.load_state()?;
+            state.updated_at = chrono::Utc::now().to_rfc3339();
+            fs::write(&self.state_file, serde_json::to_string_pretty(&state)?)?;
+        }
+        
+        Ok(squashed)
+    }
+
     /// Get pending migrations in dependency-resolved order
     pub fn get_pending_migrations(
         &self, 
         migrations: &HashMap<String, Migration>, 
         state: &MigrationState
     ) -> Result<Vec<String>> {
-        let all_migration_ids: Vec<_> = migrations.keys().cloned().collect();
-        
-        let pending: Vec<_> = all_migration_ids
-            .into_iter()
+        let mut pending: Vec<_> = migrations.keys()
             .filter(|id| !state.applied_migrations.contains(id))
+            .cloned()
             .collect();
         
+        // Start from id order so independent migrations apply in a stable order;
+        // ids created by `migrate new` are timestamped, so this is creation order
+        pending.sort();
+        
         // Resolve dependencies and return in correct order
         self.resolve_migration_dependencies(&pending, migrations)
     }
 
-    /// Resolve migration dependencies and return migrations in correct execution order
+    /// Resolve migration dependencies and return migrations in correct execution order.
+    /// Migrations keep their input order unless a dependency has to run first.
     fn resolve_migration_dependencies(
         &self,
         pending_migrations: &[String],
         migrations: &HashMap<String, Migration>
     ) -> Result<Vec<String>> {
-        let mut resolved = Vec::new();
-        let mut visited = HashSet::new();
-        let mut visiting = HashSet::new();
-
-        for migration_id in pending_migrations {
-            self.visit_migration_deps(
-                migration_id, 
-                migrations, 
-                &mut resolved, 
-                &mut visited, 
-                &mut visiting
-            )?;
-        }
-
-        // Filter to only include pending migrations in the final result
-        let pending_set: HashSet<_> = pending_migrations.iter().cloned().collect();
-        Ok(resolved.into_iter().filter(|id| pending_set.contains(id)).collect())
-    }
-
-    /// Recursive dependency visitor for topological sorting
-    fn visit_migration_deps(
-        &self,
-        migration_id: &str,
-        migrations: &HashMap<String, Migration>,
-        resolved: &mut Vec<String>,
-        visited: &mut HashSet<String>,
-        visiting: &mut HashSet<String>
-    ) -> Result<()> {
-        if visited.contains(migration_id) {
-            return Ok(());
-        }
-
-        if visiting.contains(migration_id) {
-            return Err(crate::error::FdmlError::migration_error(format!(
-                "Circular dependency detected involving migration '{}'", migration_id
-            )));
-        }
-
-        visiting.insert(migration_id.to_string());
-
-        if let Some(migration) = migrations.get(migration_id) {
-            if let Some(dependencies) = &migration.dependencies {
-                for dep_id in dependencies {
-                    self.visit_migration_deps(dep_id, migrations, resolved, visited, visiting)?;
-                }
-            }
-        }
-
-        visiting.remove(migration_id);
-        visited.insert(migration_id.to_string());
-        
-        if !resolved.contains(&migration_id.to_string()) {
-            resolved.push(migration_id.to_string());
+        // Repeatedly take the earliest pending migration whose pending dependencies are done
+        let pending_set: HashSet<_> = pending_migrations.iter().collect();
+        let mut remaining: Vec<&String> = pending_migrations.iter().collect();
+        let mut ordered: Vec<String> = Vec::new();
+        while !remaining.is_empty() {
+            let ready = remaining.iter().position(|id| {
+                migrations.get(*id)
+                    .and_then(|m| m.dependencies.as_ref())
+                    .into_iter()
+                    .flatten()
+                    .all(|dep| !pending_set.contains(dep) || ordered.contains(dep))
+            });
+            let Some(index) = ready else {
+                return Err(crate::error::FdmlError::migration_error(format!(
+                    "Circular dependency detected involving migration '{}'", remaining[0]
+                )));
+            };
+            ordered.push(remaining.remove(index).clone());
         }
 
-        Ok(())
+        Ok(ordered)
     }
 
     fn apply_migration(&self, migration: &Migration, document: &mut FdmlDocument) -> Result<()> {
@@ -501,7 +1222,15 @@ impl MigrationRunner {
     /// Save the modified FDML document
     fn save_target_document(&self, document: &FdmlDocument) -> Result<()> {
         if let Some(target_file) = &self.target_fdml_file {
-            let content = serde_yaml::to_string(document)?;
+            // Edit the existing file in place so comments and layout survive
+            let content = match fs::read_to_string(target_file) {
+                Ok(original) => yaml_edit::update_yaml(&original, document).map_err(|e| {
+                    crate::error::FdmlError::migration_error(format!(
+                        "Not updating {}: {}", target_file.display(), e
+                    ))
+                })?,
+                Err(_) => serde_yaml::to_string(document)?,
+            };
             fs::write(target_file, content)?;
             println!("  💾 Updated {}", target_file.display());
         }
@@ -511,8 +1240,14 @@ impl MigrationRunner {
     /// Describe what an operation would do (for dry-run mode)
     fn describe_operation(&self, operation: &MigrationOperation) {
         match operation {
-            MigrationOperation::AddFeature { id, title, .. } => {
+            MigrationOperation::AddFeature { id, title, entity, operation, roles, .. } => {
                 println!("    + Add feature: {} ({})", id, title);
+                if let Some(entity) = entity {
+                    println!("      - Entity: {} ({})", entity, operation.as_deref().unwrap_or("any operation"));
+                }
+                if let Some(roles) = roles {
+                    println!("      - Roles: {}", roles.join(", "));
+                }
             },
             MigrationOperation::RemoveFeature { id } => {
                 println!("    - Remove feature: {}", id);
@@ -556,18 +1291,41 @@ impl MigrationRunner {
             MigrationOperation::ChangeValidation { target_id, target_type, .. } => {
                 println!("    ~ Change validation for {} ({})", target_id, target_type);
             },
+            MigrationOperation::AddTraceabilityLink { from, to, relation, .. } => {
+                println!("    + Add traceability link: {} -[{}]-> {}", from, relation, to);
+            },
+            MigrationOperation::RemoveTraceabilityLink { link_id } => {
+                println!("    - Remove traceability link: {}", link_id);
+            },
+            MigrationOperation::UpdateTraceabilityLink { link_id, changes } => {
+                println!("    ~ Update traceability link: {}", link_id);
+                if let Some(relation) = &changes.relation {
+                    println!("      - Change relation to: {}", relation);
+                }
+                if let Some(confidence) = changes.confidence {
+                    println!("      - Change confidence to: {}", confidence);
+                }
+                if let Some(verified) = changes.verified {
+                    println!("      - Mark as verified: {}", verified);
+                }
+            },
         }
     }
 
     /// Validate that an operation is valid
     pub fn validate_operation(&self, operation: &MigrationOperation) -> Result<()> {
         match operation {
-            MigrationOperation::AddFeature { id, title, .. } => {
+            MigrationOperation::AddFeature { id, title, roles, .. } => {
                 if id.trim().is_empty() || title.trim().is_empty() {
                     return Err(crate::error::FdmlError::migration_error(
                         "AddFeature operation requires non-empty id and title".to_string()
                     ));
                 }
+                if roles.iter().flatten().any(|role| role.trim().is_empty()) {
+                    return Err(crate::error::FdmlError::migration_error(
+                        "AddFeature roles must be non-empty".to_string()
+                    ));
+                }
             },
             MigrationOperation::RemoveFeature { id } => {
                 if id.trim().is_empty() {
@@ -604,12 +1362,11 @@ impl MigrationRunner {
                     ));
                 }
             },
-            MigrationOperation::AddConstraint { id, name, condition, applies_to, .. } => {
-                if id.trim().is_empty() || name.trim().is_empty() || condition.trim().is_empty() || applies_to.trim().is_empty() {
-                    return Err(crate::error::FdmlError::migration_error(
-                        "AddConstraint operation requires non-empty id, name, condition, and applies_to".to_string()
-                    ));
-                }
+            MigrationOperation::AddConstraint { id, name, condition, applies_to, .. }
+                if id.trim().is_empty() || name.trim().is_empty() || condition.trim().is_empty() || applies_to.trim().is_empty() => {
+                return Err(crate::error::FdmlError::migration_error(
+                    "AddConstraint operation requires non-empty id, name, condition, and applies_to".to_string()
+                ));
             },
             MigrationOperation::RemoveConstraint { id } => {
                 if id.trim().is_empty() {
@@ -632,14 +1389,199 @@ impl MigrationRunner {
                     ));
                 }
             },
+            MigrationOperation::AddTraceabilityLink { link_id, from, to, relation, confidence, .. } => {
+                if from.trim().is_empty() || to.trim().is_empty() || relation.trim().is_empty() {
+                    return Err(crate::error::FdmlError::migration_error(
+                        "AddTraceabilityLink operation requires non-empty from, to, and relation".to_string()
+                    ));
+                }
+                if link_id.as_ref().is_some_and(|id| id.trim().is_empty()) {
+                    return Err(crate::error::FdmlError::migration_error(
+                        "AddTraceabilityLink operation link_id must not be empty when given".to_string()
+                    ));
+                }
+                validate_confidence(*confidence)?;
+            },
+            MigrationOperation::RemoveTraceabilityLink { link_id } if link_id.trim().is_empty() => {
+                return Err(crate::error::FdmlError::migration_error(
+                    "RemoveTraceabilityLink operation requires non-empty link_id".to_string()
+                ));
+            },
+            MigrationOperation::UpdateTraceabilityLink { link_id, changes } => {
+                if link_id.trim().is_empty() {
+                    return Err(crate::error::FdmlError::migration_error(
+                        "UpdateTraceabilityLink operation requires non-empty link_id".to_string()
+                    ));
+                }
+                if changes.relation.is_none() && changes.confidence.is_none() && changes.verified.is_none() {
+                    return Err(crate::error::FdmlError::migration_error(
+                        "UpdateTraceabilityLink operation requires at least one of relation, confidence, or verified".to_string()
+                    ));
+                }
+                if changes.relation.as_ref().is_some_and(|r| r.trim().is_empty()) {
+                    return Err(crate::error::FdmlError::migration_error(
+                        "UpdateTraceabilityLink operation relation must not be empty".to_string()
+                    ));
+                }
+                validate_confidence(changes.confidence)?;
+            },
             _ => {} // Other operations are assumed valid for now
         }
         Ok(())
     }
 
-    fn execute_operation(&self, operation: &MigrationOperation, document: &mut FdmlDocument) -> Result<()> {
+    /// Validate an operation of a new or pending migration; unlike [`Self::validate_operation`]
+    /// this also requires constraint conditions to parse, which applied migrations predate
+    pub fn validate_new_operation(&self, operation: &MigrationOperation) -> Result<()> {
+        self.validate_operation(operation)?;
+        match operation {
+            MigrationOperation::AddConstraint { id, condition, .. } => crate::condition::parse_condition(condition)
+                .map(|_| ())
+                .map_err(|problem| crate::error::FdmlError::migration_error(format!(
+                    "AddConstraint '{}' has an invalid condition \"{}\": {}", id, condition, problem
+                ))),
+            _ => Ok(()),
+        }
+    }
+
+    /// Validate a migration's design impact against the principle taxonomy
+    pub fn validate_design_impact(&self, impact: &DesignImpact) -> Result<()> {
+        let entries = impact.principles_added.iter().map(|a| ("principles_added", &a.element_id, &a.principle))
+            .chain(impact.principles_removed.iter().map(|r| ("principles_removed", &r.element_id, &r.principle)))
+            .chain(impact.strategy_changes.iter().map(|c| ("strategy_changes", &c.element_id, &c.principle)));
+
+        for (section, element_id, principle) in entries {
+            if element_id.trim().is_empty() {
+                return Err(crate::error::FdmlError::migration_error(format!(
+                    "{} entry requires a non-empty element_id", section
+                )));
+            }
+            if !principles::is_known_principle(principle) {
+                return Err(crate::error::FdmlError::migration_error(format!(
+                    "{} entry for '{}' references unknown principle '{}'", section, element_id, principle
+                )));
+            }
+        }
+
+        for change in &impact.strategy_changes {
+            if change.old_strategy.is_none() && change.new_strategy.is_none() {
+                return Err(crate::error::FdmlError::migration_error(format!(
+                    "strategy_changes entry for '{}' requires old_strategy or new_strategy", change.element_id
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Create a new timestamped migration template depending on the current head
+    /// migration. Returns the path of the created file.
+    pub fn create_migration(&self, title: &str) -> Result<PathBuf> {
+        let slug = migration_slug(title);
+        if slug.is_empty() {
+            return Err(crate::error::FdmlError::migration_error(
+                "Migration title must contain at least one letter or digit".to_string()
+            ));
+        }
+
+        let head = self.migration_history()?.pop().map(|(migration, _)| migration.id);
+        let timestamp = chrono::Utc::now().format("%Y%m%d%H%M%S");
+        let migration_id = format!("{}_{}", timestamp, slug);
+        let path = self.migration_dir.join(format!("{}.yaml", migration_id));
+
+        if path.exists() {
+            return Err(crate::error::FdmlError::migration_error(format!(
+                "Migration file {} already exists", path.display()
+            )));
+        }
+
+        let dependencies = match head {
+            Some(head_id) => format!("dependencies:\n  - {}\n", serde_yaml::to_string(&head_id)?.trim_end()),
+            None => "dependencies: []\n".to_string(),
+        };
+        let author = match current_author() {
+            Some(author) => serde_yaml::to_string(&author)?.trim_end().to_string(),
+            None => "null".to_string(),
+        };
+        let content = format!(
+            "id: {}\ntitle: {}\nauthor: {}\ndate: {}\ndescription: null\n{}up: []\n# - type: add_entity\n#   id: example\n#   name: Example\ndown: []\n# - type: remove_entity\n#   id: example\n",
+            migration_id,
+            serde_yaml::to_string(title)?.trim_end(),
+            author,
+            chrono::Utc::now().to_rfc3339(),
+            dependencies
+        );
+
+        fs::create_dir_all(&self.migration_dir)?;
+        fs::write(&path, content)?;
+        Ok(path)
+    }
+
+    /// All known migrations in history order: applied ones in the order they were
+    /// applied, followed by pending ones in execution order. The flag marks applied migrations.
+    pub fn migration_history(&self) -> Result<Vec<(Migration, bool)>> {
+        let mut migrations = self.load_migrations()?;
+        let state = self.load_state().unwrap_or_default();
+        let pending = self.get_pending_migrations(&migrations, &state)?;
+
+        let mut history = Vec::new();
+        for migration_id in &state.applied_migrations {
+            if let Some(migration) = migrations.remove(migration_id) {
+                history.push((migration, true));
+            }
+        }
+        for migration_id in &pending {
+            if let Some(migration) = migrations.remove(migration_id) {
+                history.push((migration, false));
+            }
+        }
+
+        Ok(history)
+    }
+
+    /// Collect design principle changes per element, in migration history order
+    pub fn principle_history(&self) -> Result<BTreeMap<String, Vec<PrincipleEvent>>> {
+        let mut history: BTreeMap<String, Vec<PrincipleEvent>> = BTreeMap::new();
+
+        for (migration, applied) in self.migration_history()? {
+            let Some(impact) = &migration.design_impact else {
+                continue;
+            };
+            let migration_id = &migration.id;
+
+            let mut record = |element_id: &str, principle: &str, change: PrincipleChange| {
+                history.entry(element_id.to_string()).or_default().push(PrincipleEvent {
+                    migration_id: migration_id.clone(),
+                    applied,
+                    principle: principle.to_string(),
+                    change,
+                });
+            };
+
+            for added in &impact.principles_added {
+                record(&added.element_id, &added.principle, PrincipleChange::Added {
+                    rationale: added.rationale.clone(),
+                });
+            }
+            for removed in &impact.principles_removed {
+                record(&removed.element_id, &removed.principle, PrincipleChange::Removed {
+                    reason: removed.reason.clone(),
+                });
+            }
+            for changed in &impact.strategy_changes {
+                record(&changed.element_id, &changed.principle, PrincipleChange::StrategyChanged {
+                    old_strategy: changed.old_strategy.clone(),
+                    new_strategy: changed.new_strategy.clone(),
+                });
+            }
+        }
+
+        Ok(history)
+    }
+
+    pub(crate) fn execute_operation(&self, operation: &MigrationOperation, document: &mut FdmlDocument) -> Result<()> {
         match operation {
-            MigrationOperation::AddFeature { id, title, description, scenarios } => {
+            MigrationOperation::AddFeature { id, title, description, scenarios, entity, operation, roles } => {
                 println!("  + Adding feature: {} - {}", id, title);
                 
                 let scenarios = scenarios.as_ref().map(|s| {
@@ -662,6 +1604,10 @@ impl MigrationRunner {
                     scenarios,
                     acceptance_criteria: None,
                     dependencies: None,
+                    entity: entity.clone(),
+                    operation: operation.clone(),
+                    roles: roles.clone(),
+                    effort: None,
                 };
                 
                 document.features.push(feature);
@@ -681,6 +1627,8 @@ impl MigrationRunner {
                     description: description.clone(),
                     fields: Vec::new(),
                     relationships: None,
+                    design_principles: None,
+                    implementation_strategies: None,
                 };
                 
                 document.entities.push(entity);
@@ -722,6 +1670,9 @@ impl MigrationRunner {
                     side_effects: None,
                     preconditions: None,
                     postconditions: None,
+                    exceptions: None,
+                    design_principles: None,
+                    execution_strategies: None,
                 };
                 
                 document.actions.push(action);
@@ -735,14 +1686,21 @@ impl MigrationRunner {
             MigrationOperation::AddConstraint { id, name, description, condition, applies_to, message } => {
                 println!("  + Adding constraint: {} - {}", id, name);
                 
+                // `applies_to` is an entity or one of its fields, e.g. `user.email`
+                let entity_id = applies_to.split('.').next().unwrap_or_default();
+                let entities = document.entities.iter().any(|entity| entity.id == entity_id)
+                    .then(|| vec![entity_id.to_string()]);
+                
                 let constraint = Constraint {
                     id: id.clone(),
                     name: name.clone(),
                     description: description.clone(),
                     constraint_type: "rule".to_string(),
                     rule: condition.clone(),
-                    entities: None,
+                    entities,
                     actions: None,
+                    design_principles: None,
+                    enforcement_strategy: None,
                 };
                 
                 document.constraints.push(constraint);
@@ -776,6 +1734,7 @@ impl MigrationRunner {
                             }
                         }),
                         constraints: None,
+                        design_hints: None,
                     };
                     
                     entity.fields.push(field);
@@ -855,17 +1814,95 @@ impl MigrationRunner {
                     }
                 }
             },
+
+            MigrationOperation::AddTraceabilityLink { link_id, from, to, relation, description, confidence, verified } => {
+                println!("  + Adding traceability link: {} -[{}]-> {}", from, relation, to);
+
+                let link = Traceability {
+                    id: link_id.clone(),
+                    from: from.clone(),
+                    to: to.clone(),
+                    relation: relation.clone(),
+                    description: description.clone(),
+                    confidence: *confidence,
+                    verified: *verified,
+                    metadata: None,
+                };
+
+                let new_id = link.link_id();
+                if document.traceability.iter().any(|t| t.link_id() == new_id) {
+                    return Err(crate::error::FdmlError::migration_error(format!(
+                        "Traceability link '{}' already exists", new_id
+                    )));
+                }
+
+                document.traceability.push(link);
+            },
+
+            MigrationOperation::RemoveTraceabilityLink { link_id } => {
+                println!("  - Removing traceability link: {}", link_id);
+
+                let initial_count = document.traceability.len();
+                document.traceability.retain(|t| t.link_id() != *link_id);
+
+                if document.traceability.len() == initial_count {
+                    return Err(crate::error::FdmlError::migration_error(format!(
+                        "Traceability link '{}' not found", link_id
+                    )));
+                }
+            },
+
+            MigrationOperation::UpdateTraceabilityLink { link_id, changes } => {
+                println!("  ~ Updating traceability link: {}", link_id);
+
+                if let Some(link) = document.traceability.iter_mut().find(|t| t.link_id() == *link_id) {
+                    // Pin the id first so that a derived id survives a relation change
+                    if link.id.is_none() {
+                        link.id = Some(link_id.clone());
+                    }
+                    if let Some(new_relation) = &changes.relation {
+                        println!("    - Changing relation to: {}", new_relation);
+                        link.relation = new_relation.clone();
+                    }
+                    if let Some(new_confidence) = changes.confidence {
+                        println!("    - Changing confidence to: {}", new_confidence);
+                        link.confidence = Some(new_confidence);
+                    }
+                    if let Some(new_verified) = changes.verified {
+                        println!("    - Changing verified to: {}", new_verified);
+                        link.verified = Some(new_verified);
+                    }
+                } else {
+                    return Err(crate::error::FdmlError::migration_error(format!(
+                        "Traceability link '{}' not found", link_id
+                    )));
+                }
+            },
         }
         Ok(())
     }
 
-    fn update_state(&self, applied: &[String]) -> Result<()> {
+    fn update_state(&self, applied: &[String], files: &HashMap<String, MigrationFile>) -> Result<()> {
         let mut state = self.load_state().unwrap_or_default();
+        let applied_at = chrono::Utc::now().to_rfc3339();
+        let author = current_author();
+        let target_file = self.target_fdml_file.as_ref().map(|p| p.display().to_string());
         
         for migration_id in applied {
             if !state.applied_migrations.contains(migration_id) {
                 state.applied_migrations.push(migration_id.clone());
             }
+            
+            if let Some(file) = files.get(migration_id) {
+                state.applied_records.retain(|r| r.id != *migration_id);
+                state.applied_records.push(AppliedMigration {
+                    id: migration_id.clone(),
+                    checksum: file.checksum.clone(),
+                    applied_at: applied_at.clone(),
+                    author: author.clone(),
+                    target_file: target_file.clone(),
+                });
+            }
         }
         
         state.last_migration = applied.last().cloned();
@@ -882,6 +1919,7 @@ impl MigrationRunner {
         
         for migration_id in rolled_back {
             state.applied_migrations.retain(|id| id != migration_id);
+            state.applied_records.retain(|r| r.id != *migration_id);
         }
         
         state.last_migration = state.applied_migrations.last().cloned();
@@ -894,10 +1932,161 @@ impl MigrationRunner {
     }
 }
 
+/// Read `up`/`down` operations written either as `- type: add_feature` with inline
+/// parameters or in the spec's `- add_feature: { ... }` form
+fn deserialize_operations<'de, D>(deserializer: D) -> std::result::Result<Vec<MigrationOperation>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let values = Vec::<serde_yaml::Value>::deserialize(deserializer)?;
+    values
+        .into_iter()
+        .map(|value| serde_yaml::from_value(normalize_operation(value)).map_err(serde::de::Error::custom))
+        .collect()
+}
+
+/// Rewrite `{add_feature: {id: ...}}` into `{type: add_feature, id: ...}`
+fn normalize_operation(value: serde_yaml::Value) -> serde_yaml::Value {
+    let serde_yaml::Value::Mapping(mapping) = &value else {
+        return value;
+    };
+    if mapping.len() != 1 || mapping.contains_key("type") {
+        return value;
+    }
+    
+    let Some((serde_yaml::Value::String(operation), params)) = mapping.iter().next() else {
+        return value;
+    };
+    let mut normalized = match params {
+        serde_yaml::Value::Mapping(params) => params.clone(),
+        serde_yaml::Value::Null => serde_yaml::Mapping::new(),
+        _ => return value,
+    };
+    normalized.insert("type".into(), operation.as_str().into());
+    serde_yaml::Value::Mapping(normalized)
+}
+
+/// Checksum of a migration file, insensitive to line ending conversions
+fn migration_checksum(content: &str) -> String {
+    let normalized = content.replace("\r\n", "\n");
+    Sha256::digest(normalized.as_bytes())
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+/// Name recorded as the author of applied migrations
+fn current_author() -> Option<String> {
+    ["FDML_AUTHOR", "USER", "USERNAME"]
+        .iter()
+        .find_map(|var| std::env::var(var).ok().filter(|v| !v.trim().is_empty()))
+}
+
+/// Bring a state file written before a squash up to date: a squashed migration counts
+/// as applied once every migration it replaces is applied, and migrations whose
+/// dependencies the squash rewrote keep a valid checksum
+fn collapse_squashed_state(state: &mut MigrationState, files: &HashMap<String, MigrationFile>) {
+    let mut squashed: Vec<_> = files.values()
+        .filter_map(|file| file.migration.squash.as_ref().map(|info| (file, info)))
+        .collect();
+    squashed.sort_by(|a, b| a.0.migration.id.cmp(&b.0.migration.id));
+    
+    for (file, info) in squashed {
+        let squashed_id = &file.migration.id;
+        let all_applied = !info.replaces.is_empty()
+            && info.replaces.iter().all(|id| state.applied_migrations.contains(id));
+        
+        if all_applied && !state.applied_migrations.contains(squashed_id) {
+            let position = state.applied_migrations
+                .iter()
+                .rposition(|id| info.replaces.contains(id))
+                .map_or(state.applied_migrations.len(), |last| last + 1);
+            let last_record = state.applied_records
+                .iter()
+                .rev()
+                .find(|r| info.replaces.contains(&r.id))
+                .cloned();
+            
+            state.applied_migrations.insert(position, squashed_id.clone());
+            state.applied_migrations.retain(|id| !info.replaces.contains(id));
+            state.applied_records.retain(|r| !info.replaces.contains(&r.id));
+            if let Some(record) = last_record {
+                state.applied_records.push(AppliedMigration {
+                    id: squashed_id.clone(),
+                    checksum: file.checksum.clone(),
+                    ..record
+                });
+            }
+            if state.last_migration.as_ref().is_some_and(|id| info.replaces.contains(id)) {
+                state.last_migration = Some(squashed_id.clone());
+            }
+        }
+        
+        for rewrite in &info.rewritten {
+            let current = files.get(&rewrite.id).map(|f| &f.checksum);
+            if current != Some(&rewrite.checksum) {
+                continue;
+            }
+            if let Some(record) = state.applied_records.iter_mut().find(|r| r.id == rewrite.id) {
+                if record.checksum == rewrite.previous_checksum {
+                    record.checksum = rewrite.checksum.clone();
+                }
+            }
+        }
+    }
+}
+
+/// Compare two element lists by id, recording elements that are missing, extra or changed
+fn compare_elements<T: PartialEq>(
+    kind: &'static str,
+    expected: &[T],
+    actual: &[T],
+    id_of: impl Fn(&T) -> String,
+    drift: &mut Vec<SpecDrift>
+) {
+    for element in expected {
+        let id = id_of(element);
+        match actual.iter().find(|other| id_of(other) == id) {
+            None => drift.push(SpecDrift::Missing { kind, id }),
+            Some(other) if other != element => drift.push(SpecDrift::Modified { kind, id }),
+            Some(_) => {}
+        }
+    }
+    for element in actual {
+        let id = id_of(element);
+        if !expected.iter().any(|other| id_of(other) == id) {
+            drift.push(SpecDrift::Unexpected { kind, id });
+        }
+    }
+}
+
+/// snake_case file name fragment for a migration title
+fn migration_slug(title: &str) -> String {
+    title
+        .to_lowercase()
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("_")
+}
+
+/// Confidence scores on traceability links are defined on the 0.0-1.0 range
+fn validate_confidence(confidence: Option<f64>) -> Result<()> {
+    if let Some(value) = confidence {
+        if !(0.0..=1.0).contains(&value) {
+            return Err(crate::error::FdmlError::migration_error(format!(
+                "Traceability confidence must be between 0.0 and 1.0, got {}", value
+            )));
+        }
+    }
+    Ok(())
+}
+
 impl Default for MigrationState {
     fn default() -> Self {
         Self {
             applied_migrations: Vec::new(),
+            applied_records: Vec::new(),
             last_migration: None,
             created_at: chrono::Utc::now().to_rfc3339(),
             updated_at: chrono::Utc::now().to_rfc3339(),
@@ -912,4 +2101,40 @@ pub struct MigrationStatus {
     pub pending_count: usize,
     pub applied_migrations: Vec<String>,
     pub pending_migrations: Vec<String>,
+    pub design_impacts: HashMap<String, DesignImpact>,
+    pub integrity_issues: Vec<IntegrityIssue>,
+    /// Author and date of migrations that declare them
+    pub authorship: HashMap<String, MigrationAuthorship>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MigrationAuthorship {
+    pub author: Option<String>,
+    pub date: Option<String>,
+}
+
+impl std::fmt::Display for MigrationAuthorship {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (&self.author, &self.date) {
+            (Some(author), Some(date)) => write!(f, "by {}, {}", author, date),
+            (Some(author), None) => write!(f, "by {}", author),
+            (None, Some(date)) => write!(f, "{}", date),
+            (None, None) => Ok(()),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PrincipleEvent {
+    pub migration_id: String,
+    pub applied: bool,
+    pub principle: String,
+    pub change: PrincipleChange,
+}
+
+#[derive(Debug, Clone)]
+pub enum PrincipleChange {
+    Added { rationale: Option<String> },
+    Removed { reason: Option<String> },
+    StrategyChanged { old_strategy: Option<String>, new_strategy: Option<String> },
 }
\ No newline at end of file
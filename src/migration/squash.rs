@@ -0,0 +1,254 @@
+use crate::migration::runner::MigrationOperation;
+
+/// Element of the document an operation creates, removes or changes
+#[derive(Debug, Clone, PartialEq)]
+enum Target {
+    Element(&'static str, String),
+    Field(String, String),
+}
+
+impl Target {
+    /// Whether removing `self` makes an operation on `other` redundant
+    fn contains(&self, other: &Target) -> bool {
+        match (self, other) {
+            (Target::Element("entity", entity_id), Target::Field(field_entity, _)) => entity_id == field_entity,
+            _ => self == other,
+        }
+    }
+}
+
+fn created(operation: &MigrationOperation) -> Option<Target> {
+    match operation {
+        MigrationOperation::AddFeature { id, .. } => Some(Target::Element("feature", id.clone())),
+        MigrationOperation::AddEntity { id, .. } => Some(Target::Element("entity", id.clone())),
+        MigrationOperation::AddAction { id, .. } => Some(Target::Element("action", id.clone())),
+        MigrationOperation::AddConstraint { id, .. } => Some(Target::Element("constraint", id.clone())),
+        MigrationOperation::AddTraceabilityLink { link_id, from, to, relation, .. } => {
+            let id = link_id.clone().unwrap_or_else(|| format!("{}:{}:{}", from, relation, to));
+            Some(Target::Element("traceability", id))
+        },
+        MigrationOperation::AddField { entity_id, field_name, .. } => {
+            Some(Target::Field(entity_id.clone(), field_name.clone()))
+        },
+        _ => None,
+    }
+}
+
+fn removed(operation: &MigrationOperation) -> Option<Target> {
+    match operation {
+        MigrationOperation::RemoveFeature { id } => Some(Target::Element("feature", id.clone())),
+        MigrationOperation::RemoveEntity { id } => Some(Target::Element("entity", id.clone())),
+        MigrationOperation::RemoveAction { id } => Some(Target::Element("action", id.clone())),
+        MigrationOperation::RemoveConstraint { id } => Some(Target::Element("constraint", id.clone())),
+        MigrationOperation::RemoveTraceabilityLink { link_id } => {
+            Some(Target::Element("traceability", link_id.clone()))
+        },
+        MigrationOperation::RemoveField { entity_id, field_name } => {
+            Some(Target::Field(entity_id.clone(), field_name.clone()))
+        },
+        _ => None,
+    }
+}
+
+fn modified(operation: &MigrationOperation) -> Option<Target> {
+    match operation {
+        MigrationOperation::ModifyEntity { id, .. } => Some(Target::Element("entity", id.clone())),
+        MigrationOperation::UpdateAction { id, .. } => Some(Target::Element("action", id.clone())),
+        MigrationOperation::UpdateTraceabilityLink { link_id, .. } => {
+            Some(Target::Element("traceability", link_id.clone()))
+        },
+        MigrationOperation::ChangeValidation { target_id, target_type, .. } => match target_type.as_str() {
+            "entity" => Some(Target::Element("entity", target_id.clone())),
+            "action" => Some(Target::Element("action", target_id.clone())),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Whether the operation only matters while `target` exists
+fn depends_on(operation: &MigrationOperation, target: &Target) -> bool {
+    modified(operation).is_some_and(|t| target.contains(&t))
+        || created(operation).is_some_and(|t| target != &t && target.contains(&t))
+        || removed(operation).is_some_and(|t| target != &t && target.contains(&t))
+}
+
+/// Fold a sequence of operations into a shorter sequence with the same effect.
+///
+/// - an element added and later removed is dropped together with everything done to it
+/// - changes to an element that is removed later in the sequence are dropped
+/// - changes to an element are merged into the operation that added it
+/// - repeated changes to the same element are merged into one
+pub fn fold_operations(operations: &[MigrationOperation]) -> Vec<MigrationOperation> {
+    let mut slots: Vec<Option<MigrationOperation>> = operations.iter().cloned().map(Some).collect();
+
+    // Cancel add/remove pairs and drop changes made moot by a removal
+    for index in 0..slots.len() {
+        let Some(target) = slots[index].as_ref().and_then(removed) else {
+            continue;
+        };
+
+        let mut pair = None;
+        let mut dependents = Vec::new();
+        for earlier in (0..index).rev() {
+            let Some(operation) = &slots[earlier] else {
+                continue;
+            };
+            if created(operation).as_ref() == Some(&target) {
+                pair = Some(earlier);
+                break;
+            }
+            if removed(operation).as_ref() == Some(&target)
+                || created(operation).is_some_and(|t| t.contains(&target))
+            {
+                // The element was removed before, or its parent re-created
+                break;
+            }
+            if depends_on(operation, &target) {
+                dependents.push(earlier);
+            }
+        }
+
+        for dependent in dependents {
+            slots[dependent] = None;
+        }
+        if let Some(created_at) = pair {
+            slots[created_at] = None;
+            slots[index] = None;
+        }
+    }
+
+    // Merge changes into the preceding add or change of the same element
+    for index in 0..slots.len() {
+        let Some(target) = slots[index].as_ref().and_then(modified) else {
+            continue;
+        };
+
+        for earlier in (0..index).rev() {
+            let Some(operation) = &slots[earlier] else {
+                continue;
+            };
+            if removed(operation).is_some_and(|t| t.contains(&target)) {
+                break;
+            }
+            let is_target = created(operation).as_ref() == Some(&target)
+                || modified(operation).as_ref() == Some(&target);
+            if !is_target {
+                continue;
+            }
+
+            let change = slots[index].as_ref().unwrap();
+            if let Some(merged) = merge_change(operation, change) {
+                slots[earlier] = Some(merged);
+                slots[index] = None;
+            }
+            break;
+        }
+    }
+
+    slots.into_iter().flatten().collect()
+}
+
+/// Fold the `down` operations of a range of migrations, given in migration order.
+/// Rollback runs each `down` list in reverse, so folding happens in execution order.
+pub fn fold_down_operations(downs: &[MigrationOperation]) -> Vec<MigrationOperation> {
+    let execution_order: Vec<_> = downs.iter().rev().cloned().collect();
+    let mut folded = fold_operations(&execution_order);
+    folded.reverse();
+    folded
+}
+
+/// Combine `change` into the earlier `operation`, if the result is equivalent
+fn merge_change(operation: &MigrationOperation, change: &MigrationOperation) -> Option<MigrationOperation> {
+    match (operation, change) {
+        (
+            MigrationOperation::AddEntity { id, name, description },
+            MigrationOperation::ModifyEntity { changes, .. },
+        ) if changes.add_fields.is_none() && changes.remove_fields.is_none() => {
+            Some(MigrationOperation::AddEntity {
+                id: id.clone(),
+                name: changes.name.clone().unwrap_or_else(|| name.clone()),
+                description: changes.description.clone().or_else(|| description.clone()),
+            })
+        },
+        (
+            MigrationOperation::ModifyEntity { id, changes: first },
+            MigrationOperation::ModifyEntity { changes: second, .. },
+        ) => {
+            let mut changes = first.clone();
+            changes.name = second.name.clone().or(changes.name);
+            changes.description = second.description.clone().or(changes.description);
+            changes.add_fields = merge_lists(&first.add_fields, &second.add_fields);
+            changes.remove_fields = merge_lists(&first.remove_fields, &second.remove_fields);
+            Some(MigrationOperation::ModifyEntity { id: id.clone(), changes })
+        },
+        (
+            MigrationOperation::AddAction { id, name, description },
+            MigrationOperation::UpdateAction { changes, .. },
+        ) if changes.input_changes.is_none() && changes.output_changes.is_none() => {
+            Some(MigrationOperation::AddAction {
+                id: id.clone(),
+                name: changes.name.clone().unwrap_or_else(|| name.clone()),
+                description: changes.description.clone().or_else(|| description.clone()),
+            })
+        },
+        (
+            MigrationOperation::UpdateAction { id, changes: first },
+            MigrationOperation::UpdateAction { changes: second, .. },
+        ) => {
+            let mut changes = first.clone();
+            changes.name = second.name.clone().or(changes.name);
+            changes.description = second.description.clone().or(changes.description);
+            for (merged, later) in [
+                (&mut changes.input_changes, &second.input_changes),
+                (&mut changes.output_changes, &second.output_changes),
+            ] {
+                if let Some(later) = later {
+                    merged.get_or_insert_with(Default::default).extend(later.clone());
+                }
+            }
+            Some(MigrationOperation::UpdateAction { id: id.clone(), changes })
+        },
+        (
+            MigrationOperation::AddTraceabilityLink { link_id, from, to, relation, description, confidence, verified },
+            MigrationOperation::UpdateTraceabilityLink { link_id: updated_id, changes },
+        ) => {
+            // The update pins the link id, so keep it stable across a relation change
+            Some(MigrationOperation::AddTraceabilityLink {
+                link_id: link_id.clone().or_else(|| Some(updated_id.clone())),
+                from: from.clone(),
+                to: to.clone(),
+                relation: changes.relation.clone().unwrap_or_else(|| relation.clone()),
+                description: description.clone(),
+                confidence: changes.confidence.or(*confidence),
+                verified: changes.verified.or(*verified),
+            })
+        },
+        (
+            MigrationOperation::UpdateTraceabilityLink { link_id, changes: first },
+            MigrationOperation::UpdateTraceabilityLink { changes: second, .. },
+        ) => {
+            let mut changes = first.clone();
+            changes.relation = second.relation.clone().or(changes.relation);
+            changes.confidence = second.confidence.or(changes.confidence);
+            changes.verified = second.verified.or(changes.verified);
+            Some(MigrationOperation::UpdateTraceabilityLink { link_id: link_id.clone(), changes })
+        },
+        _ => None,
+    }
+}
+
+fn merge_lists(first: &Option<Vec<String>>, second: &Option<Vec<String>>) -> Option<Vec<String>> {
+    match (first, second) {
+        (None, None) => None,
+        _ => {
+            let mut merged = first.clone().unwrap_or_default();
+            for item in second.iter().flatten() {
+                if !merged.contains(item) {
+                    merged.push(item.clone());
+                }
+            }
+            Some(merged)
+        },
+    }
+}
@@ -4,7 +4,8 @@ mod tests {
     use tempfile::TempDir;
     use std::fs;
     use crate::parser::ast::*;
-    use crate::migration::{Migration, MigrationOperation, MigrationRunner, MigrationState};
+    use crate::migration::{Migration, MigrationOperation, MigrationRunner, MigrationState, TraceabilityChanges, PrincipleChange, IntegrityIssue, SpecDrift, EntityChanges};
+    use crate::migration::squash::fold_operations;
 
     fn create_test_migration_file(dir: &std::path::Path, filename: &str, migration: &Migration) {
         let content = serde_yaml::to_string(migration).unwrap();
@@ -51,22 +52,31 @@ features:
         let migration1 = Migration {
             id: "001_add_user".to_string(),
             title: Some("Add User".to_string()),
+            author: None,
+            date: None,
             description: None,
             up: vec![MigrationOperation::AddFeature {
                 id: "user_management".to_string(),
                 title: "User Management".to_string(),
                 description: None,
                 scenarios: None,
+                entity: None,
+                operation: None,
+                roles: None,
             }],
             down: vec![MigrationOperation::RemoveFeature {
                 id: "user_management".to_string(),
             }],
             dependencies: None,
+            design_impact: None,
+            squash: None,
         };
 
         let migration2 = Migration {
             id: "002_add_profile".to_string(),
             title: Some("Add Profile".to_string()),
+            author: None,
+            date: None,
             description: None,
             up: vec![MigrationOperation::AddField {
                 entity_id: "user".to_string(),
@@ -80,6 +90,8 @@ features:
                 field_name: "profile".to_string(),
             }],
             dependencies: Some(vec!["001_add_user".to_string()]),
+            design_impact: None,
+            squash: None,
         };
 
         create_test_migration_file(&migration_dir, "001_add_user.yaml", &migration1);
@@ -106,19 +118,27 @@ features:
         let migration1 = Migration {
             id: "001_circular".to_string(),
             title: Some("Circular 1".to_string()),
+            author: None,
+            date: None,
             description: None,
             up: vec![],
             down: vec![],
             dependencies: Some(vec!["002_circular".to_string()]),
+            design_impact: None,
+            squash: None,
         };
 
         let migration2 = Migration {
             id: "002_circular".to_string(),
             title: Some("Circular 2".to_string()),
+            author: None,
+            date: None,
             description: None,
             up: vec![],
             down: vec![],
             dependencies: Some(vec!["001_circular".to_string()]),
+            design_impact: None,
+            squash: None,
         };
 
         create_test_migration_file(&migration_dir, "001_circular.yaml", &migration1);
@@ -145,17 +165,24 @@ features:
         let migration = Migration {
             id: "001_add_feature".to_string(),
             title: Some("Add Feature".to_string()),
+            author: None,
+            date: None,
             description: None,
             up: vec![MigrationOperation::AddFeature {
                 id: "new_feature".to_string(),
                 title: "New Feature".to_string(),
                 description: Some("A new feature".to_string()),
                 scenarios: Some(vec!["Scenario 1".to_string()]),
+                entity: None,
+                operation: None,
+                roles: None,
             }],
             down: vec![MigrationOperation::RemoveFeature {
                 id: "new_feature".to_string(),
             }],
             dependencies: None,
+            design_impact: None,
+            squash: None,
         };
 
         create_test_migration_file(&migration_dir, "001_add_feature.yaml", &migration);
@@ -188,6 +215,8 @@ features:
         let migration = Migration {
             id: "001_add_field".to_string(),
             title: Some("Add Field".to_string()),
+            author: None,
+            date: None,
             description: None,
             up: vec![MigrationOperation::AddField {
                 entity_id: "user".to_string(),
@@ -201,6 +230,8 @@ features:
                 field_name: "age".to_string(),
             }],
             dependencies: None,
+            design_impact: None,
+            squash: None,
         };
 
         create_test_migration_file(&migration_dir, "001_add_field.yaml", &migration);
@@ -232,15 +263,22 @@ features:
         let migration = Migration {
             id: "001_no_rollback".to_string(),
             title: Some("No Rollback".to_string()),
+            author: None,
+            date: None,
             description: None,
             up: vec![MigrationOperation::AddFeature {
                 id: "feature".to_string(),
                 title: "Feature".to_string(),
                 description: None,
                 scenarios: None,
+                entity: None,
+                operation: None,
+                roles: None,
             }],
             down: vec![], // No down operations
             dependencies: None,
+            design_impact: None,
+            squash: None,
         };
 
         create_test_migration_file(&migration_dir, "001_no_rollback.yaml", &migration);
@@ -270,15 +308,22 @@ features:
         let migration = Migration {
             id: "001_test".to_string(),
             title: Some("Test".to_string()),
+            author: None,
+            date: None,
             description: None,
             up: vec![MigrationOperation::AddFeature {
                 id: "test_feature".to_string(),
                 title: "Test Feature".to_string(),
                 description: None,
                 scenarios: None,
+                entity: None,
+                operation: None,
+                roles: None,
             }],
             down: vec![],
             dependencies: None,
+            design_impact: None,
+            squash: None,
         };
 
         create_test_migration_file(&migration_dir, "001_test.yaml", &migration);
@@ -307,15 +352,22 @@ features:
         let migration = Migration {
             id: "001_dry_run".to_string(),
             title: Some("Dry Run Test".to_string()),
+            author: None,
+            date: None,
             description: None,
             up: vec![MigrationOperation::AddFeature {
                 id: "dry_feature".to_string(),
                 title: "Dry Feature".to_string(),
                 description: None,
                 scenarios: None,
+                entity: None,
+                operation: None,
+                roles: None,
             }],
             down: vec![],
             dependencies: None,
+            design_impact: None,
+            squash: None,
         };
 
         create_test_migration_file(&migration_dir, "001_dry_run.yaml", &migration);
@@ -347,6 +399,9 @@ features:
             title: "Valid Feature".to_string(),
             description: None,
             scenarios: None,
+            entity: None,
+            operation: None,
+            roles: None,
         };
         assert!(runner.validate_operation(&valid_op).is_ok());
 
@@ -356,6 +411,9 @@ features:
             title: "Invalid Feature".to_string(),
             description: None,
             scenarios: None,
+            entity: None,
+            operation: None,
+            roles: None,
         };
         assert!(runner.validate_operation(&invalid_op).is_err());
 
@@ -382,6 +440,8 @@ features:
         let migration = Migration {
             id: "001_add_entity".to_string(),
             title: Some("Add Entity".to_string()),
+            author: None,
+            date: None,
             description: None,
             up: vec![MigrationOperation::AddEntity {
                 id: "new_entity".to_string(),
@@ -392,6 +452,8 @@ features:
                 id: "new_entity".to_string(),
             }],
             dependencies: None,
+            design_impact: None,
+            squash: None,
         };
 
         create_test_migration_file(&migration_dir, "001_add_entity.yaml", &migration);
@@ -419,6 +481,8 @@ features:
         let migration = Migration {
             id: "001_add_action".to_string(),
             title: Some("Add Action".to_string()),
+            author: None,
+            date: None,
             description: None,
             up: vec![MigrationOperation::AddAction {
                 id: "new_action".to_string(),
@@ -429,6 +493,8 @@ features:
                 id: "new_action".to_string(),
             }],
             dependencies: None,
+            design_impact: None,
+            squash: None,
         };
 
         create_test_migration_file(&migration_dir, "001_add_action.yaml", &migration);
@@ -456,12 +522,14 @@ features:
         let migration = Migration {
             id: "001_add_constraint".to_string(),
             title: Some("Add Constraint".to_string()),
+            author: None,
+            date: None,
             description: None,
             up: vec![MigrationOperation::AddConstraint {
                 id: "new_constraint".to_string(),
                 name: "New Constraint".to_string(),
                 description: Some("A new constraint".to_string()),
-                condition: "unique(email)".to_string(),
+                condition: "email is unique".to_string(),
                 applies_to: "user.email".to_string(),
                 message: Some("Email must be unique".to_string()),
             }],
@@ -469,6 +537,8 @@ features:
                 id: "new_constraint".to_string(),
             }],
             dependencies: None,
+            design_impact: None,
+            squash: None,
         };
 
         create_test_migration_file(&migration_dir, "001_add_constraint.yaml", &migration);
@@ -482,7 +552,7 @@ features:
         let content = fs::read_to_string(&target_file).unwrap();
         assert!(content.contains("new_constraint"));
         assert!(content.contains("New Constraint"));
-        assert!(content.contains("unique(email)"));
+        assert!(content.contains("email is unique"));
     }
 
     #[test]
@@ -530,11 +600,26 @@ features:
             id: "valid_constraint".to_string(),
             name: "Valid Constraint".to_string(),
             description: None,
-            condition: "valid condition".to_string(),
+            condition: "email is unique".to_string(),
             applies_to: "entity.field".to_string(),
             message: None,
         };
         assert!(runner.validate_operation(&valid_constraint_op).is_ok());
+        assert!(runner.validate_new_operation(&valid_constraint_op).is_ok());
+
+        // Prose conditions pass the structural check (rollback of applied migrations)
+        // but not the one for new and pending migrations
+        let prose_constraint_op = MigrationOperation::AddConstraint {
+            id: "prose_constraint".to_string(),
+            name: "Prose Constraint".to_string(),
+            description: None,
+            condition: "each user has one email".to_string(),
+            applies_to: "entity.field".to_string(),
+            message: None,
+        };
+        assert!(runner.validate_operation(&prose_constraint_op).is_ok());
+        let error = runner.validate_new_operation(&prose_constraint_op).unwrap_err().to_string();
+        assert!(error.contains("AddConstraint 'prose_constraint' has an invalid condition"), "{}", error);
 
         // Invalid AddConstraint operation (empty condition)
         let invalid_constraint_op = MigrationOperation::AddConstraint {
@@ -547,4 +632,906 @@ features:
         };
         assert!(runner.validate_operation(&invalid_constraint_op).is_err());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_traceability_link_operations() {
+        let temp_dir = TempDir::new().unwrap();
+        let migration_dir = temp_dir.path().join("migrations");
+        let target_file = temp_dir.path().join("test.fdml");
+        fs::create_dir_all(&migration_dir).unwrap();
+
+        create_test_fdml_file(&target_file);
+
+        let migration1 = Migration {
+            id: "001_add_link".to_string(),
+            title: Some("Add Link".to_string()),
+            author: None,
+            date: None,
+            description: None,
+            up: vec![MigrationOperation::AddTraceabilityLink {
+                link_id: Some("auth_impl".to_string()),
+                from: "user_auth".to_string(),
+                to: "src/auth.py:login".to_string(),
+                relation: "implements".to_string(),
+                description: None,
+                confidence: Some(0.6),
+                verified: Some(false),
+            }],
+            down: vec![MigrationOperation::RemoveTraceabilityLink {
+                link_id: "auth_impl".to_string(),
+            }],
+            dependencies: None,
+            design_impact: None,
+            squash: None,
+        };
+
+        let migration2 = Migration {
+            id: "002_verify_link".to_string(),
+            title: Some("Verify Link".to_string()),
+            author: None,
+            date: None,
+            description: None,
+            up: vec![MigrationOperation::UpdateTraceabilityLink {
+                link_id: "auth_impl".to_string(),
+                changes: TraceabilityChanges {
+                    relation: Some("verifies".to_string()),
+                    confidence: Some(1.0),
+                    verified: Some(true),
+                },
+            }],
+            down: vec![MigrationOperation::UpdateTraceabilityLink {
+                link_id: "auth_impl".to_string(),
+                changes: TraceabilityChanges {
+                    relation: Some("implements".to_string()),
+                    confidence: Some(0.6),
+                    verified: Some(false),
+                },
+            }],
+            dependencies: Some(vec!["001_add_link".to_string()]),
+            design_impact: None,
+            squash: None,
+        };
+
+        create_test_migration_file(&migration_dir, "001_add_link.yaml", &migration1);
+        create_test_migration_file(&migration_dir, "002_verify_link.yaml", &migration2);
+
+        let runner = MigrationRunner::new(&migration_dir).with_target_file(&target_file);
+        let applied = runner.apply_migrations(false).unwrap();
+        assert_eq!(applied.len(), 2);
+
+        let content = fs::read_to_string(&target_file).unwrap();
+        let document: FdmlDocument = serde_yaml::from_str(&content).unwrap();
+        assert_eq!(document.traceability.len(), 1);
+
+        let link = &document.traceability[0];
+        assert_eq!(link.link_id(), "auth_impl");
+        assert_eq!(link.relation, "verifies");
+        assert_eq!(link.confidence, Some(1.0));
+        assert_eq!(link.verified, Some(true));
+
+        // Rolling back both migrations removes the link again
+        let rolled_back = runner.rollback_migrations(2, false).unwrap();
+        assert_eq!(rolled_back.len(), 2);
+
+        let content = fs::read_to_string(&target_file).unwrap();
+        let document: FdmlDocument = serde_yaml::from_str(&content).unwrap();
+        assert!(document.traceability.is_empty());
+    }
+
+    #[test]
+    fn test_derived_traceability_link_id() {
+        let mut document = FdmlDocument::default();
+        document.traceability.push(Traceability {
+            id: None,
+            from: "user_auth".to_string(),
+            to: "login".to_string(),
+            relation: "implements".to_string(),
+            description: None,
+            confidence: None,
+            verified: None,
+            metadata: None,
+        });
+
+        let runner = MigrationRunner::new("unused");
+        let update = MigrationOperation::UpdateTraceabilityLink {
+            link_id: "user_auth:implements:login".to_string(),
+            changes: TraceabilityChanges {
+                relation: Some("verifies".to_string()),
+                confidence: None,
+                verified: None,
+            },
+        };
+        runner.execute_operation(&update, &mut document).unwrap();
+
+        // The derived id is pinned so the link stays addressable after the relation change
+        assert_eq!(document.traceability[0].link_id(), "user_auth:implements:login");
+        assert_eq!(document.traceability[0].relation, "verifies");
+
+        let remove = MigrationOperation::RemoveTraceabilityLink {
+            link_id: "missing".to_string(),
+        };
+        assert!(runner.execute_operation(&remove, &mut document).is_err());
+    }
+
+    #[test]
+    fn test_traceability_operation_validation() {
+        let runner = MigrationRunner::new("unused");
+
+        let valid_op = MigrationOperation::AddTraceabilityLink {
+            link_id: None,
+            from: "user_auth".to_string(),
+            to: "login".to_string(),
+            relation: "implements".to_string(),
+            description: None,
+            confidence: Some(0.9),
+            verified: None,
+        };
+        assert!(runner.validate_operation(&valid_op).is_ok());
+
+        // Confidence out of range
+        let invalid_confidence = MigrationOperation::AddTraceabilityLink {
+            link_id: None,
+            from: "user_auth".to_string(),
+            to: "login".to_string(),
+            relation: "implements".to_string(),
+            description: None,
+            confidence: Some(1.5),
+            verified: None,
+        };
+        assert!(runner.validate_operation(&invalid_confidence).is_err());
+
+        // Update without any change
+        let empty_update = MigrationOperation::UpdateTraceabilityLink {
+            link_id: "auth_impl".to_string(),
+            changes: TraceabilityChanges {
+                relation: None,
+                confidence: None,
+                verified: None,
+            },
+        };
+        assert!(runner.validate_operation(&empty_update).is_err());
+
+        let invalid_remove = MigrationOperation::RemoveTraceabilityLink {
+            link_id: " ".to_string(),
+        };
+        assert!(runner.validate_operation(&invalid_remove).is_err());
+    }
+
+    #[test]
+    fn test_design_impact_parsing_and_history() {
+        let temp_dir = TempDir::new().unwrap();
+        let migration_dir = temp_dir.path().join("migrations");
+        fs::create_dir_all(&migration_dir).unwrap();
+
+        fs::write(migration_dir.join("001_modular_orders.yaml"), r#"
+id: "001_modular_orders"
+title: "Split order service"
+design_impact:
+  principles_added:
+    - element_id: order_service
+      principle: Mo
+      rationale: "Independent deployment"
+  strategy_changes:
+    - element_id: create_order
+      principle: Op
+      old_strategy: "Pessimistic locking"
+      new_strategy: "Optimistic locking"
+up: []
+down: []
+"#).unwrap();
+
+        fs::write(migration_dir.join("002_drop_cache.yaml"), r#"
+id: "002_drop_cache"
+dependencies: ["001_modular_orders"]
+design_impact:
+  principles_removed:
+    - element_id: order_service
+      principle: Rc
+      reason: "Cache invalidation too costly"
+up: []
+down: []
+"#).unwrap();
+
+        let runner = MigrationRunner::new(&migration_dir);
+        let migrations = runner.load_migrations().unwrap();
+        let impact = migrations["001_modular_orders"].design_impact.as_ref().unwrap();
+        assert_eq!(impact.principles_added.len(), 1);
+        assert_eq!(impact.strategy_changes[0].new_strategy.as_deref(), Some("Optimistic locking"));
+        assert!(impact.principles_removed.is_empty());
+
+        let status = runner.migration_status().unwrap();
+        assert_eq!(status.design_impacts.len(), 2);
+
+        let history = runner.principle_history().unwrap();
+        let order_service = &history["order_service"];
+        assert_eq!(order_service.len(), 2);
+        assert_eq!(order_service[0].migration_id, "001_modular_orders");
+        assert!(matches!(order_service[0].change, PrincipleChange::Added { .. }));
+        assert_eq!(order_service[1].principle, "Rc");
+        assert!(matches!(order_service[1].change, PrincipleChange::Removed { .. }));
+        assert!(!order_service[1].applied);
+        assert_eq!(history["create_order"].len(), 1);
+    }
+
+    #[test]
+    fn test_design_impact_unknown_principle() {
+        let temp_dir = TempDir::new().unwrap();
+        let migration_dir = temp_dir.path().join("migrations");
+        fs::create_dir_all(&migration_dir).unwrap();
+
+        fs::write(migration_dir.join("001_bad_principle.yaml"), r#"
+id: "001_bad_principle"
+design_impact:
+  principles_added:
+    - element_id: order_service
+      principle: Zz
+up: []
+down: []
+"#).unwrap();
+
+        let runner = MigrationRunner::new(&migration_dir);
+        let result = runner.load_migrations();
+        assert!(result.is_err());
+        assert!(result.unwrap_err().to_string().contains("unknown principle 'Zz'"));
+    }
+
+    #[test]
+    fn test_applied_migration_records() {
+        let temp_dir = TempDir::new().unwrap();
+        let migration_dir = temp_dir.path().join("migrations");
+        let target_file = temp_dir.path().join("test.fdml");
+        fs::create_dir_all(&migration_dir).unwrap();
+
+        create_test_fdml_file(&target_file);
+
+        let migration = Migration {
+            id: "001_add_feature".to_string(),
+            title: Some("Add Feature".to_string()),
+            author: None,
+            date: None,
+            description: None,
+            up: vec![MigrationOperation::AddFeature {
+                id: "new_feature".to_string(),
+                title: "New Feature".to_string(),
+                description: None,
+                scenarios: None,
+                entity: None,
+                operation: None,
+                roles: None,
+            }],
+            down: vec![MigrationOperation::RemoveFeature {
+                id: "new_feature".to_string(),
+            }],
+            dependencies: None,
+            design_impact: None,
+            squash: None,
+        };
+
+        create_test_migration_file(&migration_dir, "001_add_feature.yaml", &migration);
+
+        let runner = MigrationRunner::new(&migration_dir).with_target_file(&target_file);
+        runner.apply_migrations(false).unwrap();
+
+        let state_content = fs::read_to_string(migration_dir.join(".migration_state.json")).unwrap();
+        let state: MigrationState = serde_json::from_str(&state_content).unwrap();
+        let record = state.applied_record("001_add_feature").unwrap();
+        assert_eq!(record.checksum.len(), 64);
+        assert_eq!(record.target_file.as_deref(), Some(target_file.display().to_string().as_str()));
+        assert!(runner.validate_integrity().unwrap().is_empty());
+
+        // Rolling back drops the record together with the id
+        runner.rollback_migrations(1, false).unwrap();
+        let state_content = fs::read_to_string(migration_dir.join(".migration_state.json")).unwrap();
+        let state: MigrationState = serde_json::from_str(&state_content).unwrap();
+        assert!(state.applied_records.is_empty());
+    }
+
+    #[test]
+    fn test_modified_and_missing_migrations_detected() {
+        let temp_dir = TempDir::new().unwrap();
+        let migration_dir = temp_dir.path().join("migrations");
+        let target_file = temp_dir.path().join("test.fdml");
+        fs::create_dir_all(&migration_dir).unwrap();
+
+        create_test_fdml_file(&target_file);
+
+        let mut migration = Migration {
+            id: "001_add_feature".to_string(),
+            title: Some("Add Feature".to_string()),
+            author: None,
+            date: None,
+            description: None,
+            up: vec![MigrationOperation::AddFeature {
+                id: "new_feature".to_string(),
+                title: "New Feature".to_string(),
+                description: None,
+                scenarios: None,
+                entity: None,
+                operation: None,
+                roles: None,
+            }],
+            down: vec![],
+            dependencies: None,
+            design_impact: None,
+            squash: None,
+        };
+        create_test_migration_file(&migration_dir, "001_add_feature.yaml", &migration);
+
+        let runner = MigrationRunner::new(&migration_dir).with_target_file(&target_file);
+        runner.apply_migrations(false).unwrap();
+
+        // Edit the applied migration and add a new pending one
+        migration.title = Some("Edited after the fact".to_string());
+        create_test_migration_file(&migration_dir, "001_add_feature.yaml", &migration);
+        let pending = Migration {
+            id: "002_add_entity".to_string(),
+            title: None,
+            author: None,
+            date: None,
+            description: None,
+            up: vec![MigrationOperation::AddEntity {
+                id: "order".to_string(),
+                name: "Order".to_string(),
+                description: None,
+            }],
+            down: vec![],
+            dependencies: None,
+            design_impact: None,
+            squash: None,
+        };
+        create_test_migration_file(&migration_dir, "002_add_entity.yaml", &pending);
+
+        let issues = runner.validate_integrity().unwrap();
+        assert_eq!(issues.len(), 1);
+        assert!(matches!(&issues[0], IntegrityIssue::Modified { id, .. } if id == "001_add_feature"));
+
+        let result = runner.apply_migrations(false);
+        assert!(result.is_err());
+        assert!(result.unwrap_err().to_string().contains("was modified after it was applied"));
+
+        // Status still works and reports the issue
+        let status = runner.migration_status().unwrap();
+        assert_eq!(status.integrity_issues.len(), 1);
+
+        fs::remove_file(migration_dir.join("001_add_feature.yaml")).unwrap();
+        let issues = runner.validate_integrity().unwrap();
+        assert_eq!(issues, vec![IntegrityIssue::Missing { id: "001_add_feature".to_string() }]);
+    }
+
+    #[test]
+    fn test_reordered_and_legacy_state() {
+        let temp_dir = TempDir::new().unwrap();
+        let migration_dir = temp_dir.path().join("migrations");
+        fs::create_dir_all(&migration_dir).unwrap();
+
+        let first = Migration {
+            id: "001_base".to_string(),
+            title: None,
+            author: None,
+            date: None,
+            description: None,
+            up: vec![],
+            down: vec![],
+            dependencies: None,
+            design_impact: None,
+            squash: None,
+        };
+        let second = Migration {
+            id: "002_child".to_string(),
+            title: None,
+            author: None,
+            date: None,
+            description: None,
+            up: vec![],
+            down: vec![],
+            dependencies: Some(vec!["001_base".to_string()]),
+            design_impact: None,
+            squash: None,
+        };
+        create_test_migration_file(&migration_dir, "001_base.yaml", &first);
+        create_test_migration_file(&migration_dir, "002_child.yaml", &second);
+
+        // State written by an older version: no checksum records, child applied first
+        fs::write(migration_dir.join(".migration_state.json"), r#"{
+  "applied_migrations": ["002_child", "001_base"],
+  "last_migration": "001_base",
+  "created_at": "2024-01-01T00:00:00Z",
+  "updated_at": "2024-01-01T00:00:00Z"
+}"#).unwrap();
+
+        let runner = MigrationRunner::new(&migration_dir);
+        let issues = runner.validate_integrity().unwrap();
+        assert_eq!(issues, vec![IntegrityIssue::Reordered {
+            id: "002_child".to_string(),
+            dependency: "001_base".to_string(),
+        }]);
+    }
+
+    fn entity_migration(id: &str, entity_id: &str, dependencies: Option<Vec<&str>>) -> Migration {
+        Migration {
+            id: id.to_string(),
+            title: None,
+            author: None,
+            date: None,
+            description: None,
+            up: vec![MigrationOperation::AddEntity {
+                id: entity_id.to_string(),
+                name: entity_id.to_string(),
+                description: None,
+            }],
+            down: vec![MigrationOperation::RemoveEntity {
+                id: entity_id.to_string(),
+            }],
+            dependencies: dependencies.map(|deps| deps.into_iter().map(String::from).collect()),
+            design_impact: None,
+            squash: None,
+        }
+    }
+
+    #[test]
+    fn test_apply_to_target_migration() {
+        let temp_dir = TempDir::new().unwrap();
+        let migration_dir = temp_dir.path().join("migrations");
+        let target_file = temp_dir.path().join("test.fdml");
+        fs::create_dir_all(&migration_dir).unwrap();
+        create_test_fdml_file(&target_file);
+
+        create_test_migration_file(&migration_dir, "001_order.yaml", &entity_migration("001_order", "order", None));
+        create_test_migration_file(&migration_dir, "002_invoice.yaml", &entity_migration("002_invoice", "invoice", Some(vec!["001_order"])));
+        create_test_migration_file(&migration_dir, "003_coupon.yaml", &entity_migration("003_coupon", "coupon", None));
+
+        let runner = MigrationRunner::new(&migration_dir).with_target_file(&target_file);
+
+        // Only the target and its dependencies are applied
+        let applied = runner.apply_migrations_to(Some("002_invoice"), false).unwrap();
+        assert_eq!(applied, vec!["001_order".to_string(), "002_invoice".to_string()]);
+
+        // Already applied target is a no-op
+        assert!(runner.apply_migrations_to(Some("002_invoice"), false).unwrap().is_empty());
+
+        let result = runner.apply_migrations_to(Some("999_missing"), false);
+        assert!(result.unwrap_err().to_string().contains("not found"));
+
+        let applied = runner.apply_migrations(false).unwrap();
+        assert_eq!(applied, vec!["003_coupon".to_string()]);
+    }
+
+    #[test]
+    fn test_rollback_to_target_migration() {
+        let temp_dir = TempDir::new().unwrap();
+        let migration_dir = temp_dir.path().join("migrations");
+        let target_file = temp_dir.path().join("test.fdml");
+        fs::create_dir_all(&migration_dir).unwrap();
+        create_test_fdml_file(&target_file);
+
+        create_test_migration_file(&migration_dir, "001_order.yaml", &entity_migration("001_order", "order", None));
+        create_test_migration_file(&migration_dir, "002_coupon.yaml", &entity_migration("002_coupon", "coupon", None));
+        create_test_migration_file(&migration_dir, "003_invoice.yaml", &entity_migration("003_invoice", "invoice", Some(vec!["001_order"])));
+
+        let runner = MigrationRunner::new(&migration_dir).with_target_file(&target_file);
+        for id in ["001_order", "002_coupon", "003_invoice"] {
+            runner.apply_migrations_to(Some(id), false).unwrap();
+        }
+
+        // Rolling back the base alone would leave its dependent applied
+        fs::write(migration_dir.join(".migration_state.json"), {
+            let mut state: MigrationState = serde_json::from_str(
+                &fs::read_to_string(migration_dir.join(".migration_state.json")).unwrap()
+            ).unwrap();
+            state.applied_migrations = vec!["002_coupon".to_string(), "003_invoice".to_string(), "001_order".to_string()];
+            state.applied_records.clear();
+            serde_json::to_string(&state).unwrap()
+        }).unwrap();
+        let result = runner.rollback_migrations(1, true);
+        assert!(result.unwrap_err().to_string().contains("Cannot roll back '001_order'"));
+
+        let result = runner.rollback_migrations_to("999_missing", false);
+        assert!(result.unwrap_err().to_string().contains("is not applied"));
+
+        // Everything applied after the target is rolled back, dependents first
+        let rolled_back = runner.rollback_migrations_to("002_coupon", false).unwrap();
+        assert_eq!(rolled_back, vec!["003_invoice".to_string(), "001_order".to_string()]);
+
+        let state: MigrationState = serde_json::from_str(
+            &fs::read_to_string(migration_dir.join(".migration_state.json")).unwrap()
+        ).unwrap();
+        assert_eq!(state.applied_migrations, vec!["002_coupon".to_string()]);
+    }
+
+    #[test]
+    fn test_independent_migrations_apply_in_id_order() {
+        let temp_dir = TempDir::new().unwrap();
+        let migration_dir = temp_dir.path().join("migrations");
+        fs::create_dir_all(&migration_dir).unwrap();
+
+        let ids = ["003_coupon", "001_order", "004_refund", "002_invoice"];
+        for id in ids {
+            create_test_migration_file(&migration_dir, &format!("{}.yaml", id), &entity_migration(id, id, None));
+        }
+        create_test_migration_file(&migration_dir, "000_late.yaml", &entity_migration("000_late", "late", Some(vec!["004_refund"])));
+
+        let runner = MigrationRunner::new(&migration_dir);
+        let migrations = runner.load_migrations().unwrap();
+        let pending = runner.get_pending_migrations(&migrations, &MigrationState::default()).unwrap();
+        assert_eq!(pending, vec!["001_order", "002_invoice", "003_coupon", "004_refund", "000_late"]);
+    }
+
+    #[test]
+    fn test_create_migration_template() {
+        let temp_dir = TempDir::new().unwrap();
+        let migration_dir = temp_dir.path().join("migrations");
+        let runner = MigrationRunner::new(&migration_dir);
+
+        let first = runner.create_migration("Add Orders!").unwrap();
+        let file_name = first.file_name().unwrap().to_str().unwrap().to_string();
+        assert!(file_name.ends_with("_add_orders.yaml"));
+        assert_eq!(file_name.split('_').next().unwrap().len(), 14);
+
+        let migrations = runner.load_migrations().unwrap();
+        let first_id = file_name.trim_end_matches(".yaml").to_string();
+        let migration = &migrations[&first_id];
+        assert_eq!(migration.title.as_deref(), Some("Add Orders!"));
+        assert!(migration.up.is_empty() && migration.down.is_empty());
+        assert_eq!(migration.dependencies, Some(vec![]));
+
+        // The next migration depends on the current head
+        let second = runner.create_migration("add invoices").unwrap();
+        let content = fs::read_to_string(&second).unwrap();
+        let migration: Migration = serde_yaml::from_str(&content).unwrap();
+        assert_eq!(migration.dependencies, Some(vec![first_id]));
+        assert!(content.contains("# - type: add_entity"));
+
+        assert!(runner.create_migration("  --  ").is_err());
+    }
+
+    #[test]
+    fn test_verify_migrations_reports_hand_edits() {
+        let temp_dir = TempDir::new().unwrap();
+        let migration_dir = temp_dir.path().join("migrations");
+        let target_file = temp_dir.path().join("test.fdml");
+        let baseline_file = temp_dir.path().join("baseline.fdml");
+        fs::create_dir_all(&migration_dir).unwrap();
+        create_test_fdml_file(&target_file);
+        create_test_fdml_file(&baseline_file);
+
+        create_test_migration_file(&migration_dir, "001_order.yaml", &entity_migration("001_order", "order", None));
+        create_test_migration_file(&migration_dir, "002_invoice.yaml", &entity_migration("002_invoice", "invoice", Some(vec!["001_order"])));
+
+        let runner = MigrationRunner::new(&migration_dir).with_target_file(&target_file);
+        runner.apply_migrations(false).unwrap();
+
+        let verification = runner.verify_migrations(Some(&baseline_file)).unwrap();
+        assert_eq!(verification.replayed, vec!["001_order", "002_invoice"]);
+        assert!(verification.pending.is_empty());
+        assert!(verification.drift.is_empty());
+
+        // Without the baseline, the hand-written parts of the spec are reported
+        let verification = runner.verify_migrations(None).unwrap();
+        assert!(verification.drift.contains(&SpecDrift::Unexpected { kind: "entity", id: "user".to_string() }));
+        assert!(verification.drift.contains(&SpecDrift::Unexpected { kind: "feature", id: "user_auth".to_string() }));
+
+        // Edit the spec by hand
+        let mut document = crate::parser::parse_fdml_yaml(&fs::read_to_string(&target_file).unwrap()).unwrap();
+        document.entities.retain(|e| e.id != "invoice");
+        document.entities.iter_mut().find(|e| e.id == "order").unwrap().description = Some("Edited".to_string());
+        document.actions.push(Action {
+            id: "cancel_order".to_string(),
+            name: None,
+            description: None,
+            input: None,
+            output: None,
+            side_effects: None,
+            preconditions: None,
+            postconditions: None,
+            exceptions: None,
+            design_principles: None,
+            execution_strategies: None,
+        });
+        fs::write(&target_file, serde_yaml::to_string(&document).unwrap()).unwrap();
+
+        let verification = runner.verify_migrations(Some(&baseline_file)).unwrap();
+        assert_eq!(verification.drift, vec![
+            SpecDrift::Modified { kind: "entity", id: "order".to_string() },
+            SpecDrift::Missing { kind: "entity", id: "invoice".to_string() },
+            SpecDrift::Unexpected { kind: "action", id: "cancel_order".to_string() },
+        ]);
+    }
+
+    #[test]
+    fn test_fold_operations_matches_replay() {
+        let up = vec![
+            MigrationOperation::AddEntity { id: "draft".to_string(), name: "Draft".to_string(), description: None },
+            MigrationOperation::AddField {
+                entity_id: "draft".to_string(),
+                field_name: "body".to_string(),
+                field_type: "string".to_string(),
+                required: None,
+                default: None,
+            },
+            MigrationOperation::AddEntity { id: "order".to_string(), name: "Order".to_string(), description: None },
+            MigrationOperation::ModifyEntity {
+                id: "order".to_string(),
+                changes: EntityChanges { name: Some("Purchase Order".to_string()), description: None, add_fields: None, remove_fields: None },
+            },
+            MigrationOperation::AddField {
+                entity_id: "order".to_string(),
+                field_name: "total".to_string(),
+                field_type: "decimal".to_string(),
+                required: Some(true),
+                default: None,
+            },
+            MigrationOperation::RemoveEntity { id: "draft".to_string() },
+            MigrationOperation::AddField {
+                entity_id: "user".to_string(),
+                field_name: "nickname".to_string(),
+                field_type: "string".to_string(),
+                required: None,
+                default: None,
+            },
+            MigrationOperation::RemoveField { entity_id: "user".to_string(), field_name: "nickname".to_string() },
+            MigrationOperation::AddTraceabilityLink {
+                link_id: None,
+                from: "user_auth".to_string(),
+                to: "order".to_string(),
+                relation: "uses".to_string(),
+                description: None,
+                confidence: None,
+                verified: None,
+            },
+            MigrationOperation::UpdateTraceabilityLink {
+                link_id: "user_auth:uses:order".to_string(),
+                changes: TraceabilityChanges { relation: Some("creates".to_string()), confidence: Some(0.9), verified: None },
+            },
+        ];
+
+        let folded = fold_operations(&up);
+        assert_eq!(folded.len(), 3);
+        assert!(matches!(&folded[0], MigrationOperation::AddEntity { id, name, .. } if id == "order" && name == "Purchase Order"));
+        assert!(matches!(&folded[1], MigrationOperation::AddField { field_name, .. } if field_name == "total"));
+        assert!(matches!(&folded[2], MigrationOperation::AddTraceabilityLink { link_id: Some(link_id), relation, .. }
+            if link_id == "user_auth:uses:order" && relation == "creates"));
+
+        // Folding must not change the resulting document
+        let temp_dir = TempDir::new().unwrap();
+        let target_file = temp_dir.path().join("test.fdml");
+        create_test_fdml_file(&target_file);
+        let base = crate::parser::parse_fdml_yaml(&fs::read_to_string(&target_file).unwrap()).unwrap();
+        let runner = MigrationRunner::new(temp_dir.path());
+
+        let mut replayed = base.clone();
+        for operation in &up {
+            runner.execute_operation(operation, &mut replayed).unwrap();
+        }
+        let mut folded_document = base.clone();
+        for operation in &folded {
+            runner.execute_operation(operation, &mut folded_document).unwrap();
+        }
+        assert_eq!(replayed, folded_document);
+    }
+
+    #[test]
+    fn test_squash_migrations_keeps_state_valid() {
+        let temp_dir = TempDir::new().unwrap();
+        let migration_dir = temp_dir.path().join("migrations");
+        let target_file = temp_dir.path().join("test.fdml");
+        fs::create_dir_all(&migration_dir).unwrap();
+        create_test_fdml_file(&target_file);
+        let original_spec = fs::read_to_string(&target_file).unwrap();
+
+        create_test_migration_file(&migration_dir, "001_order.yaml", &entity_migration("001_order", "order", None));
+        let mut total = entity_migration("002_total", "unused", Some(vec!["001_order"]));
+        total.up = vec![MigrationOperation::AddField {
+            entity_id: "order".to_string(),
+            field_name: "total".to_string(),
+            field_type: "decimal".to_string(),
+            required: None,
+            default: None,
+        }];
+        total.down = vec![MigrationOperation::RemoveField { entity_id: "order".to_string(), field_name: "total".to_string() }];
+        create_test_migration_file(&migration_dir, "002_total.yaml", &total);
+        create_test_migration_file(&migration_dir, "003_coupon.yaml", &entity_migration("003_coupon", "coupon", None));
+        create_test_migration_file(&migration_dir, "004_invoice.yaml", &entity_migration("004_invoice", "invoice", Some(vec!["002_total"])));
+        let invoice_path = migration_dir.join("004_invoice.yaml");
+        let invoice = fs::read_to_string(&invoice_path).unwrap();
+        fs::write(&invoice_path, format!("# Invoices need order totals\n{}", invoice)).unwrap();
+
+        let runner = MigrationRunner::new(&migration_dir).with_target_file(&target_file);
+        runner.apply_migrations(false).unwrap();
+        let migrated_spec = fs::read_to_string(&target_file).unwrap();
+        let other_environment_state = fs::read_to_string(migration_dir.join(".migration_state.json")).unwrap();
+
+        // Dry run leaves everything in place
+        runner.squash_migrations("001_order", "003_coupon", true).unwrap();
+        assert!(migration_dir.join("001_order.yaml").exists());
+
+        let squashed = runner.squash_migrations("001_order", "003_coupon", false).unwrap();
+        assert_eq!(squashed.id, "003_coupon_squashed");
+        assert_eq!(squashed.up.len(), 3);
+        assert_eq!(squashed.squash.as_ref().unwrap().replaces, vec!["001_order", "002_total", "003_coupon"]);
+        assert!(!migration_dir.join("002_total.yaml").exists());
+
+        let migrations = runner.load_migrations().unwrap();
+        assert_eq!(migrations.len(), 2);
+        assert_eq!(migrations["004_invoice"].dependencies, Some(vec!["003_coupon_squashed".to_string()]));
+        let invoice = fs::read_to_string(&invoice_path).unwrap();
+        assert!(invoice.starts_with("# Invoices need order totals\n"), "{}", invoice);
+
+        let state: MigrationState = serde_json::from_str(
+            &fs::read_to_string(migration_dir.join(".migration_state.json")).unwrap()
+        ).unwrap();
+        assert_eq!(state.applied_migrations, vec!["003_coupon_squashed", "004_invoice"]);
+        assert!(runner.validate_integrity().unwrap().is_empty());
+
+        // An environment that applied the original range still has a valid state
+        fs::write(migration_dir.join(".migration_state.json"), other_environment_state).unwrap();
+        assert!(runner.validate_integrity().unwrap().is_empty());
+        assert_eq!(runner.migration_status().unwrap().pending_count, 0);
+
+        // And the squashed down operations restore the original spec
+        runner.rollback_migrations(2, false).unwrap();
+        let restored = crate::parser::parse_fdml_yaml(&fs::read_to_string(&target_file).unwrap()).unwrap();
+        assert_eq!(restored, crate::parser::parse_fdml_yaml(&original_spec).unwrap());
+
+        runner.apply_migrations(false).unwrap();
+        let reapplied = crate::parser::parse_fdml_yaml(&fs::read_to_string(&target_file).unwrap()).unwrap();
+        assert_eq!(reapplied, crate::parser::parse_fdml_yaml(&migrated_spec).unwrap());
+    }
+
+    #[test]
+    fn test_squash_rejects_partially_applied_range() {
+        let temp_dir = TempDir::new().unwrap();
+        let migration_dir = temp_dir.path().join("migrations");
+        let target_file = temp_dir.path().join("test.fdml");
+        fs::create_dir_all(&migration_dir).unwrap();
+        create_test_fdml_file(&target_file);
+
+        create_test_migration_file(&migration_dir, "001_order.yaml", &entity_migration("001_order", "order", None));
+        create_test_migration_file(&migration_dir, "002_coupon.yaml", &entity_migration("002_coupon", "coupon", None));
+
+        let runner = MigrationRunner::new(&migration_dir).with_target_file(&target_file);
+        runner.apply_migrations_to(Some("001_order"), false).unwrap();
+
+        let result = runner.squash_migrations("001_order", "002_coupon", false);
+        assert!(result.unwrap_err().to_string().contains("partially applied"));
+
+        let result = runner.squash_migrations("002_coupon", "001_order", false);
+        assert!(result.unwrap_err().to_string().contains("must come before"));
+    }
+
+    #[test]
+    fn test_backup_list_restore_and_prune() {
+        let temp_dir = TempDir::new().unwrap();
+        let migration_dir = temp_dir.path().join("migrations");
+        let target_file = temp_dir.path().join("test.fdml");
+        fs::create_dir_all(&migration_dir).unwrap();
+        create_test_fdml_file(&target_file);
+        let original_spec = fs::read_to_string(&target_file).unwrap();
+
+        create_test_migration_file(&migration_dir, "001_order.yaml", &entity_migration("001_order", "order", None));
+        create_test_migration_file(&migration_dir, "002_coupon.yaml", &entity_migration("002_coupon", "coupon", None));
+
+        let runner = MigrationRunner::new(&migration_dir).with_target_file(&target_file);
+        runner.apply_migrations_to(Some("001_order"), false).unwrap();
+        runner.apply_migrations(false).unwrap();
+
+        // A backup written before runs were recorded
+        fs::write(migration_dir.join(".backups").join("backup_20200101_000000.fdml"), &original_spec).unwrap();
+
+        let backups = runner.list_backups().unwrap();
+        assert_eq!(backups.len(), 3);
+        assert!(backups[0].operation.is_none());
+        assert_eq!(backups[1].operation.as_deref(), Some("apply"));
+        assert_eq!(backups[1].migrations, vec!["001_order"]);
+        assert_eq!(backups[2].migrations, vec!["002_coupon"]);
+        assert_eq!(backups[2].state.as_ref().unwrap().applied_migrations, vec!["001_order"]);
+
+        // Restoring the first run's backup returns to the original spec and state
+        let first_run = backups[1].name.clone();
+        runner.restore_backup(&first_run).unwrap();
+        assert_eq!(fs::read_to_string(&target_file).unwrap(), original_spec);
+        let status = runner.migration_status().unwrap();
+        assert_eq!(status.applied_count, 0);
+        assert_eq!(status.pending_count, 2);
+
+        // The restore took its own backup of the migrated spec
+        let backups = runner.list_backups().unwrap();
+        assert_eq!(backups.len(), 4);
+        assert_eq!(backups[3].operation.as_deref(), Some("restore"));
+
+        assert!(runner.restore_backup("backup_missing").is_err());
+
+        let removed = runner.prune_backups(1).unwrap();
+        assert_eq!(removed.len(), 3);
+        assert_eq!(removed[1], first_run);
+        let backups = runner.list_backups().unwrap();
+        assert_eq!(backups.len(), 1);
+        assert_eq!(backups[0].operation.as_deref(), Some("restore"));
+    }
+
+    #[test]
+    fn test_spec_operation_syntax() {
+        let temp_dir = TempDir::new().unwrap();
+        let migration_dir = temp_dir.path().join("migrations");
+        let target_file = temp_dir.path().join("test.fdml");
+        fs::create_dir_all(&migration_dir).unwrap();
+        create_test_fdml_file(&target_file);
+
+        // Example from spec section 15.4, plus a tagged operation in the same file
+        fs::write(migration_dir.join("2025_07_24_001_add_crud_user.yaml"), r#"
+id: 2025_07_24_001_add_crud_user
+author: ara
+date: 2025-07-24T12:00:00Z
+description: Added CRUD features for User entity
+
+up:
+  - add_feature:
+      id: user.create
+      title: Create user
+      entity: user
+      operation: create
+      roles: [admin]
+
+  - add_feature:
+      id: user.read
+      title: View user
+      entity: user
+      operation: read
+      roles: [admin, support]
+
+  - type: add_field
+    entity_id: user
+    field_name: role
+    field_type: string
+
+down:
+  - remove_field: { entity_id: user, field_name: role }
+
+  - remove_feature:
+      id: user.create
+
+  - remove_feature:
+      id: user.read
+"#).unwrap();
+
+        let runner = MigrationRunner::new(&migration_dir).with_target_file(&target_file);
+        let migrations = runner.load_migrations().unwrap();
+        let migration = &migrations["2025_07_24_001_add_crud_user"];
+        assert_eq!(migration.author.as_deref(), Some("ara"));
+        assert_eq!(migration.date.as_deref(), Some("2025-07-24T12:00:00Z"));
+        assert_eq!(migration.up.len(), 3);
+        assert!(matches!(&migration.down[0], MigrationOperation::RemoveField { field_name, .. } if field_name == "role"));
+
+        let status = runner.migration_status().unwrap();
+        let authorship = &status.authorship["2025_07_24_001_add_crud_user"];
+        assert_eq!(authorship.to_string(), "by ara, 2025-07-24T12:00:00Z");
+
+        runner.apply_migrations(false).unwrap();
+        let document = crate::parser::parse_fdml_yaml(&fs::read_to_string(&target_file).unwrap()).unwrap();
+        let feature = document.features.iter().find(|f| f.id == "user.read").unwrap();
+        assert_eq!(feature.entity.as_deref(), Some("user"));
+        assert_eq!(feature.operation.as_deref(), Some("read"));
+        assert_eq!(feature.roles, Some(vec!["admin".to_string(), "support".to_string()]));
+
+        runner.rollback_migrations(1, false).unwrap();
+        let document = crate::parser::parse_fdml_yaml(&fs::read_to_string(&target_file).unwrap()).unwrap();
+        assert_eq!(document.features.len(), 1);
+    }
+
+    #[test]
+    fn test_unknown_spec_operation_rejected() {
+        let temp_dir = TempDir::new().unwrap();
+        let migration_dir = temp_dir.path().join("migrations");
+        fs::create_dir_all(&migration_dir).unwrap();
+
+        fs::write(migration_dir.join("001_bad.yaml"), r#"
+id: "001_bad"
+up:
+  - add_widget:
+      id: w
+down: []
+"#).unwrap();
+
+        let result = MigrationRunner::new(&migration_dir).load_migrations();
+        assert!(result.unwrap_err().to_string().contains("add_widget"));
+    }
+}
@@ -1,3 +1,4 @@
+use crate::principles::PrincipleGroup;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -37,6 +38,8 @@ pub struct System {
     pub description: Option<String>,
     pub components: Vec<String>,
     pub relationships: Vec<Relationship>,
+    pub design_principles: Option<DesignPrinciples>,
+    pub implementation_strategies: Option<Vec<PrincipleStrategy>>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -55,6 +58,8 @@ pub struct Entity {
     pub description: Option<String>,
     pub fields: Vec<Field>,
     pub relationships: Option<Vec<EntityRelationship>>,
+    pub design_principles: Option<DesignPrinciples>,
+    pub implementation_strategies: Option<Vec<PrincipleStrategy>>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -66,6 +71,7 @@ pub struct Field {
     pub required: Option<bool>,
     pub default: Option<Value>,
     pub constraints: Option<Vec<FieldConstraint>>,
+    pub design_hints: Option<PrincipleHint>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -94,6 +100,9 @@ pub struct Action {
     pub side_effects: Option<Vec<String>>,
     pub preconditions: Option<Vec<String>>,
     pub postconditions: Option<Vec<String>>,
+    pub exceptions: Option<Vec<ActionException>>,
+    pub design_principles: Option<DesignPrinciples>,
+    pub execution_strategies: Option<Vec<PrincipleStrategy>>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -111,6 +120,14 @@ pub struct Feature {
     pub scenarios: Vec<Scenario>,
     pub acceptance_criteria: Option<Vec<String>>,
     pub dependencies: Option<Vec<String>>,
+    /// Entity the feature operates on (migration spec 15.3.2)
+    pub entity: Option<String>,
+    /// Operation the feature performs on the entity, e.g. create or read
+    pub operation: Option<String>,
+    /// Roles allowed to use the feature
+    pub roles: Option<Vec<String>>,
+    /// Estimated implementation effort (for example story points), used by `fdml plan`
+    pub effort: Option<f64>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -129,6 +146,8 @@ pub struct Flow {
     pub name: String,
     pub description: Option<String>,
     pub steps: Vec<FlowStep>,
+    pub design_principles: Option<DesignPrinciples>,
+    pub optimization_strategies: Option<Vec<PrincipleStrategy>>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -137,6 +156,7 @@ pub struct FlowStep {
     pub action: String,
     pub description: Option<String>,
     pub conditions: Option<Vec<String>>,
+    pub execution_hints: Option<PrincipleHint>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -149,14 +169,109 @@ pub struct Constraint {
     pub rule: String,
     pub entities: Option<Vec<String>>,
     pub actions: Option<Vec<String>>,
+    pub design_principles: Option<DesignPrinciples>,
+    pub enforcement_strategy: Option<EnforcementStrategy>,
+}
+
+/// Design principle codes declared per group (extension 1.3.3, section 16.3)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DesignPrinciples {
+    pub structure: Option<Vec<String>>,
+    pub efficiency: Option<Vec<String>>,
+    pub semantics: Option<Vec<String>>,
+    pub distribution: Option<Vec<String>>,
+    pub planning: Option<Vec<String>>,
+    pub operability: Option<Vec<String>>,
+    pub reliability: Option<Vec<String>>,
+    pub security: Option<Vec<String>>,
+}
+
+impl DesignPrinciples {
+    /// Declared groups and their codes, in taxonomy order
+    pub fn groups(&self) -> Vec<(PrincipleGroup, &[String])> {
+        let declared = [
+            (PrincipleGroup::Structure, &self.structure),
+            (PrincipleGroup::Efficiency, &self.efficiency),
+            (PrincipleGroup::Semantics, &self.semantics),
+            (PrincipleGroup::Distribution, &self.distribution),
+            (PrincipleGroup::Planning, &self.planning),
+            (PrincipleGroup::Operability, &self.operability),
+            (PrincipleGroup::Reliability, &self.reliability),
+            (PrincipleGroup::Security, &self.security),
+        ];
+        declared
+            .into_iter()
+            .filter_map(|(group, codes)| codes.as_deref().map(|codes| (group, codes)))
+            .collect()
+    }
+
+    pub fn declares(&self, code: &str) -> bool {
+        self.groups().iter().any(|(_, codes)| codes.iter().any(|declared| declared == code))
+    }
+}
+
+/// How an element applies one of its principles (implementation, execution and optimization strategies)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PrincipleStrategy {
+    pub principle: String,
+    pub strategy: String,
+    pub rationale: Option<String>,
+}
+
+/// Principle-based hint on a field (`design_hints`) or flow step (`execution_hints`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PrincipleHint {
+    pub principle: String,
+    pub hint: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ActionException {
+    pub code: String,
+    pub message: Option<String>,
+    pub recovery_strategy: Option<RecoveryStrategy>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecoveryStrategy {
+    pub principle: String,
+    pub approach: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EnforcementStrategy {
+    pub principle: String,
+    pub implementation: String,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Traceability {
+    pub id: Option<String>,
     pub from: String,
     pub to: String,
     pub relation: String,
     pub description: Option<String>,
+    pub confidence: Option<f64>,
+    pub verified: Option<bool>,
+    pub metadata: Option<TraceabilityMetadata>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TraceabilityMetadata {
+    pub created_by: Option<String>,
+    pub created_at: Option<String>,
+    pub last_verified: Option<String>,
+}
+
+impl Traceability {
+    /// Stable identifier of the link: the explicit `id` if present,
+    /// otherwise derived from `from`, `relation` and `to`.
+    pub fn link_id(&self) -> String {
+        match &self.id {
+            Some(id) => id.clone(),
+            None => format!("{}:{}:{}", self.from, self.relation, self.to),
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -0,0 +1,453 @@
+//! Canonical formatting of FDML files.
+//!
+//! Sections and element keys are written in the order of the AST, with two-space
+//! indentation, plain scalars where YAML allows them and null fields left out.
+//! Comments stay attached to the node they precede or follow on the same line.
+//! Files in the native section syntax (`entity:` blocks) are rewritten to YAML.
+
+use std::collections::HashMap;
+use serde_yaml::{Mapping, Value};
+use crate::error::{FdmlError, Result};
+use super::outline::{outline, NodePath, OutlineLine, Segment};
+use super::yaml_edit::{indent_of, inline_span, parse_key, render_inline};
+use super::{parse_fdml, parse_fdml_yaml};
+
+/// Sections written once per element in the native syntax, with their YAML name
+const NATIVE_SECTIONS: &[(&str, &str)] = &[
+    ("entity", "entities"),
+    ("action", "actions"),
+    ("feature", "features"),
+    ("flow", "flows"),
+    ("constraint", "constraints"),
+    ("traceability", "traceability"),
+    ("generation_rule", "generation_rules"),
+];
+
+/// Format FDML source text into its canonical layout
+pub fn format_fdml(content: &str) -> Result<String> {
+    let (text, native) = if is_native(content) {
+        let document = parse_fdml(content)?;
+        (native_to_yaml(content), Some(serde_yaml::to_value(document)?))
+    } else {
+        parse_fdml_yaml(content)?;
+        (content.to_string(), None)
+    };
+
+    let mut value: Value = serde_yaml::from_str(&text)?;
+    // Only sections the file has, so the empty ones do not show up
+    if let (Some(parsed), Value::Mapping(sections)) = (&native, &mut value) {
+        for (key, section) in sections.iter_mut() {
+            if let Some(parsed_section) = parsed.get(key) {
+                fill_missing(section, parsed_section);
+            }
+        }
+    }
+    let expected = parse_fdml_yaml(&serde_yaml::to_string(&value)?)?;
+
+    let formatter = Formatter {
+        comments: collect_comments(&text),
+        order: serde_yaml::to_value(&expected)?,
+        lines: Vec::new(),
+    };
+    let formatted = formatter.render(&value);
+
+    // Formatting must never change what the file means
+    if parse_fdml_yaml(&formatted)? != expected {
+        return Err(FdmlError::simple_parser_error("Formatting would change the document"));
+    }
+    Ok(formatted)
+}
+
+type Path = NodePath;
+
+#[derive(Debug, Default)]
+struct Comments {
+    /// Comments at the top of the file, separated from the first node by a blank line
+    header: Vec<String>,
+    leading: HashMap<Path, Vec<String>>,
+    trailing: HashMap<Path, String>,
+    /// Comments after the last node
+    footer: Vec<String>,
+}
+
+/// Find the node each comment belongs to
+fn collect_comments(text: &str) -> Comments {
+    let mut comments = Comments::default();
+    let mut pending: Vec<String> = Vec::new();
+    let mut blank_after_pending = false;
+    let mut seen_content = false;
+
+    for (line, kind) in text.lines().zip(outline(text)) {
+        match kind {
+            OutlineLine::Continuation => {},
+            OutlineLine::Blank => blank_after_pending = !pending.is_empty(),
+            OutlineLine::Comment => {
+                if !seen_content && blank_after_pending {
+                    comments.header.append(&mut pending);
+                }
+                blank_after_pending = false;
+                pending.push(line.trim().to_string());
+            },
+            OutlineLine::Node(nodes) => {
+                if !seen_content && blank_after_pending {
+                    comments.header.append(&mut pending);
+                }
+                seen_content = true;
+                blank_after_pending = false;
+
+                if let Some(comment) = &nodes.comment {
+                    comments.trailing.insert(nodes.path().clone(), comment.clone());
+                }
+                if !pending.is_empty() {
+                    comments.leading.entry(nodes.paths[0].clone()).or_default().append(&mut pending);
+                }
+            },
+        }
+    }
+
+    comments.footer = pending;
+    comments
+}
+
+struct Formatter {
+    comments: Comments,
+    /// The parsed document, whose mappings give the canonical key order
+    order: Value,
+    lines: Vec<String>,
+}
+
+impl Formatter {
+    fn render(mut self, value: &Value) -> String {
+        if !self.comments.header.is_empty() {
+            let header = std::mem::take(&mut self.comments.header);
+            self.lines.extend(header);
+            self.lines.push(String::new());
+        }
+
+        if let Value::Mapping(sections) = value {
+            for (index, (key, value)) in self.ordered(&[], sections).into_iter().enumerate() {
+                if index > 0 {
+                    self.lines.push(String::new());
+                }
+                self.entry(vec![Segment::Key(key.clone())], &key, value, 0, "");
+            }
+        }
+
+        if !self.comments.footer.is_empty() {
+            let footer = std::mem::take(&mut self.comments.footer);
+            self.lines.push(String::new());
+            self.lines.extend(footer);
+        }
+
+        let mut text = self.lines.join("\n");
+        text.push('\n');
+        text
+    }
+
+    /// Non-null entries, known keys first in AST order, then the rest as written
+    fn ordered<'v>(&self, path: &[Segment], mapping: &'v Mapping) -> Vec<(String, &'v Value)> {
+        let mut reference = Some(&self.order);
+        for segment in path {
+            reference = match (reference, segment) {
+                (Some(Value::Mapping(entries)), Segment::Key(key)) => entries.get(key.as_str()),
+                (Some(Value::Sequence(items)), Segment::Index(index)) => items.get(*index),
+                _ => None,
+            };
+        }
+        let known: Vec<&Value> = match reference {
+            Some(Value::Mapping(entries)) => entries.keys().collect(),
+            _ => Vec::new(),
+        };
+
+        let mut entries: Vec<(String, &Value)> = mapping
+            .iter()
+            .filter(|(_, value)| !value.is_null())
+            .map(|(key, value)| (key_text(key), value))
+            .collect();
+        entries.sort_by_key(|(key, _)| {
+            known.iter().position(|k| k.as_str() == Some(key.as_str())).unwrap_or(known.len())
+        });
+        entries
+    }
+
+    fn leading(&mut self, path: &Path, indent: usize) {
+        if let Some(comments) = self.comments.leading.remove(path) {
+            let pad = " ".repeat(indent);
+            self.lines.extend(comments.iter().map(|comment| format!("{}{}", pad, comment)));
+        }
+    }
+
+    /// Push a line, followed by the trailing comment of `path` if it has one
+    fn push(&mut self, path: &Path, line: String) {
+        match self.comments.trailing.remove(path) {
+            Some(comment) => self.lines.push(format!("{}  {}", line, comment)),
+            None => self.lines.push(line),
+        }
+    }
+
+    /// Write `key: value` at `indent`; `prefix` replaces the indentation of the first line
+    fn entry(&mut self, path: Path, key: &str, value: &Value, indent: usize, prefix: &str) {
+        self.leading(&path, indent);
+        let pad = " ".repeat(indent);
+        let first = if prefix.is_empty() { pad.clone() } else { prefix.to_string() };
+
+        match render_inline(value) {
+            Some(inline) => self.scalar(&path, &format!("{}{}: ", first, key), &pad, &inline),
+            None => {
+                self.push(&path, format!("{}{}:", first, key));
+                self.nested(path, value, indent + 2);
+            },
+        }
+    }
+
+    fn item(&mut self, path: Path, value: &Value, indent: usize) {
+        self.leading(&path, indent);
+        let pad = " ".repeat(indent);
+
+        match (value, render_inline(value)) {
+            (_, Some(inline)) => self.scalar(&path, &format!("{}- ", pad), &pad, &inline),
+            (Value::Mapping(entries), None) => {
+                let dash = format!("{}- ", pad);
+                for (index, (key, value)) in self.ordered(&path, entries).into_iter().enumerate() {
+                    let mut child = path.clone();
+                    child.push(Segment::Key(key.clone()));
+                    let prefix = if index == 0 { dash.as_str() } else { "" };
+                    self.entry(child, &key, value, indent + 2, prefix);
+                }
+            },
+            (_, None) => {
+                self.push(&path, format!("{}-", pad));
+                self.nested(path, value, indent + 2);
+            },
+        }
+    }
+
+    fn nested(&mut self, path: Path, value: &Value, indent: usize) {
+        match value {
+            Value::Mapping(entries) => {
+                for (key, value) in self.ordered(&path, entries) {
+                    let mut child = path.clone();
+                    child.push(Segment::Key(key.clone()));
+                    self.entry(child, &key, value, indent, "");
+                }
+            },
+            Value::Sequence(items) => {
+                for (index, item) in items.iter().enumerate() {
+                    let mut child = path.clone();
+                    child.push(Segment::Index(index));
+                    self.item(child, item, indent);
+                }
+            },
+            _ => {},
+        }
+    }
+
+    /// Write a scalar whose first line starts with `first` and continues at `pad`
+    fn scalar(&mut self, path: &Path, first: &str, pad: &str, inline: &str) {
+        let mut lines = inline.lines();
+        self.push(path, format!("{}{}", first, lines.next().unwrap_or_default()));
+        self.lines.extend(lines.map(|line| format!("{}{}", pad, line)));
+    }
+}
+
+fn key_text(key: &Value) -> String {
+    match key {
+        Value::String(text) if parse_key(&format!("{}:", text)).is_some_and(|(parsed, _)| parsed == *text) => {
+            text.clone()
+        },
+        _ => render_inline(key).unwrap_or_default(),
+    }
+}
+
+/// Whether the text uses native sections, which YAML either rejects as duplicate keys
+/// or reads as unknown keys
+fn is_native(content: &str) -> bool {
+    match serde_yaml::from_str::<Value>(content) {
+        Ok(Value::Mapping(sections)) => NATIVE_SECTIONS
+            .iter()
+            .any(|(native, _)| matches!(sections.get(*native), Some(Value::Mapping(_)))),
+        Ok(_) => false,
+        Err(_) => parse_fdml(content).is_ok(),
+    }
+}
+
+/// Rewrite native `entity:`-style blocks into YAML section lists
+fn native_to_yaml(content: &str) -> String {
+    struct Block<'a> {
+        key: String,
+        head: &'a str,
+        leading: Vec<&'a str>,
+        body: Vec<&'a str>,
+    }
+
+    let mut blocks: Vec<Block> = Vec::new();
+    let mut pending: Vec<&str> = Vec::new();
+    for line in content.lines() {
+        let top_level = indent_of(line) == 0 && !line.trim().is_empty();
+        if top_level && !line.starts_with('#') {
+            let key = parse_key(line).map(|(key, _)| key).unwrap_or_default();
+            blocks.push(Block { key, head: line, leading: std::mem::take(&mut pending), body: Vec::new() });
+        } else if top_level || blocks.is_empty() {
+            pending.push(line);
+        } else {
+            let block = blocks.last_mut().unwrap();
+            block.body.append(&mut pending);
+            block.body.push(line);
+        }
+    }
+
+    let mut lines: Vec<String> = Vec::new();
+    let mut sections: Vec<(&str, Vec<String>)> = Vec::new();
+    for block in &blocks {
+        let Some((_, section)) = NATIVE_SECTIONS.iter().find(|(native, _)| *native == block.key) else {
+            lines.extend(block.leading.iter().map(|line| line.to_string()));
+            lines.push(block.head.to_string());
+            lines.extend(block.body.iter().map(|line| line.to_string()));
+            continue;
+        };
+
+        let position = match sections.iter().position(|(name, _)| name == section) {
+            Some(position) => position,
+            None => {
+                sections.push((section, Vec::new()));
+                sections.len() - 1
+            },
+        };
+        let item = &mut sections[position].1;
+        item.extend(block.leading.iter().filter(|line| !line.trim().is_empty()).map(|line| format!("  {}", line)));
+        let (_, head_end) = inline_span(block.head, block.head.find(':').map_or(0, |colon| colon + 1));
+        let head_comment = block.head[head_end..].trim();
+        if head_comment.starts_with('#') {
+            item.push(format!("  {}", head_comment));
+        }
+
+        let base = block.body.iter().find(|line| !line.trim().is_empty()).map_or(2, |line| indent_of(line));
+        let mut dashed = false;
+        for line in &block.body {
+            let trimmed = line.trim();
+            if trimmed.is_empty() {
+                item.push(String::new());
+            } else if !dashed && !trimmed.starts_with('#') {
+                item.push(format!("  - {}", &line[base.min(indent_of(line))..]));
+                dashed = true;
+            } else if indent_of(line) >= base {
+                item.push(format!("    {}", &line[base..]));
+            } else {
+                item.push(format!("    {}", trimmed));
+            }
+        }
+    }
+
+    for (section, items) in sections {
+        lines.push(format!("{}:", section));
+        lines.extend(items);
+    }
+    lines.extend(pending.iter().map(|line| line.to_string()));
+    let mut text = lines.join("\n");
+    text.push('\n');
+    text
+}
+
+/// Add what the native parser filled in but the text leaves out, such as empty lists
+fn fill_missing(value: &mut Value, parsed: &Value) {
+    match (value, parsed) {
+        (Value::Mapping(entries), Value::Mapping(parsed)) => {
+            for (key, parsed_value) in parsed {
+                match entries.get_mut(key) {
+                    Some(existing) => fill_missing(existing, parsed_value),
+                    None if !parsed_value.is_null() => {
+                        entries.insert(key.clone(), parsed_value.clone());
+                    },
+                    None => {},
+                }
+            }
+        },
+        (Value::Sequence(items), Value::Sequence(parsed)) => {
+            for (item, parsed_item) in items.iter_mut().zip(parsed) {
+                fill_missing(item, parsed_item);
+            }
+        },
+        (value @ Value::String(_), Value::Sequence(_)) => *value = parsed.clone(),
+        _ => {},
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_canonical_order_and_comments() {
+        let input = r#"# Shop specification
+
+features:
+  - title: "Checkout"   # shown in reports
+    id: checkout
+    scenarios: []
+entities:
+# Customers
+- fields:
+  - type: string
+    name: id
+    description: null
+  name: "Customer"
+  id: customer
+metadata:
+  author: Shop
+  version: "1.3"
+# end of spec
+"#;
+        let formatted = format_fdml(input).unwrap();
+        assert_eq!(formatted, r#"# Shop specification
+
+metadata:
+  version: '1.3'
+  author: Shop
+
+entities:
+  # Customers
+  - id: customer
+    name: Customer
+    fields:
+      - name: id
+        type: string
+
+features:
+  - id: checkout
+    title: Checkout  # shown in reports
+    scenarios: []
+
+# end of spec
+"#);
+        assert_eq!(format_fdml(&formatted).unwrap(), formatted);
+    }
+
+    #[test]
+    fn test_native_syntax_is_rewritten() {
+        let input = r#"
+# The user
+entity:
+  id: user
+  name: "User Entity"
+
+entity:
+  id: order
+"#;
+        let formatted = format_fdml(input).unwrap();
+        assert_eq!(formatted, r#"entities:
+  # The user
+  - id: user
+    name: User Entity
+    fields: []
+  - id: order
+    fields: []
+"#);
+    }
+
+    #[test]
+    fn test_block_scalars_and_unknown_keys() {
+        let input = "entities:\n  - id: user\n    x-owner: team\n    description: |\n      Line one\n      # not a comment\n    fields: []\n";
+        let formatted = format_fdml(input).unwrap();
+        assert_eq!(formatted, "entities:\n  - id: user\n    description: |\n      Line one\n      # not a comment\n    fields: []\n    x-owner: team\n");
+    }
+}
@@ -0,0 +1,207 @@
+use crate::parser::outline::{outline, NodePath, OutlineLine, Segment};
+use crate::validator::suggest::split_suggestion;
+
+/// An element definition, located by its id (or name, for fields)
+#[derive(Debug, Clone, PartialEq)]
+pub struct Symbol {
+    pub id: String,
+    pub kind: &'static str,
+    /// Path of the element, such as `entities[0]`
+    pub path: NodePath,
+    pub line: usize,
+    /// Byte columns of the id on `line`
+    pub columns: (usize, usize),
+    /// First and last line of the element
+    pub lines: (usize, usize),
+}
+
+impl Symbol {
+    /// Whether other elements can refer to this one by id
+    pub fn is_global(&self) -> bool {
+        !matches!(self.kind, "field" | "step")
+    }
+}
+
+/// A use of an element id
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reference {
+    pub id: String,
+    /// Kind of element expected, if the position only accepts one
+    pub expects: Option<&'static str>,
+    pub line: usize,
+    pub columns: (usize, usize),
+}
+
+/// Sections of the document as shown in the outline
+#[derive(Debug, Clone, PartialEq)]
+pub struct Section {
+    pub name: String,
+    pub lines: (usize, usize),
+}
+
+/// Where ids are defined and used in a document
+#[derive(Debug, Default)]
+pub struct DocumentIndex {
+    pub sections: Vec<Section>,
+    pub symbols: Vec<Symbol>,
+    pub references: Vec<Reference>,
+}
+
+impl DocumentIndex {
+    pub fn build(text: &str) -> Self {
+        let lines: Vec<&str> = text.lines().collect();
+        let outline = outline(text);
+        let mut index = Self::default();
+
+        for (line_number, kind) in outline.iter().enumerate() {
+            let OutlineLine::Node(nodes) = kind else {
+                continue;
+            };
+            let line = lines[line_number];
+
+            for path in &nodes.paths {
+                if let [Segment::Key(name)] = path.as_slice() {
+                    index.sections.push(Section {
+                        name: name.clone(),
+                        lines: (line_number, element_end(&outline, line_number, path)),
+                    });
+                }
+            }
+
+            let path = nodes.path();
+            let Some((value, columns)) = unquote(line, nodes.value) else {
+                continue;
+            };
+            let pattern = pattern_of(path);
+
+            if let Some(kind) = definition_kind(&pattern) {
+                let element = path[..path.len() - 1].to_vec();
+                let start = element_start(&outline, &element).unwrap_or(line_number);
+                index.symbols.push(Symbol {
+                    id: value.to_string(),
+                    kind,
+                    lines: (start, element_end(&outline, start, &element)),
+                    path: element,
+                    line: line_number,
+                    columns,
+                });
+            } else if let Some(expects) = reference_kind(&pattern) {
+                index.references.push(Reference { id: value.to_string(), expects, line: line_number, columns });
+            }
+        }
+        index
+    }
+
+    /// Id of the symbol or reference at a position
+    pub fn id_at(&self, line: usize, column: usize) -> Option<&str> {
+        let covers = |at: usize, (from, to): (usize, usize)| at == line && from <= column && column <= to;
+        self.symbols
+            .iter()
+            .filter(|symbol| symbol.is_global())
+            .find(|symbol| covers(symbol.line, symbol.columns))
+            .map(|symbol| symbol.id.as_str())
+            .or_else(|| {
+                self.references
+                    .iter()
+                    .find(|reference| covers(reference.line, reference.columns))
+                    .map(|reference| reference.id.as_str())
+            })
+    }
+
+    /// The element an id refers to
+    pub fn definition(&self, id: &str) -> Option<&Symbol> {
+        self.symbols.iter().find(|symbol| symbol.is_global() && symbol.id == id)
+    }
+
+    pub fn references_to<'a>(&'a self, id: &'a str) -> impl Iterator<Item = &'a Reference> + 'a {
+        self.references.iter().filter(move |reference| reference.id == id)
+    }
+
+    /// Position of the last quoted id in a validation message that appears in the document,
+    /// not counting a "did you mean" suggestion
+    pub fn locate(&self, message: &str) -> Option<(usize, (usize, usize))> {
+        let (message, _) = split_suggestion(message);
+        let quoted: Vec<&str> = message.split('\'').skip(1).step_by(2).collect();
+        quoted.iter().rev().find_map(|id| {
+            if let Some(reference) = self.references_to(id).next() {
+                return Some((reference.line, reference.columns));
+            }
+            // For duplicates, the later definition is the offending one
+            self.symbols.iter().rev().find(|symbol| symbol.id == *id).map(|symbol| (symbol.line, symbol.columns))
+        })
+    }
+
+    /// The reference whose position expects a certain kind of element, on `line`
+    pub fn expected_kind(&self, line: usize) -> Option<&'static str> {
+        self.references.iter().find(|reference| reference.line == line).and_then(|reference| reference.expects)
+    }
+}
+
+/// Path with list indexes replaced by `*`, e.g. `entities/*/id`
+pub fn pattern_of(path: &[Segment]) -> String {
+    path.iter()
+        .map(|segment| match segment {
+            Segment::Key(key) => key.as_str(),
+            Segment::Index(_) => "*",
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn definition_kind(pattern: &str) -> Option<&'static str> {
+    Some(match pattern {
+        "entities/*/id" => "entity",
+        "actions/*/id" => "action",
+        "features/*/id" => "feature",
+        "flows/*/id" => "flow",
+        "constraints/*/id" => "constraint",
+        "traceability/*/id" => "traceability",
+        "generation_rules/*/id" => "generation_rule",
+        "features/*/scenarios/*/id" => "scenario",
+        "flows/*/steps/*/id" => "step",
+        "entities/*/fields/*/name" => "field",
+        _ => return None,
+    })
+}
+
+/// `Some(expected kind)` for positions that hold an id
+fn reference_kind(pattern: &str) -> Option<Option<&'static str>> {
+    Some(match pattern {
+        "features/*/dependencies/*" | "traceability/*/from" | "traceability/*/to" | "generation_rules/*/triggers/*" => None,
+        "features/*/entity"
+        | "actions/*/input/entity"
+        | "actions/*/output/entity"
+        | "constraints/*/entities/*"
+        | "entities/*/relationships/*/entity" => Some("entity"),
+        "flows/*/steps/*/action" | "constraints/*/actions/*" => Some("action"),
+        _ => return None,
+    })
+}
+
+/// The inline value without quotes, with its columns
+fn unquote(line: &str, (from, to): (usize, usize)) -> Option<(&str, (usize, usize))> {
+    let value = line.get(from..to).filter(|value| !value.is_empty())?;
+    for quote in ['"', '\''] {
+        if let Some(inner) = value.strip_prefix(quote).and_then(|v| v.strip_suffix(quote)) {
+            return Some((inner, (from + 1, to - 1)));
+        }
+    }
+    Some((value, (from, to)))
+}
+
+fn element_start(outline: &[OutlineLine], path: &NodePath) -> Option<usize> {
+    outline.iter().position(|kind| matches!(kind, OutlineLine::Node(nodes) if nodes.paths.contains(path)))
+}
+
+/// Last content line of the node at `path`, which starts on `start`
+fn element_end(outline: &[OutlineLine], start: usize, path: &NodePath) -> usize {
+    let mut end = start;
+    for (line, kind) in outline.iter().enumerate().skip(start + 1) {
+        match kind {
+            OutlineLine::Node(nodes) if !nodes.paths[0].starts_with(path) => break,
+            OutlineLine::Node(_) | OutlineLine::Continuation => end = line,
+            OutlineLine::Blank | OutlineLine::Comment => {},
+        }
+    }
+    end
+}
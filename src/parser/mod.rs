@@ -1,6 +1,10 @@
 pub mod ast;
 pub mod lexer;
 pub mod parser;
+pub mod format;
+pub mod index;
+pub mod outline;
+pub mod yaml_edit;
 
 use crate::error::Result;
 use self::lexer::Lexer;
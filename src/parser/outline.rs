@@ -0,0 +1,149 @@
+//! Line-level outline of a block-style YAML document.
+//!
+//! Each line is classified and content lines are tagged with the path of the nodes
+//! that start on them, so tools can map text positions to document elements.
+
+use std::collections::HashMap;
+use super::yaml_edit::{indent_of, inline_span, is_dash, parse_key};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Segment {
+    Key(String),
+    Index(usize),
+}
+
+pub type NodePath = Vec<Segment>;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum OutlineLine {
+    Blank,
+    Comment,
+    /// Part of a value that started on an earlier line, such as a block scalar
+    Continuation,
+    Node(LineNodes),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LineNodes {
+    /// Paths of the nodes starting on this line, outermost first
+    pub paths: Vec<NodePath>,
+    /// Byte columns of the inline value, empty when the value is on the next lines
+    pub value: (usize, usize),
+    /// Comment after the value
+    pub comment: Option<String>,
+}
+
+impl LineNodes {
+    /// The innermost node on the line
+    pub fn path(&self) -> &NodePath {
+        self.paths.last().expect("content lines start at least one node")
+    }
+}
+
+/// Classify every line of `text`
+pub fn outline(text: &str) -> Vec<OutlineLine> {
+    let mut lines = Vec::new();
+    let mut frames: Vec<(usize, Segment)> = Vec::new();
+    let mut counters: HashMap<NodePath, usize> = HashMap::new();
+    let mut block_scalar: Option<usize> = None;
+
+    for line in text.lines() {
+        let trimmed = line.trim();
+        let mut column = indent_of(line);
+        if let Some(owner) = block_scalar {
+            if trimmed.is_empty() || column > owner {
+                lines.push(OutlineLine::Continuation);
+                continue;
+            }
+            block_scalar = None;
+        }
+        if trimmed.is_empty() || trimmed == "---" {
+            lines.push(OutlineLine::Blank);
+            continue;
+        }
+        if trimmed.starts_with('#') {
+            lines.push(OutlineLine::Comment);
+            continue;
+        }
+
+        let mut rest = &line[column..];
+        let mut paths = Vec::new();
+
+        while is_dash(rest) {
+            while frames.last().is_some_and(|(col, segment)| {
+                *col > column || (*col == column && matches!(segment, Segment::Index(_)))
+            }) {
+                frames.pop();
+            }
+            let counter = counters.entry(path_of(&frames)).or_insert(0);
+            frames.push((column, Segment::Index(*counter)));
+            *counter += 1;
+            paths.push(path_of(&frames));
+
+            let after = rest[1..].trim_start_matches(' ');
+            column += rest.len() - after.len();
+            rest = after;
+        }
+
+        let value_column = match parse_key(rest) {
+            Some((key, _)) => {
+                while frames.last().is_some_and(|(col, _)| *col >= column) {
+                    frames.pop();
+                }
+                frames.push((column, Segment::Key(key)));
+                paths.push(path_of(&frames));
+                column + rest.find(':').map_or(0, |colon| colon + 1)
+            },
+            _ if !paths.is_empty() => column,
+            // Continuation of a multi-line plain scalar
+            _ => {
+                lines.push(OutlineLine::Continuation);
+                continue;
+            },
+        };
+
+        let (from, to) = inline_span(line, value_column);
+        if from < to && matches!(&line[from..from + 1], "|" | ">") {
+            block_scalar = Some(frames.last().map_or(0, |(col, _)| *col));
+        }
+        let comment = Some(line[to..].trim()).filter(|comment| comment.starts_with('#'));
+        lines.push(OutlineLine::Node(LineNodes {
+            paths,
+            value: (from, to),
+            comment: comment.map(str::to_string),
+        }));
+    }
+    lines
+}
+
+fn path_of(frames: &[(usize, Segment)]) -> NodePath {
+    frames.iter().map(|(_, segment)| segment.clone()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(name: &str) -> Segment {
+        Segment::Key(name.to_string())
+    }
+
+    #[test]
+    fn test_outline_paths() {
+        let text = "entities:\n  # Users\n  - id: user   # main\n    description: |\n      text\n    fields:\n    - name: id\n";
+        let lines = outline(text);
+
+        assert_eq!(lines[1], OutlineLine::Comment);
+        let OutlineLine::Node(item) = &lines[2] else { panic!("expected a node") };
+        assert_eq!(item.paths, vec![
+            vec![key("entities"), Segment::Index(0)],
+            vec![key("entities"), Segment::Index(0), key("id")],
+        ]);
+        assert_eq!(&text.lines().nth(2).unwrap()[item.value.0..item.value.1], "user");
+        assert_eq!(item.comment.as_deref(), Some("# main"));
+        assert_eq!(lines[4], OutlineLine::Continuation);
+
+        let OutlineLine::Node(field) = &lines[6] else { panic!("expected a node") };
+        assert_eq!(field.path(), &vec![key("entities"), Segment::Index(0), key("fields"), Segment::Index(0), key("name")]);
+    }
+}
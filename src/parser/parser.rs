@@ -125,6 +125,8 @@ impl Parser {
             description: None,
             components: Vec::new(),
             relationships: Vec::new(),
+            design_principles: None,
+            implementation_strategies: None,
         };
         
         while self.match_identifier() {
@@ -170,6 +172,8 @@ impl Parser {
             description: None,
             fields: Vec::new(),
             relationships: None,
+            design_principles: None,
+            implementation_strategies: None,
         };
         
         while self.match_identifier() {
@@ -212,6 +216,10 @@ impl Parser {
             scenarios: Vec::new(),
             acceptance_criteria: None,
             dependencies: None,
+            entity: None,
+            operation: None,
+            roles: None,
+            effort: None,
         };
         
         while self.match_identifier() {
@@ -258,6 +266,9 @@ impl Parser {
             side_effects: None,
             preconditions: None,
             postconditions: None,
+            exceptions: None,
+            design_principles: None,
+            execution_strategies: None,
         })
     }
     
@@ -271,6 +282,8 @@ impl Parser {
             name: "placeholder".to_string(),
             description: None,
             steps: Vec::new(),
+            design_principles: None,
+            optimization_strategies: None,
         })
     }
     
@@ -287,6 +300,8 @@ impl Parser {
             rule: "placeholder".to_string(),
             entities: None,
             actions: None,
+            design_principles: None,
+            enforcement_strategy: None,
         })
     }
     
@@ -296,10 +311,14 @@ impl Parser {
         self.skip_to_next_section();
         
         Ok(Traceability {
+            id: None,
             from: "placeholder".to_string(),
             to: "placeholder".to_string(),
             relation: "placeholder".to_string(),
             description: None,
+            confidence: None,
+            verified: None,
+            metadata: None,
         })
     }
     
@@ -382,6 +401,7 @@ impl Parser {
                 required: None,
                 default: None,
                 constraints: None,
+                design_hints: None,
             };
             
             // Parse field properties
@@ -0,0 +1,766 @@
+//! Format-preserving YAML updates.
+//!
+//! Instead of re-serializing a whole document, the previous and the updated values are
+//! compared and only the entries and list items that changed are rewritten in the
+//! original text. Comments, key order, quoting and blank lines elsewhere are kept.
+
+use serde::{de::DeserializeOwned, Serialize};
+use serde_yaml::{Mapping, Value};
+use crate::error::{FdmlError, Result};
+
+/// Write `updated` back into `original`, touching only the lines of changed nodes.
+/// Fails rather than re-serializing the whole document when the original text cannot be
+/// edited in place, since that would drop its comments and layout; blank originals are
+/// simply serialized.
+pub fn update_yaml<T>(original: &str, updated: &T) -> Result<String>
+where
+    T: Serialize + DeserializeOwned + PartialEq,
+{
+    if original.trim().is_empty() {
+        return Ok(serde_yaml::to_string(updated)?);
+    }
+    let cannot_edit = || FdmlError::simple_parser_error("YAML cannot be edited in place without losing its comments and layout");
+
+    let previous = serde_yaml::from_str::<T>(original).map_err(|_| cannot_edit())?;
+    if previous == *updated {
+        return Ok(original.to_string());
+    }
+
+    let old = serde_yaml::to_value(&previous)?;
+    let new = serde_yaml::to_value(updated)?;
+    let patched = YamlText::new(original).patch(&old, &new).ok_or_else(cannot_edit)?;
+
+    // Only keep the edited text if it reads back as the updated value
+    match serde_yaml::from_str::<T>(&patched) {
+        Ok(reparsed) if reparsed == *updated => Ok(patched),
+        _ => Err(cannot_edit()),
+    }
+}
+
+/// Lines `[start, end)` holding mapping entries whose keys start at column `indent`.
+/// For a list item the first line begins with the item's `- `.
+#[derive(Debug, Clone, Copy)]
+struct Block {
+    start: usize,
+    end: usize,
+    indent: usize,
+    in_item: bool,
+}
+
+#[derive(Debug)]
+struct Entry {
+    key: String,
+    line: usize,
+    end: usize,
+    indent: usize,
+    value: Node,
+}
+
+#[derive(Debug)]
+struct Item {
+    line: usize,
+    end: usize,
+    value: Node,
+}
+
+/// How a value is written in the text
+#[derive(Debug)]
+enum Node {
+    /// Single-line value on the key or dash line, between the given columns
+    Inline { from: usize, to: usize },
+    Mapping(Block),
+    Sequence { start: usize, end: usize, indent: usize },
+    /// Anything else (block scalars, anchors, nested lists in lists)
+    Opaque,
+}
+
+struct Edit {
+    start: usize,
+    end: usize,
+    lines: Vec<String>,
+}
+
+struct YamlText<'a> {
+    lines: Vec<&'a str>,
+    trailing_newline: bool,
+    edits: Vec<Edit>,
+}
+
+impl<'a> YamlText<'a> {
+    fn new(text: &'a str) -> Self {
+        Self {
+            lines: text.lines().collect(),
+            trailing_newline: text.ends_with('\n'),
+            edits: Vec::new(),
+        }
+    }
+
+    fn patch(mut self, old: &Value, new: &Value) -> Option<String> {
+        let (Value::Mapping(old), Value::Mapping(new)) = (old, new) else {
+            return None;
+        };
+
+        let start = self.lines
+            .iter()
+            .position(|line| !is_filler(line))
+            .map(|first| if self.lines[first].trim_end() == "---" { first + 1 } else { first })?;
+        let root = Block { start, end: self.lines.len(), indent: 0, in_item: false };
+        self.patch_mapping(root, old, new)?;
+
+        // Apply from the bottom up; at the same line, removals go before insertions
+        let mut edits: Vec<_> = self.edits.into_iter().enumerate().collect();
+        edits.sort_by(|(a_order, a), (b_order, b)| {
+            b.start.cmp(&a.start).then(b.end.cmp(&a.end)).then(b_order.cmp(a_order))
+        });
+
+        let mut lines: Vec<String> = self.lines.iter().map(|line| line.to_string()).collect();
+        for (_, edit) in edits {
+            lines.splice(edit.start..edit.end, edit.lines);
+        }
+
+        let mut text = lines.join("\n");
+        if self.trailing_newline {
+            text.push('\n');
+        }
+        Some(text)
+    }
+
+    fn patch_mapping(&mut self, block: Block, old: &Mapping, new: &Mapping) -> Option<()> {
+        let mark = self.edits.len();
+        let result = self.patch_mapping_entries(block, old, new);
+        if result.is_none() {
+            self.edits.truncate(mark);
+        }
+        result
+    }
+
+    fn patch_mapping_entries(&mut self, block: Block, old: &Mapping, new: &Mapping) -> Option<()> {
+        let entries = self.entries(block)?;
+        let last = entries.last()?;
+        let separated = entries.len() >= 2 && self.has_blank_line(entries[0].end, entries[1].line);
+        let mut inserted = Vec::new();
+
+        for (key, new_value) in new {
+            let key = key.as_str()?;
+            if is_absent(Some(new_value)) {
+                continue;
+            }
+            match entries.iter().find(|entry| entry.key == key) {
+                Some(entry) => {
+                    let old_value = old.get(key);
+                    if !same(old_value, Some(new_value)) {
+                        self.patch_entry(entry, old_value, new_value);
+                    }
+                },
+                None if same(old.get(key), Some(new_value)) => {},
+                None => {
+                    if separated {
+                        inserted.push(String::new());
+                    }
+                    inserted.extend(render_entry(key, new_value, block.indent));
+                },
+            }
+        }
+
+        for entry in &entries {
+            if is_absent(new.get(entry.key.as_str())) && !is_absent(old.get(entry.key.as_str())) {
+                if block.in_item && entry.line == block.start {
+                    return None;
+                }
+                self.remove(entry.line, entry.end, entry.indent);
+            }
+        }
+
+        if !inserted.is_empty() {
+            self.edits.push(Edit { start: last.end, end: last.end, lines: inserted });
+        }
+        Some(())
+    }
+
+    fn patch_entry(&mut self, entry: &Entry, old: Option<&Value>, new: &Value) {
+        let patched = match (&entry.value, old, new) {
+            (Node::Inline { from, to }, _, _) => self.replace_inline(entry.line, *from, *to, new),
+            (Node::Mapping(block), Some(Value::Mapping(old)), Value::Mapping(new)) => {
+                self.patch_mapping(*block, old, new)
+            },
+            (Node::Sequence { start, end, indent }, Some(Value::Sequence(old)), Value::Sequence(new))
+                if !new.is_empty() =>
+            {
+                self.patch_sequence(*start, *end, *indent, old, new)
+            },
+            _ => None,
+        };
+
+        if patched.is_none() {
+            let mut lines = render_entry(&entry.key, new, entry.indent);
+            // Keep the `- ` of a list item whose first key is being rewritten
+            lines[0].replace_range(..entry.indent, &self.lines[entry.line][..entry.indent]);
+            self.edits.push(Edit { start: entry.line, end: entry.end, lines });
+        }
+    }
+
+    fn patch_sequence(&mut self, start: usize, end: usize, indent: usize, old: &[Value], new: &[Value]) -> Option<()> {
+        let mark = self.edits.len();
+        let result = self.patch_sequence_items(start, end, indent, old, new);
+        if result.is_none() {
+            self.edits.truncate(mark);
+        }
+        result
+    }
+
+    fn patch_sequence_items(&mut self, start: usize, end: usize, indent: usize, old: &[Value], new: &[Value]) -> Option<()> {
+        let items = self.items(start, end, indent)?;
+        if items.len() != old.len() || items.is_empty() {
+            return None;
+        }
+        let separated = items.len() >= 2 && self.has_blank_line(items[0].end, items[1].line);
+        let render_new = |value: &Value, blank_before: bool, blank_after: bool| {
+            let mut lines = Vec::new();
+            if blank_before {
+                lines.push(String::new());
+            }
+            lines.extend(render_item(value, indent));
+            if blank_after {
+                lines.push(String::new());
+            }
+            lines
+        };
+
+        match (identities(old), identities(new)) {
+            (Some(old_ids), Some(new_ids)) if !renamed_in_place(&old_ids, &new_ids) => {
+                let kept_old: Vec<_> = old_ids.iter().filter(|id| new_ids.contains(id)).collect();
+                let kept_new: Vec<_> = new_ids.iter().filter(|id| old_ids.contains(id)).collect();
+                if kept_old != kept_new {
+                    return None;
+                }
+
+                for (index, id) in old_ids.iter().enumerate() {
+                    if !new_ids.contains(id) {
+                        self.remove(items[index].line, items[index].end, indent);
+                    }
+                }
+
+                let mut anchor: Option<usize> = None;
+                for (value, id) in new.iter().zip(&new_ids) {
+                    match old_ids.iter().position(|old_id| old_id == id) {
+                        Some(index) => {
+                            if !same(Some(&old[index]), Some(value)) {
+                                self.patch_item(&items[index], &old[index], value, indent);
+                            }
+                            anchor = Some(items[index].end);
+                        },
+                        None => {
+                            let (position, lines) = match anchor {
+                                Some(position) => (position, render_new(value, separated, false)),
+                                None => (self.leading_start(items[0].line, indent), render_new(value, false, separated)),
+                            };
+                            self.edits.push(Edit { start: position, end: position, lines });
+                        },
+                    }
+                }
+            },
+            _ => {
+                for (index, (old_value, new_value)) in old.iter().zip(new).enumerate() {
+                    if !same(Some(old_value), Some(new_value)) {
+                        self.patch_item(&items[index], old_value, new_value, indent);
+                    }
+                }
+                for item in items.iter().skip(new.len()) {
+                    self.remove(item.line, item.end, indent);
+                }
+                let position = items[items.len() - 1].end;
+                let lines: Vec<String> = new
+                    .iter()
+                    .skip(old.len())
+                    .flat_map(|value| render_new(value, separated, false))
+                    .collect();
+                if !lines.is_empty() {
+                    self.edits.push(Edit { start: position, end: position, lines });
+                }
+            },
+        }
+        Some(())
+    }
+
+    fn patch_item(&mut self, item: &Item, old: &Value, new: &Value, indent: usize) {
+        let patched = match (&item.value, old, new) {
+            (Node::Inline { from, to }, _, _) => self.replace_inline(item.line, *from, *to, new),
+            (Node::Mapping(block), Value::Mapping(old), Value::Mapping(new)) => self.patch_mapping(*block, old, new),
+            _ => None,
+        };
+
+        if patched.is_none() {
+            self.edits.push(Edit { start: item.line, end: item.end, lines: render_item(new, indent) });
+        }
+    }
+
+    /// Replace a single-line value, keeping whatever follows it (such as a comment)
+    fn replace_inline(&mut self, line: usize, from: usize, to: usize, value: &Value) -> Option<()> {
+        let text = self.lines[line];
+        let rendered = match value {
+            Value::Sequence(items) if text[from..to].starts_with('[') => render_flow_sequence(items)?,
+            _ => render_inline(value)?,
+        };
+        if rendered.contains('\n') {
+            return None;
+        }
+        let replaced = format!("{}{}{}", &text[..from], rendered, &text[to..]);
+        self.edits.push(Edit { start: line, end: line + 1, lines: vec![replaced] });
+        Some(())
+    }
+
+    /// Remove lines together with the comments directly above them
+    fn remove(&mut self, line: usize, end: usize, indent: usize) {
+        let mut start = self.leading_start(line, indent);
+        // Do not leave two blank lines behind
+        let blank_after = end >= self.lines.len() || self.lines[end].trim().is_empty();
+        if start > 0 && self.lines[start - 1].trim().is_empty() && blank_after {
+            start -= 1;
+        }
+        self.edits.push(Edit { start, end, lines: Vec::new() });
+    }
+
+    fn leading_start(&self, line: usize, indent: usize) -> usize {
+        let mut start = line;
+        while start > 0 {
+            let previous = self.lines[start - 1];
+            if previous.trim_start().starts_with('#') && indent_of(previous) == indent {
+                start -= 1;
+            } else {
+                break;
+            }
+        }
+        start
+    }
+
+    fn has_blank_line(&self, from: usize, to: usize) -> bool {
+        self.lines[from..to].iter().any(|line| line.trim().is_empty())
+    }
+
+    fn entries(&self, block: Block) -> Option<Vec<Entry>> {
+        let mut starts = Vec::new();
+        for index in block.start..block.end {
+            let line = self.lines[index];
+            let first_item_line = block.in_item && index == block.start;
+            if is_filler(line) && !first_item_line {
+                continue;
+            }
+            let line_indent = indent_of(line);
+            // `key:` followed by `- item` lines at the key's own indent
+            if !first_item_line && line_indent == block.indent && is_dash(line.trim_start()) {
+                if starts.is_empty() {
+                    return None;
+                }
+                continue;
+            }
+            if first_item_line || line_indent == block.indent {
+                let (key, _) = parse_key(line.get(block.indent..)?)?;
+                starts.push((index, key));
+            } else if line_indent < block.indent {
+                return None;
+            }
+        }
+
+        let mut entries = Vec::new();
+        for (position, (line, key)) in starts.iter().enumerate() {
+            let next = starts.get(position + 1).map_or(block.end, |(next, _)| *next);
+            let end = self.trim_end(*line, next);
+            let value = self.entry_value(*line, end, block.indent, key.len())?;
+            entries.push(Entry { key: key.clone(), line: *line, end, indent: block.indent, value });
+        }
+        Some(entries)
+    }
+
+    fn entry_value(&self, line: usize, end: usize, indent: usize, key_length: usize) -> Option<Node> {
+        let text = self.lines[line];
+        let (_, rest) = parse_key(&text[indent..])?;
+        let rest_column = indent + key_length + (text[indent..].len() - key_length - rest.len());
+        let (from, to) = inline_span(text, rest_column);
+
+        if from < to {
+            return Some(match &text[from..from + 1] {
+                "|" | ">" | "&" | "*" | "!" => Node::Opaque,
+                _ => Node::Inline { from, to },
+            });
+        }
+
+        let Some(child) = (line + 1..end).find(|&index| !is_filler(self.lines[index])) else {
+            return Some(Node::Opaque);
+        };
+        let child_text = self.lines[child];
+        let child_indent = indent_of(child_text);
+        if is_dash(child_text.trim_start()) && child_indent >= indent {
+            Some(Node::Sequence { start: line + 1, end, indent: child_indent })
+        } else if child_indent > indent {
+            Some(Node::Mapping(Block { start: line + 1, end, indent: child_indent, in_item: false }))
+        } else {
+            None
+        }
+    }
+
+    fn items(&self, start: usize, end: usize, indent: usize) -> Option<Vec<Item>> {
+        let mut starts = Vec::new();
+        for index in start..end {
+            let line = self.lines[index];
+            if is_filler(line) {
+                continue;
+            }
+            let line_indent = indent_of(line);
+            if line_indent == indent && is_dash(line.trim_start()) {
+                starts.push(index);
+            } else if line_indent <= indent {
+                return None;
+            }
+        }
+
+        let mut items = Vec::new();
+        for (position, line) in starts.iter().enumerate() {
+            let next = starts.get(position + 1).copied().unwrap_or(end);
+            let item_end = self.trim_end(*line, next);
+            let text = self.lines[*line];
+            let content = indent + 1;
+            let value = match text.get(indent + 2..) {
+                Some(rest) if parse_key(rest).is_some() => {
+                    Node::Mapping(Block { start: *line, end: item_end, indent: indent + 2, in_item: true })
+                },
+                _ => {
+                    let (from, to) = inline_span(text, content);
+                    if from < to && !matches!(&text[from..from + 1], "|" | ">" | "&" | "*" | "!" | "-") {
+                        Node::Inline { from, to }
+                    } else {
+                        Node::Opaque
+                    }
+                },
+            };
+            items.push(Item { line: *line, end: item_end, value });
+        }
+        Some(items)
+    }
+
+    /// Drop trailing blank lines and comments, which belong to whatever follows
+    fn trim_end(&self, line: usize, mut end: usize) -> usize {
+        while end > line + 1 && is_filler(self.lines[end - 1]) {
+            end -= 1;
+        }
+        end
+    }
+}
+
+pub(super) fn indent_of(line: &str) -> usize {
+    line.len() - line.trim_start_matches(' ').len()
+}
+
+pub(super) fn is_filler(line: &str) -> bool {
+    let trimmed = line.trim();
+    trimmed.is_empty() || trimmed.starts_with('#')
+}
+
+pub(super) fn is_dash(text: &str) -> bool {
+    text == "-" || text.starts_with("- ")
+}
+
+/// Split `key: rest` at the start of `text`
+pub(super) fn parse_key(text: &str) -> Option<(String, &str)> {
+    let (key, after) = match text.chars().next()? {
+        quote @ ('"' | '\'') => {
+            let close = text[1..].find(quote)? + 1;
+            (text[1..close].to_string(), &text[close + 1..])
+        },
+        '#' | '-' | '[' | '{' => return None,
+        _ => {
+            let colon = text.find(": ").or_else(|| text.strip_suffix(':').map(|t| t.len()))?;
+            let key = &text[..colon];
+            if key.contains(" #") {
+                return None;
+            }
+            (key.to_string(), &text[colon..])
+        },
+    };
+    let rest = after.strip_prefix(':')?;
+    if !rest.is_empty() && !rest.starts_with(' ') {
+        return None;
+    }
+    Some((key, rest))
+}
+
+/// Columns of the value that starts at or after `column`, excluding a trailing comment
+pub(super) fn inline_span(text: &str, column: usize) -> (usize, usize) {
+    let bytes = text.as_bytes();
+    let mut from = column.min(text.len());
+    while from < text.len() && bytes[from] == b' ' {
+        from += 1;
+    }
+
+    let mut quote = None;
+    let mut to = text.len();
+    for (offset, character) in text[from..].char_indices() {
+        match (quote, character) {
+            (None, '"' | '\'') if offset == 0 => quote = Some(character),
+            (Some(open), c) if c == open => quote = None,
+            (None, '#') if offset > 0 && text[..from + offset].ends_with(' ') => {
+                to = from + offset;
+                break;
+            },
+            _ => {},
+        }
+    }
+    while to > from && bytes[to - 1] == b' ' {
+        to -= 1;
+    }
+    (from, to)
+}
+
+fn is_absent(value: Option<&Value>) -> bool {
+    matches!(value, None | Some(Value::Null))
+}
+
+/// Equality that treats missing and null mapping entries as the same
+fn same(a: Option<&Value>, b: Option<&Value>) -> bool {
+    match (a, b) {
+        (Some(Value::Mapping(a)), Some(Value::Mapping(b))) => {
+            a.iter().all(|(key, value)| same(Some(value), b.get(key)))
+                && b.iter().all(|(key, value)| same(a.get(key), Some(value)))
+        },
+        (Some(Value::Sequence(a)), Some(Value::Sequence(b))) => {
+            a.len() == b.len() && a.iter().zip(b).all(|(a, b)| same(Some(a), Some(b)))
+        },
+        _ if is_absent(a) && is_absent(b) => true,
+        (a, b) => a == b,
+    }
+}
+
+/// Identity of each list item (its `id`, `name`, or traceability endpoints), if every
+/// item has a distinct one
+fn identities(values: &[Value]) -> Option<Vec<String>> {
+    let mut ids: Vec<String> = Vec::new();
+    for value in values {
+        let Value::Mapping(mapping) = value else {
+            return None;
+        };
+        let field = |key: &str| mapping.get(key).and_then(Value::as_str);
+        let id = match (field("id"), field("name"), field("from"), field("relation"), field("to")) {
+            (Some(id), _, _, _, _) => id.to_string(),
+            (None, Some(name), _, _, _) => name.to_string(),
+            (None, None, Some(from), Some(relation), Some(to)) => format!("{}:{}:{}", from, relation, to),
+            _ => return None,
+        };
+        if ids.contains(&id) {
+            return None;
+        }
+        ids.push(id);
+    }
+    Some(ids)
+}
+
+/// Whether every item whose identity changed kept its position, as when an id is renamed
+fn renamed_in_place(old_ids: &[String], new_ids: &[String]) -> bool {
+    old_ids.len() == new_ids.len()
+        && old_ids != new_ids
+        && old_ids
+            .iter()
+            .zip(new_ids)
+            .all(|(old_id, new_id)| old_id == new_id || (!new_ids.contains(old_id) && !old_ids.contains(new_id)))
+}
+
+/// `[a, b]` text of a list of plain scalars
+fn render_flow_sequence(items: &[Value]) -> Option<String> {
+    let mut rendered = Vec::new();
+    for item in items {
+        if matches!(item, Value::Sequence(_) | Value::Mapping(_)) {
+            return None;
+        }
+        let text = render_inline(item)?;
+        if text.contains(['\n', ',', '[', ']', '{', '}']) {
+            return None;
+        }
+        rendered.push(text);
+    }
+    Some(format!("[{}]", rendered.join(", ")))
+}
+
+/// Single-line (or block scalar) text of a scalar or empty collection
+pub(super) fn render_inline(value: &Value) -> Option<String> {
+    match value {
+        Value::Sequence(items) if items.is_empty() => Some("[]".to_string()),
+        Value::Mapping(entries) if entries.values().all(|v| is_absent(Some(v))) => Some("{}".to_string()),
+        Value::Sequence(_) | Value::Mapping(_) => None,
+        _ => serde_yaml::to_string(value).ok().map(|text| text.trim_end_matches('\n').to_string()),
+    }
+}
+
+fn render_entry(key: &str, value: &Value, indent: usize) -> Vec<String> {
+    let pad = " ".repeat(indent);
+    match render_inline(value) {
+        Some(inline) => prefix_lines(&format!("{}{}: ", pad, key), &pad, &inline),
+        None => {
+            let mut lines = vec![format!("{}{}:", pad, key)];
+            match value {
+                Value::Mapping(entries) => lines.extend(render_mapping(entries, indent + 2)),
+                Value::Sequence(items) => {
+                    lines.extend(items.iter().flat_map(|item| render_item(item, indent + 2)));
+                },
+                _ => {},
+            }
+            lines
+        },
+    }
+}
+
+fn render_mapping(entries: &Mapping, indent: usize) -> Vec<String> {
+    entries
+        .iter()
+        .filter(|(_, value)| !is_absent(Some(value)))
+        .filter_map(|(key, value)| Some(render_entry(key.as_str()?, value, indent)))
+        .flatten()
+        .collect()
+}
+
+fn render_item(value: &Value, indent: usize) -> Vec<String> {
+    let pad = " ".repeat(indent);
+    match (value, render_inline(value)) {
+        (_, Some(inline)) => prefix_lines(&format!("{}- ", pad), &pad, &inline),
+        (Value::Mapping(entries), None) => {
+            let mut lines = render_mapping(entries, indent + 2);
+            if let Some(first) = lines.first_mut() {
+                first.replace_range(..indent + 2, &format!("{}- ", pad));
+            }
+            lines
+        },
+        (Value::Sequence(items), None) => {
+            let mut lines = vec![format!("{}-", pad)];
+            lines.extend(items.iter().flat_map(|item| render_item(item, indent + 2)));
+            lines
+        },
+        _ => Vec::new(),
+    }
+}
+
+/// Put `first` before the first line of `text` and `rest` before continuation lines
+fn prefix_lines(first: &str, rest: &str, text: &str) -> Vec<String> {
+    text.lines()
+        .enumerate()
+        .map(|(index, line)| if index == 0 { format!("{}{}", first, line) } else { format!("{}{}", rest, line) })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::ast::FdmlDocument;
+
+    const SPEC: &str = r#"# Shop specification
+metadata:
+  version: "1.3"   # keep this comment
+  author: Shop Team
+
+entities:
+  # Customers
+  - id: customer
+    name: Customer
+    description: >
+      Someone who buys things.
+    fields:
+      - name: id
+        type: string
+        required: true
+
+  - id: order
+    name: Order
+    fields:
+      - name: id
+        type: string
+
+features:
+  - id: checkout
+    title: 'Checkout'
+    scenarios: []
+"#;
+
+    fn edit(change: impl FnOnce(&mut FdmlDocument)) -> String {
+        let mut document: FdmlDocument = serde_yaml::from_str(SPEC).unwrap();
+        change(&mut document);
+        let updated = update_yaml(SPEC, &document).unwrap();
+        let reparsed: FdmlDocument = serde_yaml::from_str(&updated).unwrap();
+        assert_eq!(reparsed, document);
+        updated
+    }
+
+    #[test]
+    fn test_unchanged_document_is_untouched() {
+        assert_eq!(edit(|_| {}), SPEC);
+    }
+
+    #[test]
+    fn test_scalar_change_keeps_comments() {
+        let updated = edit(|document| {
+            document.metadata.as_mut().unwrap().version = "1.4".to_string();
+        });
+        assert_eq!(updated, SPEC.replace(r#"version: "1.3""#, "version: '1.4'"));
+    }
+
+    #[test]
+    fn test_added_and_removed_list_items() {
+        let updated = edit(|document| {
+            let mut field = document.entities[1].fields[0].clone();
+            field.name = "total".to_string();
+            field.field_type = "decimal".to_string();
+            document.entities[1].fields.push(field);
+            document.entities.remove(0);
+        });
+
+        assert!(!updated.contains("Customers"));
+        assert!(!updated.contains("customer"));
+        assert!(updated.contains("      - name: total\n        type: decimal\n"));
+        // Untouched parts keep their formatting
+        assert!(updated.contains("version: \"1.3\"   # keep this comment"));
+        assert!(updated.contains("title: 'Checkout'"));
+        assert!(!updated.contains("null"));
+    }
+
+    #[test]
+    fn test_new_section_and_empty_list() {
+        let updated = edit(|document| {
+            document.features[0].acceptance_criteria = Some(vec!["Order is placed".to_string()]);
+            document.features[0].scenarios.push(crate::parser::ast::Scenario {
+                id: "pay".to_string(),
+                title: "Pay".to_string(),
+                description: None,
+                given: vec!["a cart".to_string()],
+                when: vec!["paying".to_string()],
+                then: vec!["an order exists".to_string()],
+            });
+            document.flows.push(crate::parser::ast::Flow {
+                id: "purchase".to_string(),
+                name: "Purchase".to_string(),
+                description: None,
+                steps: Vec::new(),
+                design_principles: None,
+                optimization_strategies: None,
+            });
+        });
+
+        assert!(updated.starts_with(SPEC.split("features:").next().unwrap()));
+        assert!(updated.contains("    scenarios:\n      - id: pay\n"));
+        assert!(updated.contains("    acceptance_criteria:\n      - Order is placed\n"));
+        assert!(updated.ends_with("\nflows:\n  - id: purchase\n    name: Purchase\n    steps: []\n"));
+    }
+
+    #[test]
+    fn test_sequence_at_key_indent() {
+        let original = "# keep\nid: invoice\ndependencies:\n- 002_total  # order totals\n- 003_coupon\nup: []\n";
+        let mut value: Value = serde_yaml::from_str(original).unwrap();
+        value["dependencies"] = serde_yaml::to_value(["003_squashed", "003_coupon"]).unwrap();
+        assert_eq!(
+            update_yaml(original, &value).unwrap(),
+            "# keep\nid: invoice\ndependencies:\n- 003_squashed  # order totals\n- 003_coupon\nup: []\n"
+        );
+    }
+
+    #[test]
+    fn test_refuses_to_rewrite_what_it_cannot_edit() {
+        let document: FdmlDocument = serde_yaml::from_str(SPEC).unwrap();
+        let error = update_yaml("not: [valid", &document).unwrap_err();
+        assert!(error.to_string().contains("without losing its comments and layout"), "{}", error);
+        assert_eq!(update_yaml("\n", &document).unwrap(), serde_yaml::to_string(&document).unwrap());
+    }
+}
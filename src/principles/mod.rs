@@ -0,0 +1,4 @@
+pub mod report;
+pub mod taxonomy;
+
+pub use taxonomy::{conflict, lookup, is_known_principle, Principle, PrincipleGroup, CONFLICTS, PRINCIPLES};
@@ -0,0 +1,328 @@
+//! Design principle coverage across a specification (`fdml principles report`).
+
+use crate::parser::ast::{DesignPrinciples, FdmlDocument};
+use crate::principles::{PrincipleGroup, PRINCIPLES};
+use crate::validator::principles::principled_elements;
+use serde::Serialize;
+
+/// Words in a constraint type that give it a security impact
+const SECURITY_TYPES: &[&str] = &["security", "auth", "access", "privacy", "permission"];
+
+/// Which elements declare and apply which principles, per group
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CoverageReport {
+    pub groups: Vec<GroupCoverage>,
+    /// Critical elements that declare no principles of their own
+    pub uncovered: Vec<UncoveredElement>,
+    /// Number of strategies and hints applying a principle
+    pub strategy_count: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GroupCoverage {
+    pub group: PrincipleGroup,
+    /// Principles declared under this group, in taxonomy order
+    pub principles: Vec<PrincipleCoverage>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PrincipleCoverage {
+    pub code: String,
+    pub name: String,
+    /// Elements declaring the principle, e.g. `System 'shop'`
+    pub declared_by: Vec<String>,
+    /// Strategies and hints applying it, e.g. `Action 'pay' execution strategy`
+    pub applied_by: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct UncoveredElement {
+    pub element: String,
+    pub reason: String,
+}
+
+impl CoverageReport {
+    /// Build the report from the system-level and per-element `design_principles`;
+    /// codes outside the taxonomy are left to validation
+    pub fn build(document: &FdmlDocument) -> Self {
+        let elements = principled_elements(document);
+        let applied_by = |code: &str| -> Vec<String> {
+            elements
+                .iter()
+                .flat_map(|element| {
+                    element.uses.iter().filter(|(_, used)| *used == code).map(|(kind, _)| format!("{} {}", element.owner, kind))
+                })
+                .collect()
+        };
+
+        let groups = PrincipleGroup::ALL
+            .iter()
+            .map(|&group| {
+                let principles = PRINCIPLES
+                    .iter()
+                    .filter(|principle| principle.group == group)
+                    .filter_map(|principle| {
+                        let declared_by: Vec<String> = elements
+                            .iter()
+                            .filter(|element| {
+                                element.declared.is_some_and(|declared| {
+                                    declared.groups().iter().any(|(declared_group, codes)| {
+                                        *declared_group == group && codes.iter().any(|code| code == principle.code)
+                                    })
+                                })
+                            })
+                            .map(|element| element.owner.clone())
+                            .collect();
+                        (!declared_by.is_empty()).then(|| PrincipleCoverage {
+                            code: principle.code.to_string(),
+                            name: principle.name.to_string(),
+                            declared_by,
+                            applied_by: applied_by(principle.code),
+                        })
+                    })
+                    .collect();
+                GroupCoverage { group, principles }
+            })
+            .collect();
+
+        Self {
+            groups,
+            uncovered: uncovered_elements(document),
+            strategy_count: elements.iter().map(|element| element.uses.len()).sum(),
+        }
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = String::from("# Design Principle Coverage\n\n");
+        markdown.push_str(&self.summary());
+        markdown.push('\n');
+
+        for coverage in &self.groups {
+            markdown.push_str(&format!("\n## {}\n\n", title(coverage.group)));
+            if coverage.principles.is_empty() {
+                markdown.push_str("_No principles declared._\n");
+                continue;
+            }
+            markdown.push_str("| Principle | Declared by | Applied by |\n|---|---|---|\n");
+            for principle in &coverage.principles {
+                markdown.push_str(&format!(
+                    "| {} ({}) | {} | {} |\n",
+                    principle.code,
+                    principle.name,
+                    principle.declared_by.join(", ").replace('|', "\\|"),
+                    or_dash(&principle.applied_by.join("; ")).replace('|', "\\|")
+                ));
+            }
+        }
+
+        markdown.push_str("\n## Critical Elements Without Principles\n\n");
+        if self.uncovered.is_empty() {
+            markdown.push_str("_None._\n");
+        } else {
+            markdown.push_str("| Element | Why it is critical |\n|---|---|\n");
+            for uncovered in &self.uncovered {
+                markdown.push_str(&format!("| {} | {} |\n", uncovered.element, uncovered.reason));
+            }
+        }
+        markdown
+    }
+
+    pub fn to_html(&self) -> String {
+        let mut html = String::from(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>Design Principle Coverage</title>\n</head>\n<body>\n",
+        );
+        html.push_str("<h1>Design Principle Coverage</h1>\n");
+        html.push_str(&format!("<p>{}</p>\n", escape(&self.summary())));
+
+        for coverage in &self.groups {
+            html.push_str(&format!("<h2>{}</h2>\n", title(coverage.group)));
+            if coverage.principles.is_empty() {
+                html.push_str("<p><em>No principles declared.</em></p>\n");
+                continue;
+            }
+            html.push_str("<table>\n<tr><th>Principle</th><th>Declared by</th><th>Applied by</th></tr>\n");
+            for principle in &coverage.principles {
+                html.push_str(&format!(
+                    "<tr><td>{} ({})</td><td>{}</td><td>{}</td></tr>\n",
+                    escape(&principle.code),
+                    escape(&principle.name),
+                    escape(&principle.declared_by.join(", ")),
+                    escape(or_dash(&principle.applied_by.join("; ")))
+                ));
+            }
+            html.push_str("</table>\n");
+        }
+
+        html.push_str("<h2>Critical Elements Without Principles</h2>\n");
+        if self.uncovered.is_empty() {
+            html.push_str("<p><em>None.</em></p>\n");
+        } else {
+            html.push_str("<table>\n<tr><th>Element</th><th>Why it is critical</th></tr>\n");
+            for uncovered in &self.uncovered {
+                html.push_str(&format!("<tr><td>{}</td><td>{}</td></tr>\n", escape(&uncovered.element), escape(&uncovered.reason)));
+            }
+            html.push_str("</table>\n");
+        }
+        html.push_str("</body>\n</html>\n");
+        html
+    }
+
+    fn summary(&self) -> String {
+        let principles = || self.groups.iter().flat_map(|coverage| &coverage.principles);
+        let mut codes: Vec<&str> = principles().map(|principle| principle.code.as_str()).collect();
+        codes.sort();
+        codes.dedup();
+        let unapplied: Vec<&str> = codes
+            .iter()
+            .copied()
+            .filter(|code| principles().all(|principle| principle.code != *code || principle.applied_by.is_empty()))
+            .collect();
+
+        let mut summary = format!(
+            "{} principle(s) declared, applied by {} strategy/hint(s); {} critical element(s) without principles.",
+            codes.len(),
+            self.strategy_count,
+            self.uncovered.len()
+        );
+        if !unapplied.is_empty() {
+            summary.push_str(&format!(" Declared without any strategy: {}.", unapplied.join(", ")));
+        }
+        summary
+    }
+}
+
+/// Actions with side effects and security constraints that declare no principles themselves
+fn uncovered_elements(document: &FdmlDocument) -> Vec<UncoveredElement> {
+    let declares_none = |principles: &Option<DesignPrinciples>| {
+        principles.as_ref().is_none_or(|principles| principles.groups().iter().all(|(_, codes)| codes.is_empty()))
+    };
+    let mut uncovered = Vec::new();
+
+    for action in &document.actions {
+        let side_effects = action.side_effects.as_ref().map_or(0, Vec::len);
+        if side_effects > 0 && declares_none(&action.design_principles) {
+            uncovered.push(UncoveredElement {
+                element: format!("Action '{}'", action.id),
+                reason: format!("has {} side effect(s)", side_effects),
+            });
+        }
+    }
+    for constraint in &document.constraints {
+        let constraint_type = constraint.constraint_type.to_lowercase();
+        let security = SECURITY_TYPES.iter().any(|word| constraint_type.contains(word));
+        if security && declares_none(&constraint.design_principles) {
+            uncovered.push(UncoveredElement {
+                element: format!("Constraint '{}'", constraint.id),
+                reason: format!("has security impact (type '{}')", constraint.constraint_type),
+            });
+        }
+    }
+
+    uncovered
+}
+
+fn title(group: PrincipleGroup) -> String {
+    let key = group.key();
+    key[..1].to_uppercase() + &key[1..]
+}
+
+fn or_dash(text: &str) -> &str {
+    if text.is_empty() { "-" } else { text }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_coverage_report() {
+        let spec = r#"
+system:
+  id: shop
+  name: Shop
+  components: []
+  relationships: []
+  design_principles:
+    structure: [Mo]
+    reliability: [Ft]
+  implementation_strategies:
+    - principle: Mo
+      strategy: Services per domain
+actions:
+  - id: pay
+    side_effects: ["charges the card"]
+    design_principles:
+      reliability: [Ft, At]
+    execution_strategies:
+      - principle: Ft
+        strategy: Circuit breaker
+  - id: refund
+    side_effects: ["refunds the card"]
+constraints:
+  - id: admins_only
+    name: Admins only
+    type: authorization
+    rule: only admins can refund
+"#;
+        let document: FdmlDocument = serde_yaml::from_str(spec).unwrap();
+        let report = CoverageReport::build(&document);
+
+        assert_eq!(report.to_markdown(), "\
+# Design Principle Coverage
+
+3 principle(s) declared, applied by 2 strategy/hint(s); 2 critical element(s) without principles. Declared without any strategy: At.
+
+## Structure
+
+| Principle | Declared by | Applied by |
+|---|---|---|
+| Mo (Modularity) | System 'shop' | System 'shop' implementation strategy |
+
+## Efficiency
+
+_No principles declared._
+
+## Semantics
+
+_No principles declared._
+
+## Distribution
+
+_No principles declared._
+
+## Planning
+
+_No principles declared._
+
+## Operability
+
+_No principles declared._
+
+## Reliability
+
+| Principle | Declared by | Applied by |
+|---|---|---|
+| Ft (Fault Tolerance) | System 'shop', Action 'pay' | Action 'pay' execution strategy |
+| At (Atomic Execution) | Action 'pay' | - |
+
+## Security
+
+_No principles declared._
+
+## Critical Elements Without Principles
+
+| Element | Why it is critical |
+|---|---|
+| Action 'refund' | has 1 side effect(s) |
+| Constraint 'admins_only' | has security impact (type 'authorization') |
+");
+        assert!(report.groups.iter().all(|coverage| coverage.principles.iter().all(|principle| {
+            crate::principles::lookup(&principle.code).is_some_and(|known| known.group == coverage.group)
+        })));
+        assert!(report.to_html().contains("<tr><td>At (Atomic Execution)</td><td>Action 'pay'</td><td>-</td></tr>"));
+    }
+}
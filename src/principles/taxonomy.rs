@@ -0,0 +1,178 @@
+use serde::{Deserialize, Serialize};
+
+/// Principle groups defined by FDML extension 1.3.3 (section 16.2)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PrincipleGroup {
+    Structure,
+    Efficiency,
+    Semantics,
+    Distribution,
+    Planning,
+    Operability,
+    Reliability,
+    Security,
+}
+
+impl PrincipleGroup {
+    pub const ALL: [PrincipleGroup; 8] = [
+        PrincipleGroup::Structure,
+        PrincipleGroup::Efficiency,
+        PrincipleGroup::Semantics,
+        PrincipleGroup::Distribution,
+        PrincipleGroup::Planning,
+        PrincipleGroup::Operability,
+        PrincipleGroup::Reliability,
+        PrincipleGroup::Security,
+    ];
+
+    /// Key used for the group in `design_principles` blocks
+    pub fn key(&self) -> &'static str {
+        match self {
+            PrincipleGroup::Structure => "structure",
+            PrincipleGroup::Efficiency => "efficiency",
+            PrincipleGroup::Semantics => "semantics",
+            PrincipleGroup::Distribution => "distribution",
+            PrincipleGroup::Planning => "planning",
+            PrincipleGroup::Operability => "operability",
+            PrincipleGroup::Reliability => "reliability",
+            PrincipleGroup::Security => "security",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|group| group.key() == key)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Principle {
+    pub code: &'static str,
+    pub name: &'static str,
+    pub group: PrincipleGroup,
+    pub summary: &'static str,
+}
+
+const fn principle(code: &'static str, name: &'static str, group: PrincipleGroup, summary: &'static str) -> Principle {
+    Principle { code, name, group, summary }
+}
+
+/// Built-in design principle taxonomy
+pub const PRINCIPLES: &[Principle] = &[
+    // Structure
+    principle("Si", "Simplicity", PrincipleGroup::Structure, "Choose simplest design meeting current needs"),
+    principle("Mo", "Modularity", PrincipleGroup::Structure, "Partition into cohesive units with minimal interfaces"),
+    principle("Co", "Composability", PrincipleGroup::Structure, "Design components for safe recombination"),
+    principle("Ex", "Extensibility", PrincipleGroup::Structure, "Allow safe user-defined extensions"),
+    principle("Pm", "Policy/Mechanism Separation", PrincipleGroup::Structure, "Separate what from how"),
+    principle("Gr", "Generalized Design", PrincipleGroup::Structure, "Single core with variation points"),
+    // Efficiency
+    principle("Sc", "Scalability", PrincipleGroup::Efficiency, "Handle growth with linear cost"),
+    principle("Rc", "Reuse of Computation", PrincipleGroup::Efficiency, "Cache and materialize results"),
+    principle("Wv", "Work Avoidance", PrincipleGroup::Efficiency, "Skip unnecessary computation"),
+    principle("Cc", "Common-Case Specialization", PrincipleGroup::Efficiency, "Optimize hot paths"),
+    principle("Bo", "Bottleneck-Oriented", PrincipleGroup::Efficiency, "Focus on tightest constraint"),
+    principle("Ha", "Hardware-Aware", PrincipleGroup::Efficiency, "Shape to hardware properties"),
+    principle("Op", "Optimistic Design", PrincipleGroup::Efficiency, "Assume success, handle failures"),
+    principle("La", "Learned Approximation", PrincipleGroup::Efficiency, "Use ML for efficiency"),
+    // Semantics
+    principle("Al", "Abstraction Lifting", PrincipleGroup::Semantics, "High-level interfaces over low-level ops"),
+    principle("Lu", "Language Homogeneity", PrincipleGroup::Semantics, "Single IR across components"),
+    principle("Se", "Semantically Explicit", PrincipleGroup::Semantics, "Precise interface specifications"),
+    principle("Fs", "Formal Specification", PrincipleGroup::Semantics, "Mathematical behavior models"),
+    principle("Ig", "Invariant-Guided", PrincipleGroup::Semantics, "Use invariants for transformation"),
+    // Distribution
+    principle("Lt", "Location Transparency", PrincipleGroup::Distribution, "Hide physical resource location"),
+    principle("Dc", "Decentralized Control", PrincipleGroup::Distribution, "Distribute decision-making"),
+    principle("Fp", "Function Placement", PrincipleGroup::Distribution, "Place functionality where needed"),
+    principle("Lo", "Locality of Reference", PrincipleGroup::Distribution, "Keep related data together"),
+    // Planning
+    principle("Ep", "Equivalence-based", PrincipleGroup::Planning, "Apply semantic-preserving rewrites"),
+    principle("Cm", "Cost-based", PrincipleGroup::Planning, "Use cost models to guide choices"),
+    principle("Cp", "Constraint-based", PrincipleGroup::Planning, "Encode decisions as constraints"),
+    principle("Gd", "Goal-Directed", PrincipleGroup::Planning, "Synthesize operations from goals"),
+    principle("Bb", "Black-Box Tuning", PrincipleGroup::Planning, "Empirically optimize"),
+    principle("Ah", "Advisory Hinting", PrincipleGroup::Planning, "Non-binding performance hints"),
+    // Operability
+    principle("Ad", "Adaptive Processing", PrincipleGroup::Operability, "Adjust to runtime conditions"),
+    principle("Ec", "Elasticity", PrincipleGroup::Operability, "Auto-adjust resources to demand"),
+    principle("Wa", "Workload-Aware", PrincipleGroup::Operability, "Adapt to workload patterns"),
+    principle("Au", "Automation", PrincipleGroup::Operability, "Perform routine tasks automatically"),
+    principle("Ho", "Human Observability", PrincipleGroup::Operability, "Expose internal state"),
+    principle("Ev", "Evolvability", PrincipleGroup::Operability, "Change with minimal disruption"),
+    // Reliability
+    principle("Ft", "Fault Tolerance", PrincipleGroup::Reliability, "Continue despite failures"),
+    principle("Is", "Isolation", PrincipleGroup::Reliability, "Prevent unintended interference"),
+    principle("At", "Atomic Execution", PrincipleGroup::Reliability, "All-or-nothing operations"),
+    principle("Cr", "Consistency Relaxation", PrincipleGroup::Reliability, "Trade consistency for performance"),
+    // Security
+    principle("Sy", "Security via Isolation", PrincipleGroup::Security, "Enforce strong boundaries"),
+    principle("Ac", "Access Control", PrincipleGroup::Security, "Define permissions and audit"),
+    principle("Lp", "Least Privilege", PrincipleGroup::Security, "Grant minimal authority"),
+    principle("Tq", "Trust via Quorum", PrincipleGroup::Security, "Rely on majority agreement"),
+    principle("Cf", "Conservative Defaults", PrincipleGroup::Security, "Ship with safe settings"),
+    principle("Sa", "Safety by Construction", PrincipleGroup::Security, "Make errors impossible"),
+];
+
+/// Look up a principle by its code (e.g. "Mo")
+pub fn lookup(code: &str) -> Option<&'static Principle> {
+    PRINCIPLES.iter().find(|p| p.code == code)
+}
+
+pub fn is_known_principle(code: &str) -> bool {
+    lookup(code).is_some()
+}
+
+/// Pairs of principles from the same group that pull a design in opposite directions
+pub const CONFLICTS: &[(&str, &str, &str)] = &[
+    ("Si", "Gr", "a generalized core with variation points adds the complexity simplicity avoids"),
+    ("At", "Cr", "all-or-nothing execution needs the consistency that relaxation gives up"),
+    ("Lt", "Fp", "placing functionality deliberately relies on the locations transparency hides"),
+];
+
+/// Why two principles conflict, if they do (in either order)
+pub fn conflict(a: &str, b: &str) -> Option<&'static str> {
+    CONFLICTS
+        .iter()
+        .find(|(first, second, _)| (*first == a && *second == b) || (*first == b && *second == a))
+        .map(|(_, _, reason)| *reason)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_principle_lookup() {
+        let principle = lookup("Mo").unwrap();
+        assert_eq!(principle.name, "Modularity");
+        assert_eq!(principle.group, PrincipleGroup::Structure);
+
+        assert!(is_known_principle("Lp"));
+        assert!(!is_known_principle("Zz"));
+        // Codes are case-sensitive
+        assert!(!is_known_principle("mo"));
+    }
+
+    #[test]
+    fn test_principle_codes_are_unique() {
+        let mut codes: Vec<_> = PRINCIPLES.iter().map(|p| p.code).collect();
+        codes.sort();
+        codes.dedup();
+        assert_eq!(codes.len(), PRINCIPLES.len());
+
+        for group in PrincipleGroup::ALL {
+            assert_eq!(PrincipleGroup::from_key(group.key()), Some(group));
+            assert!(PRINCIPLES.iter().any(|p| p.group == group));
+        }
+    }
+
+    #[test]
+    fn test_conflicts_are_within_a_group() {
+        for (first, second, _) in CONFLICTS {
+            assert_eq!(lookup(first).unwrap().group, lookup(second).unwrap().group);
+        }
+        assert!(conflict("Cr", "At").is_some());
+        assert!(conflict("At", "Ft").is_none());
+    }
+}
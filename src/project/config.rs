@@ -0,0 +1,135 @@
+use crate::error::{FdmlError, Result};
+use crate::validator::scenarios::ScenarioLint;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+/// Name of the project configuration file created by `fdml init`
+pub const CONFIG_FILE: &str = "fdml.yaml";
+
+/// Contents of `fdml.yaml`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProjectConfig {
+    pub project: Option<ProjectInfo>,
+    #[serde(default)]
+    pub settings: Settings,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProjectInfo {
+    pub name: Option<String>,
+    pub version: Option<String>,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Settings {
+    pub validation: Option<ValidationSettings>,
+    pub generation: Option<GenerationSettings>,
+    pub paths: Option<PathSettings>,
+    pub lint: Option<LintSettings>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ValidationSettings {
+    pub strict: Option<bool>,
+    pub rules: Option<Vec<String>>,
+}
+
+/// Word lists for `fdml lint --scenarios`; each replaces the built-in list when set
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LintSettings {
+    pub ui_terms: Option<Vec<String>>,
+    pub technical_terms: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GenerationSettings {
+    pub output_dir: Option<String>,
+    /// Output formats; code generation languages and others such as documentation
+    pub formats: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PathSettings {
+    pub specs: Option<String>,
+    pub features: Option<String>,
+    pub entities: Option<String>,
+    pub flows: Option<String>,
+    pub docs: Option<String>,
+    pub migrations: Option<String>,
+}
+
+impl ProjectConfig {
+    /// Load `fdml.yaml` from a project directory, if there is one
+    pub fn load<P: AsRef<Path>>(project_dir: P) -> Result<Option<Self>> {
+        let path = project_dir.as_ref().join(CONFIG_FILE);
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let content = fs::read_to_string(&path)?;
+        let config = serde_yaml::from_str(&content).map_err(|e| {
+            FdmlError::project_error(format!("Invalid project configuration '{}': {}", path.display(), e))
+        })?;
+        Ok(Some(config))
+    }
+
+    /// Load the `fdml.yaml` of the project containing `path`, searching its parent directories
+    pub fn find<P: AsRef<Path>>(path: P) -> Result<Option<Self>> {
+        for directory in path.as_ref().ancestors() {
+            if directory.join(CONFIG_FILE).is_file() {
+                return Self::load(directory);
+            }
+        }
+        Ok(None)
+    }
+
+    /// Rules listed under `settings.validation.rules`
+    pub fn validation_rules(&self) -> Vec<String> {
+        self.settings.validation.iter().flat_map(|validation| validation.rules.iter().flatten()).cloned().collect()
+    }
+
+    /// Scenario lint using the configured word lists, falling back to the built-in ones
+    pub fn scenario_lint(&self) -> ScenarioLint {
+        let mut lint = ScenarioLint::default();
+        if let Some(settings) = &self.settings.lint {
+            if let Some(terms) = &settings.ui_terms {
+                lint.ui_terms = terms.clone();
+            }
+            if let Some(terms) = &settings.technical_terms {
+                lint.technical_terms = terms.clone();
+            }
+        }
+        lint
+    }
+
+    /// Directory generated code is written to
+    pub fn output_dir(&self) -> &str {
+        self.settings
+            .generation
+            .as_ref()
+            .and_then(|generation| generation.output_dir.as_deref())
+            .unwrap_or("generated")
+    }
+
+    /// Directory holding migration files
+    pub fn migrations_dir(&self) -> &str {
+        self.settings
+            .paths
+            .as_ref()
+            .and_then(|paths| paths.migrations.as_deref())
+            .unwrap_or("migrations")
+    }
+
+    /// Configured formats that are code generation languages
+    pub fn generation_languages(&self) -> Vec<String> {
+        self.settings
+            .generation
+            .iter()
+            .flat_map(|generation| generation.formats.iter().flatten())
+            .filter(|format| matches!(format.as_str(), "typescript" | "ts" | "python" | "py" | "go"))
+            .cloned()
+            .collect()
+    }
+}
@@ -1,3 +1,9 @@
+pub mod config;
 pub mod init;
+pub mod plan;
+pub mod watch;
 
-pub use init::ProjectInitializer;
\ No newline at end of file
+pub use config::ProjectConfig;
+pub use init::ProjectInitializer;
+pub use plan::ImplementationPlan;
+pub use watch::{ProjectWatcher, WatchOptions};
@@ -0,0 +1,154 @@
+//! Implementation roadmaps ordered by feature dependencies (`fdml plan`).
+
+use crate::error::Result;
+use crate::parser::ast::{FdmlDocument, Feature};
+use crate::validator::DependencyGraph;
+use serde::Serialize;
+
+/// Features grouped into waves that can be implemented in order
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ImplementationPlan {
+    pub waves: Vec<Wave>,
+    /// Sum of all estimates
+    pub total_effort: f64,
+    /// Features without an `effort` estimate
+    pub unestimated: Vec<String>,
+}
+
+/// Features whose dependencies are all in earlier waves
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Wave {
+    pub number: usize,
+    pub features: Vec<PlannedFeature>,
+    pub effort: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PlannedFeature {
+    pub id: String,
+    pub title: String,
+    pub effort: Option<f64>,
+    /// Feature dependencies; dependencies on other kinds of element don't affect the order
+    pub dependencies: Vec<String>,
+}
+
+impl ImplementationPlan {
+    /// Order a document's features, failing if their dependencies are circular
+    pub fn build(document: &FdmlDocument) -> Result<Self> {
+        let waves: Vec<Wave> = DependencyGraph::new(document)
+            .waves()?
+            .into_iter()
+            .enumerate()
+            .map(|(index, features)| {
+                let features: Vec<PlannedFeature> = features.into_iter().map(|feature| planned(document, feature)).collect();
+                Wave {
+                    number: index + 1,
+                    effort: features.iter().filter_map(|feature| feature.effort).sum(),
+                    features,
+                }
+            })
+            .collect();
+
+        let features = || waves.iter().flat_map(|wave| &wave.features);
+        Ok(Self {
+            total_effort: waves.iter().map(|wave| wave.effort).sum(),
+            unestimated: features().filter(|feature| feature.effort.is_none()).map(|feature| feature.id.clone()).collect(),
+            waves,
+        })
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = String::from("# Implementation Plan\n\n");
+        let count: usize = self.waves.iter().map(|wave| wave.features.len()).sum();
+        markdown.push_str(&format!("{} feature(s) in {} wave(s)", count, self.waves.len()));
+        if self.total_effort > 0.0 {
+            markdown.push_str(&format!(", total effort {}", self.total_effort));
+        }
+        if !self.unestimated.is_empty() && self.unestimated.len() < count {
+            markdown.push_str(&format!(" ({} not estimated)", self.unestimated.len()));
+        }
+        markdown.push_str(".\n");
+
+        for wave in &self.waves {
+            markdown.push_str(&format!("\n## Wave {}", wave.number));
+            if wave.effort > 0.0 {
+                markdown.push_str(&format!(" (effort {})", wave.effort));
+            }
+            markdown.push_str("\n\n| Feature | Title | Effort | Depends on |\n|---|---|---|---|\n");
+            for feature in &wave.features {
+                let effort = feature.effort.map_or("-".to_string(), |effort| effort.to_string());
+                let dependencies = if feature.dependencies.is_empty() {
+                    "-".to_string()
+                } else {
+                    feature.dependencies.iter().map(|id| format!("`{}`", id)).collect::<Vec<_>>().join(", ")
+                };
+                markdown.push_str(&format!(
+                    "| `{}` | {} | {} | {} |\n",
+                    feature.id,
+                    feature.title.replace('|', "\\|"),
+                    effort,
+                    dependencies
+                ));
+            }
+        }
+        markdown
+    }
+}
+
+fn planned(document: &FdmlDocument, feature: &Feature) -> PlannedFeature {
+    let is_feature = |id: &String| document.features.iter().any(|other| other.id == *id);
+    PlannedFeature {
+        id: feature.id.clone(),
+        title: feature.title.clone(),
+        effort: feature.effort,
+        dependencies: feature.dependencies.iter().flatten().filter(|id| is_feature(id)).cloned().collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plan_markdown() {
+        let spec = r#"
+features:
+  - id: checkout
+    title: Checkout
+    scenarios: []
+    dependencies: [catalog]
+    effort: 5
+  - id: catalog
+    title: Catalog
+    scenarios: []
+    effort: 3
+  - id: reviews
+    title: Reviews
+    scenarios: []
+    dependencies: [catalog]
+"#;
+        let document: FdmlDocument = serde_yaml::from_str(spec).unwrap();
+        let plan = ImplementationPlan::build(&document).unwrap();
+
+        assert_eq!(plan.total_effort, 8.0);
+        assert_eq!(plan.unestimated, vec!["reviews"]);
+        assert_eq!(plan.to_markdown(), "\
+# Implementation Plan
+
+3 feature(s) in 2 wave(s), total effort 8 (1 not estimated).
+
+## Wave 1 (effort 3)
+
+| Feature | Title | Effort | Depends on |
+|---|---|---|---|
+| `catalog` | Catalog | 3 | - |
+
+## Wave 2 (effort 5)
+
+| Feature | Title | Effort | Depends on |
+|---|---|---|---|
+| `checkout` | Checkout | 5 | `catalog` |
+| `reviews` | Reviews | - | `catalog` |
+");
+    }
+}
@@ -0,0 +1,365 @@
+use crate::error::{print_info, print_success, print_warning, Result};
+use crate::generators::{create_generator, GeneratorConfig};
+use crate::migration::{Migration, MigrationRunner};
+use crate::parser::ast::FdmlDocument;
+use crate::parser::parse_fdml_yaml;
+use crate::project::config::ProjectConfig;
+use crate::validator::Validator;
+use colored::Colorize;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+
+pub struct WatchOptions {
+    /// Regenerate code for changed specs
+    pub generate: bool,
+    /// Languages to generate; the configured formats when empty
+    pub languages: Vec<String>,
+    /// How often files are polled
+    pub interval: Duration,
+    /// Quiet period after the last change before checks run
+    pub debounce: Duration,
+}
+
+impl Default for WatchOptions {
+    fn default() -> Self {
+        Self {
+            generate: false,
+            languages: Vec::new(),
+            interval: Duration::from_millis(200),
+            debounce: Duration::from_millis(300),
+        }
+    }
+}
+
+/// Result of re-checking changed files
+#[derive(Debug, Default)]
+pub struct CheckReport {
+    /// Diagnostics that were not reported before, as (file, message)
+    pub added: Vec<(PathBuf, String)>,
+    /// Diagnostics that no longer occur
+    pub resolved: Vec<(PathBuf, String)>,
+    /// Generated code, as (spec file, language, number of files)
+    pub generated: Vec<(PathBuf, String, usize)>,
+    /// Generation failures, as (spec file, message)
+    pub generation_errors: Vec<(PathBuf, String)>,
+}
+
+/// Watches a project's specs and migrations, re-checking files as they change
+pub struct ProjectWatcher {
+    root: PathBuf,
+    config: ProjectConfig,
+    options: WatchOptions,
+    modified: HashMap<PathBuf, SystemTime>,
+    diagnostics: BTreeMap<PathBuf, Vec<String>>,
+    /// Last generated document of each spec, to skip regenerating unchanged ones
+    generated: HashMap<PathBuf, FdmlDocument>,
+}
+
+impl ProjectWatcher {
+    pub fn new<P: AsRef<Path>>(root: P, options: WatchOptions) -> Result<Self> {
+        let root = root.as_ref().to_path_buf();
+        let config = ProjectConfig::load(&root)?.unwrap_or_default();
+        Ok(Self {
+            root,
+            config,
+            options,
+            modified: HashMap::new(),
+            diagnostics: BTreeMap::new(),
+            generated: HashMap::new(),
+        })
+    }
+
+    /// Spec files and migration files of the project
+    pub fn watched_files(&self) -> Vec<PathBuf> {
+        let mut files = Vec::new();
+        let skipped = [self.root.join(self.config.output_dir()), self.root.join("target")];
+        collect_files(&self.root, &skipped, &mut files);
+        files.retain(|file| self.is_spec(file) || self.is_migration(file));
+        files
+    }
+
+    fn is_spec(&self, file: &Path) -> bool {
+        file.extension().and_then(|s| s.to_str()) == Some("fdml")
+    }
+
+    fn is_migration(&self, file: &Path) -> bool {
+        matches!(file.extension().and_then(|s| s.to_str()), Some("yaml" | "yml"))
+            && file.parent() == Some(self.root.join(self.config.migrations_dir()).as_path())
+    }
+
+    /// Files added, modified or removed since the last call
+    pub fn changed_files(&mut self) -> Vec<PathBuf> {
+        let mut current = HashMap::new();
+        for file in self.watched_files() {
+            if let Ok(modified) = fs::metadata(&file).and_then(|metadata| metadata.modified()) {
+                current.insert(file, modified);
+            }
+        }
+
+        let mut changed: Vec<PathBuf> = current
+            .iter()
+            .filter(|(file, modified)| self.modified.get(*file) != Some(modified))
+            .map(|(file, _)| file.clone())
+            .chain(self.modified.keys().filter(|file| !current.contains_key(*file)).cloned())
+            .collect();
+        changed.sort();
+        self.modified = current;
+        changed
+    }
+
+    /// Re-check the given files and report how the diagnostics changed
+    pub fn check(&mut self, changed: &[PathBuf]) -> CheckReport {
+        let mut report = CheckReport::default();
+
+        for file in changed {
+            let (messages, document) = if !file.exists() {
+                (Vec::new(), None)
+            } else if self.is_migration(file) {
+                (self.check_migration(file), None)
+            } else {
+                check_spec(file, &self.config.validation_rules())
+            };
+
+            let previous = self.diagnostics.remove(file).unwrap_or_default();
+            for message in &messages {
+                if !previous.contains(message) {
+                    report.added.push((file.clone(), message.clone()));
+                }
+            }
+            for message in previous {
+                if !messages.contains(&message) {
+                    report.resolved.push((file.clone(), message));
+                }
+            }
+            if !messages.is_empty() {
+                self.diagnostics.insert(file.clone(), messages);
+            }
+
+            if let Some(document) = document {
+                if self.options.generate && self.generated.get(file) != Some(&document) {
+                    self.generate(file, &document, &mut report);
+                }
+            } else {
+                self.generated.remove(file);
+            }
+        }
+        report
+    }
+
+    fn check_migration(&self, file: &Path) -> Vec<String> {
+        let content = match fs::read_to_string(file) {
+            Ok(content) => content,
+            Err(e) => return vec![format!("Failed to read file: {}", e)],
+        };
+        let migration: Migration = match serde_yaml::from_str(&content) {
+            Ok(migration) => migration,
+            Err(e) => return vec![format!("Invalid migration: {}", e)],
+        };
+
+        let runner = MigrationRunner::new(self.root.join(self.config.migrations_dir()));
+        migration
+            .up
+            .iter()
+            .chain(&migration.down)
+            .filter_map(|operation| runner.validate_new_operation(operation).err())
+            .map(|error| error.to_string())
+            .collect()
+    }
+
+    fn generate(&mut self, file: &Path, document: &FdmlDocument, report: &mut CheckReport) {
+        let languages = if self.options.languages.is_empty() {
+            self.config.generation_languages()
+        } else {
+            self.options.languages.clone()
+        };
+        let stem = file.file_stem().and_then(|s| s.to_str()).unwrap_or("spec");
+
+        let mut succeeded = true;
+        for language in languages {
+            let output_dir = self.root.join(self.config.output_dir()).join(&language).join(stem);
+            let config = GeneratorConfig {
+                language: language.clone(),
+                output_dir: output_dir.display().to_string(),
+                template_dir: None,
+                with_tests: false,
+            };
+            match create_generator(&config).and_then(|generator| generator.generate(document, &output_dir)) {
+                Ok(files) => report.generated.push((file.to_path_buf(), language, files.len())),
+                Err(e) => {
+                    succeeded = false;
+                    report.generation_errors.push((file.to_path_buf(), format!("{} generation failed: {}", language, e)));
+                },
+            }
+        }
+        if succeeded {
+            self.generated.insert(file.to_path_buf(), document.clone());
+        }
+    }
+
+    /// Number of current diagnostics and of files that have them
+    pub fn problem_count(&self) -> (usize, usize) {
+        (self.diagnostics.values().map(Vec::len).sum(), self.diagnostics.len())
+    }
+
+    /// Check everything once, then keep re-checking changed files until interrupted
+    pub fn watch(&mut self, once: bool) -> Result<()> {
+        let initial = self.changed_files();
+        print_info(&format!("Watching {} file(s) in {}", initial.len(), self.root.display()));
+        let report = self.check(&initial);
+        self.print_report(&report);
+        if once {
+            return Ok(());
+        }
+
+        loop {
+            thread::sleep(self.options.interval);
+            let mut changed = self.changed_files();
+            if changed.is_empty() {
+                continue;
+            }
+
+            // Wait for a quiet period so a burst of saves triggers one run
+            let mut last_change = Instant::now();
+            while last_change.elapsed() < self.options.debounce {
+                thread::sleep(self.options.interval);
+                let more = self.changed_files();
+                if !more.is_empty() {
+                    last_change = Instant::now();
+                    changed.extend(more);
+                }
+            }
+            changed.sort();
+            changed.dedup();
+
+            println!();
+            println!("🔄 {} file(s) changed at {}", changed.len(), chrono::Local::now().format("%H:%M:%S"));
+            for file in &changed {
+                println!("  {}", self.display(file));
+            }
+            let report = self.check(&changed);
+            self.print_report(&report);
+        }
+    }
+
+    fn display(&self, file: &Path) -> String {
+        file.strip_prefix(&self.root).unwrap_or(file).display().to_string()
+    }
+
+    fn print_report(&self, report: &CheckReport) {
+        for (file, message) in &report.added {
+            println!("  {} {}: {}", "✗".red(), self.display(file), message);
+        }
+        for (file, message) in &report.resolved {
+            println!("  {} {}: {}", "✓".green(), self.display(file), format!("resolved: {}", message).dimmed());
+        }
+        for (file, language, count) in &report.generated {
+            println!("  🛠️  Generated {} code for {} ({} files)", language, self.display(file), count);
+        }
+        for (file, message) in &report.generation_errors {
+            println!("  {} {}: {}", "✗".red(), self.display(file), message);
+        }
+
+        match self.problem_count() {
+            (0, _) => print_success("No problems"),
+            (problems, files) => print_warning(&format!("{} problem(s) in {} file(s)", problems, files)),
+        }
+    }
+}
+
+/// Parse and validate a spec file, returning its diagnostics and the parsed document
+fn check_spec(file: &Path, optional_rules: &[String]) -> (Vec<String>, Option<FdmlDocument>) {
+    let content = match fs::read_to_string(file) {
+        Ok(content) => content,
+        Err(e) => return (vec![format!("Failed to read file: {}", e)], None),
+    };
+    let document = match parse_fdml_yaml(&content) {
+        Ok(document) => document,
+        Err(e) => return (vec![e.to_string()], None),
+    };
+    match Validator::with_optional_rules(optional_rules).validate(&document) {
+        Ok(messages) => (messages, Some(document)),
+        Err(e) => (vec![e.to_string()], Some(document)),
+    }
+}
+
+/// Collect files under `dir`, skipping hidden directories and the `skipped` ones
+fn collect_files(dir: &Path, skipped: &[PathBuf], files: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let hidden = entry.file_name().to_str().is_some_and(|name| name.starts_with('.'));
+        if path.is_dir() {
+            if !hidden && !skipped.contains(&path) {
+                collect_files(&path, skipped, files);
+            }
+        } else {
+            files.push(path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const VALID: &str = "entities:\n  - id: user\n    fields: []\n";
+    const BROKEN: &str = "entities:\n  - id: user\n    fields: []\nfeatures:\n  - id: signup\n    title: Signup\n    scenarios: []\n    dependencies: [missing]\n";
+
+    #[test]
+    fn test_reports_only_new_and_resolved_diagnostics() {
+        let temp_dir = TempDir::new().unwrap();
+        let spec = temp_dir.path().join("specs").join("app.fdml");
+        fs::create_dir_all(spec.parent().unwrap()).unwrap();
+        fs::write(&spec, BROKEN).unwrap();
+        fs::write(temp_dir.path().join("notes.txt"), "ignored").unwrap();
+
+        let mut watcher = ProjectWatcher::new(temp_dir.path(), WatchOptions::default()).unwrap();
+        let changed = watcher.changed_files();
+        assert_eq!(changed, vec![spec.clone()]);
+
+        let report = watcher.check(&changed);
+        assert_eq!(report.added.len(), 1);
+        assert!(report.added[0].1.contains("missing"));
+        assert_eq!(watcher.problem_count(), (1, 1));
+
+        // Unchanged diagnostics are not reported again
+        let report = watcher.check(std::slice::from_ref(&spec));
+        assert!(report.added.is_empty() && report.resolved.is_empty());
+
+        fs::write(&spec, VALID).unwrap();
+        let report = watcher.check(std::slice::from_ref(&spec));
+        assert!(report.added.is_empty());
+        assert_eq!(report.resolved.len(), 1);
+        assert_eq!(watcher.problem_count(), (0, 0));
+    }
+
+    #[test]
+    fn test_checks_migrations_and_generates_changed_specs() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("app.fdml"), VALID).unwrap();
+        fs::create_dir_all(temp_dir.path().join("migrations")).unwrap();
+        let migration = temp_dir.path().join("migrations").join("001_bad.yaml");
+        fs::write(&migration, "id: '001_bad'\nup:\n  - type: add_entity\n    id: ''\n    name: Broken\ndown: []\n").unwrap();
+
+        let options = WatchOptions { generate: true, languages: vec!["typescript".to_string()], ..Default::default() };
+        let mut watcher = ProjectWatcher::new(temp_dir.path(), options).unwrap();
+        let changed = watcher.changed_files();
+        let report = watcher.check(&changed);
+
+        assert_eq!(report.added.len(), 1);
+        assert_eq!(report.added[0].0, migration);
+        assert_eq!(report.generated.len(), 1);
+        assert!(temp_dir.path().join("generated/typescript/app").exists());
+
+        // Generated output is not watched, and an unchanged spec is not regenerated
+        assert!(watcher.changed_files().is_empty());
+        let report = watcher.check(&[temp_dir.path().join("app.fdml")]);
+        assert!(report.generated.is_empty());
+    }
+}
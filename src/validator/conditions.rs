@@ -0,0 +1,84 @@
+//! Constraint rules written in the condition language (`crate::condition`).
+
+use crate::condition::{check_condition, parse_condition};
+use crate::parser::ast::FdmlDocument;
+
+/// Constraint types whose rule must be a condition; other rules are checked only when they parse as one
+pub const CONDITION_TYPES: &[&str] = &["validation", "rule"];
+
+/// Syntax errors in condition-typed rules, and type errors such as misspelled fields in rules
+/// that are conditions: condition-typed, or parsing to one that compares or tests a value
+pub fn check_conditions(document: &FdmlDocument) -> Vec<String> {
+    let mut errors = Vec::new();
+    for constraint in &document.constraints {
+        let condition_type = CONDITION_TYPES.contains(&constraint.constraint_type.as_str());
+        match parse_condition(&constraint.rule) {
+            Ok(condition) if condition_type || condition.tests_values() => {
+                let entities = constraint.entities.as_deref().unwrap_or_default();
+                for problem in check_condition(&condition, document, entities) {
+                    errors.push(format!("Constraint '{}' rule {}", constraint.id, problem));
+                }
+            },
+            Err(problem) if condition_type => {
+                errors.push(format!("Constraint '{}' rule \"{}\" is not a valid condition: {}", constraint.id, constraint.rule, problem));
+            },
+            // Prose rules such as "each email belongs to one user" or "admin or owner"
+            _ => {},
+        }
+    }
+    errors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_conditions() {
+        let spec = r#"
+entities:
+  - id: product
+    fields:
+      - name: price
+        type: decimal
+constraints:
+  - id: positive_price
+    name: Positive price
+    type: validation
+    rule: product.prcie > 0
+    entities: [product]
+  - id: priced
+    name: Priced
+    type: validation
+    rule: price >
+    entities: [product]
+  - id: prose
+    name: Prose
+    type: business_rule
+    rule: prices are set by the catalogue team
+    entities: [product]
+  - id: roles
+    name: Roles
+    type: security
+    rule: admin or owner
+    entities: [product]
+  - id: required
+    name: Required
+    type: business_rule
+    rule: required
+    entities: [product]
+  - id: unique_price
+    name: Unique price
+    type: uniqueness
+    rule: cost is unique
+    entities: [product]
+"#;
+        let document: FdmlDocument = serde_yaml::from_str(spec).unwrap();
+
+        assert_eq!(check_conditions(&document), vec![
+            "Constraint 'positive_price' rule refers to unknown field 'prcie' of entity 'product' (did you mean 'price'?)",
+            "Constraint 'priced' rule \"price >\" is not a valid condition: expected a value at the end",
+            "Constraint 'unique_price' rule refers to unknown field 'cost' of entity 'product'",
+        ]);
+    }
+}
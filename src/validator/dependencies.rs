@@ -0,0 +1,253 @@
+//! Feature dependency graph (spec 10.2): cycles and implementation order.
+
+use crate::error::{FdmlError, Result};
+use crate::parser::ast::{FdmlDocument, Feature};
+use std::collections::HashMap;
+
+/// Cycles reported before giving up on densely connected graphs
+const MAX_CYCLES: usize = 50;
+
+/// Path extensions tried before giving up, for the same reason
+const SEARCH_BUDGET: usize = 100_000;
+
+/// Features and the features each one depends on, ignoring dependencies on other kinds of element
+pub struct DependencyGraph<'a> {
+    features: Vec<&'a Feature>,
+    /// Indexes into `features` of each feature's dependencies, in declaration order
+    edges: Vec<Vec<usize>>,
+}
+
+impl<'a> DependencyGraph<'a> {
+    pub fn new(document: &'a FdmlDocument) -> Self {
+        let features: Vec<&Feature> = document.features.iter().filter(|feature| !feature.id.is_empty()).collect();
+        let positions: HashMap<&str, usize> = features
+            .iter()
+            .enumerate()
+            .rev()
+            .map(|(index, feature)| (feature.id.as_str(), index))
+            .collect();
+
+        let edges = features
+            .iter()
+            .map(|feature| {
+                let mut targets = Vec::new();
+                for dependency in feature.dependencies.iter().flatten() {
+                    if let Some(&target) = positions.get(dependency.as_str()) {
+                        if !targets.contains(&target) {
+                            targets.push(target);
+                        }
+                    }
+                }
+                targets
+            })
+            .collect();
+
+        Self { features, edges }
+    }
+
+    /// Elementary dependency cycles (up to a limit), each as the path of feature IDs
+    /// that closes it, e.g. `["a", "b", "a"]`
+    pub fn cycles(&self) -> Vec<Vec<String>> {
+        let mut search = CycleSearch {
+            graph: self,
+            component: self.components(),
+            cycles: Vec::new(),
+            budget: SEARCH_BUDGET,
+        };
+        for start in 0..self.features.len() {
+            search.from(start, &mut vec![start]);
+        }
+
+        search
+            .cycles
+            .into_iter()
+            .map(|cycle| {
+                let mut path: Vec<String> = cycle.iter().map(|&index| self.features[index].id.clone()).collect();
+                path.push(path[0].clone());
+                path
+            })
+            .collect()
+    }
+
+    /// Strongly connected component of every feature (Tarjan's algorithm)
+    fn components(&self) -> Vec<usize> {
+        struct Tarjan<'g> {
+            edges: &'g [Vec<usize>],
+            index: Vec<Option<usize>>,
+            low: Vec<usize>,
+            on_stack: Vec<bool>,
+            stack: Vec<usize>,
+            component: Vec<usize>,
+            next_index: usize,
+            next_component: usize,
+        }
+
+        impl Tarjan<'_> {
+            fn visit(&mut self, node: usize) {
+                self.index[node] = Some(self.next_index);
+                self.low[node] = self.next_index;
+                self.next_index += 1;
+                self.stack.push(node);
+                self.on_stack[node] = true;
+
+                for &next in &self.edges[node] {
+                    match self.index[next] {
+                        None => {
+                            self.visit(next);
+                            self.low[node] = self.low[node].min(self.low[next]);
+                        },
+                        Some(index) if self.on_stack[next] => self.low[node] = self.low[node].min(index),
+                        Some(_) => {},
+                    }
+                }
+
+                if Some(self.low[node]) == self.index[node] {
+                    while let Some(member) = self.stack.pop() {
+                        self.on_stack[member] = false;
+                        self.component[member] = self.next_component;
+                        if member == node {
+                            break;
+                        }
+                    }
+                    self.next_component += 1;
+                }
+            }
+        }
+
+        let count = self.features.len();
+        let mut tarjan = Tarjan {
+            edges: &self.edges,
+            index: vec![None; count],
+            low: vec![0; count],
+            on_stack: vec![false; count],
+            stack: Vec::new(),
+            component: vec![0; count],
+            next_index: 0,
+            next_component: 0,
+        };
+        for node in 0..count {
+            if tarjan.index[node].is_none() {
+                tarjan.visit(node);
+            }
+        }
+        tarjan.component
+    }
+
+    /// Features grouped into waves: each wave only depends on earlier waves.
+    /// Fails when dependencies are circular, since such features can't be ordered.
+    pub fn waves(&self) -> Result<Vec<Vec<&'a Feature>>> {
+        let mut wave_of: Vec<Option<usize>> = vec![None; self.features.len()];
+        let mut waves: Vec<Vec<&Feature>> = Vec::new();
+        while wave_of.iter().any(Option::is_none) {
+            let wave = waves.len();
+            let ready: Vec<usize> = (0..self.features.len())
+                .filter(|&index| wave_of[index].is_none())
+                .filter(|&index| self.edges[index].iter().all(|&dependency| wave_of[dependency].is_some_and(|w| w < wave)))
+                .collect();
+            if ready.is_empty() {
+                return Err(self.circular_error(&wave_of));
+            }
+            for &index in &ready {
+                wave_of[index] = Some(wave);
+            }
+            waves.push(ready.into_iter().map(|index| self.features[index]).collect());
+        }
+        Ok(waves)
+    }
+
+    /// Why the features without a wave can't be ordered: the cycles, or just the features
+    /// when the cycle search gave up before finding one
+    fn circular_error(&self, wave_of: &[Option<usize>]) -> FdmlError {
+        let cycles = self.cycles();
+        let details: Vec<String> = if cycles.is_empty() {
+            (0..self.features.len())
+                .filter(|&index| wave_of[index].is_none())
+                .map(|index| self.features[index].id.clone())
+                .collect()
+        } else {
+            cycles.iter().map(|cycle| cycle.join(" -> ")).collect()
+        };
+        FdmlError::validation_error(format!(
+            "Features cannot be ordered because of circular dependencies: {}",
+            details.join("; ")
+        ))
+    }
+}
+
+struct CycleSearch<'g, 'a> {
+    graph: &'g DependencyGraph<'a>,
+    component: Vec<usize>,
+    cycles: Vec<Vec<usize>>,
+    budget: usize,
+}
+
+impl CycleSearch<'_, '_> {
+    /// Cycles through `path[0]` that only visit later features of its component,
+    /// so each cycle is found once, from its first feature
+    fn from(&mut self, start: usize, path: &mut Vec<usize>) {
+        let node = path[path.len() - 1];
+        for &next in &self.graph.edges[node] {
+            if self.cycles.len() >= MAX_CYCLES || self.budget == 0 {
+                return;
+            }
+            self.budget -= 1;
+            if next == start {
+                self.cycles.push(path.clone());
+            } else if next > start && self.component[next] == self.component[start] && !path.contains(&next) {
+                path.push(next);
+                self.from(start, path);
+                path.pop();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SPEC: &str = r#"
+features:
+  - id: checkout
+    title: Checkout
+    scenarios: []
+    dependencies: [cart, payments]
+  - id: cart
+    title: Cart
+    scenarios: []
+    dependencies: [catalog]
+  - id: catalog
+    title: Catalog
+    scenarios: []
+  - id: payments
+    title: Payments
+    scenarios: []
+    dependencies: [catalog, user]
+entities:
+  - id: user
+    fields: []
+"#;
+
+    #[test]
+    fn test_waves() {
+        let document: FdmlDocument = serde_yaml::from_str(SPEC).unwrap();
+        let waves = DependencyGraph::new(&document).waves().unwrap();
+        let ids: Vec<Vec<&str>> = waves.iter().map(|wave| wave.iter().map(|f| f.id.as_str()).collect()).collect();
+        assert_eq!(ids, vec![vec!["catalog"], vec!["cart", "payments"], vec!["checkout"]]);
+    }
+
+    #[test]
+    fn test_cycles() {
+        let spec = SPEC.replace("  - id: catalog\n    title: Catalog\n    scenarios: []\n", "  - id: catalog\n    title: Catalog\n    scenarios: []\n    dependencies: [checkout, catalog]\n");
+        let document: FdmlDocument = serde_yaml::from_str(&spec).unwrap();
+        let graph = DependencyGraph::new(&document);
+
+        assert_eq!(graph.cycles(), vec![
+            vec!["checkout", "cart", "catalog", "checkout"],
+            vec!["checkout", "payments", "catalog", "checkout"],
+            vec!["catalog", "catalog"],
+        ]);
+        let error = graph.waves().unwrap_err().to_string();
+        assert!(error.contains("circular dependencies: checkout -> cart -> catalog -> checkout; "), "{}", error);
+    }
+}
@@ -0,0 +1,312 @@
+//! Type checks for field defaults and field constraints.
+
+use crate::parser::ast::{Entity, FdmlDocument, Field, FieldConstraint, Value};
+
+/// What kind of value a field type holds; types outside these (entity references, custom types) aren't checked
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Category {
+    Text,
+    Integer,
+    Number,
+    Boolean,
+    Date,
+    DateTime,
+    Array,
+    Object,
+    Enum,
+}
+
+impl Category {
+    fn of(field_type: &str) -> Option<Self> {
+        Some(match field_type {
+            "string" | "text" | "email" | "uuid" => Category::Text,
+            "int" | "integer" => Category::Integer,
+            "float" | "double" | "decimal" => Category::Number,
+            "bool" | "boolean" => Category::Boolean,
+            "date" => Category::Date,
+            "datetime" => Category::DateTime,
+            "array" => Category::Array,
+            "object" => Category::Object,
+            "enum" => Category::Enum,
+            _ => return None,
+        })
+    }
+
+    fn is_numeric(self) -> bool {
+        matches!(self, Category::Integer | Category::Number)
+    }
+
+    fn has_length(self) -> bool {
+        matches!(self, Category::Text | Category::Array)
+    }
+}
+
+/// Check every field's default and constraints against its type
+pub fn check_fields(document: &FdmlDocument) -> Vec<String> {
+    let mut errors = Vec::new();
+    for entity in &document.entities {
+        for field in &entity.fields {
+            if let Some(category) = Category::of(&field.field_type) {
+                FieldCheck { entity, field, category, errors: &mut errors }.run();
+            }
+        }
+    }
+    errors
+}
+
+struct FieldCheck<'a> {
+    entity: &'a Entity,
+    field: &'a Field,
+    category: Category,
+    errors: &'a mut Vec<String>,
+}
+
+impl FieldCheck<'_> {
+    fn run(&mut self) {
+        if let Some(default) = &self.field.default {
+            if !matches_type(default, self.category) {
+                self.error(format!("has default {} that is not a valid {}", show(default), self.field.field_type));
+            }
+        }
+
+        for constraint in self.field.constraints.iter().flatten() {
+            self.check_constraint(constraint);
+        }
+
+        self.check_range("min_length", "max_length");
+        self.check_range("min_value", "max_value");
+        self.check_default_against_constraints();
+    }
+
+    fn check_constraint(&mut self, constraint: &FieldConstraint) {
+        let name = constraint.constraint_type.as_str();
+        let applies = match name {
+            "min_length" | "max_length" | "length" => self.category.has_length(),
+            "min_value" | "max_value" => self.category.is_numeric(),
+            "pattern" | "email" => self.category == Category::Text,
+            _ => true,
+        };
+        if !applies {
+            let expected = match name {
+                "min_length" | "max_length" | "length" => "string or array",
+                "min_value" | "max_value" => "numeric",
+                _ => "string",
+            };
+            self.constraint_error(name, format!("only applies to {} fields, not {}", expected, self.field.field_type));
+            return;
+        }
+
+        match (name, &constraint.value) {
+            ("min_length" | "max_length" | "length", value) if as_count(value.as_ref()).is_none() => {
+                self.constraint_error(name, "needs a non-negative integer value".to_string());
+            },
+            ("min_value" | "max_value", Some(Value::Number(bound))) if self.category == Category::Integer && bound.fract() != 0.0 => {
+                self.constraint_error(name, format!("has bound {} on an integer field", bound));
+            },
+            ("min_value" | "max_value", Some(Value::Number(_))) => {},
+            ("min_value" | "max_value", _) => self.constraint_error(name, "needs a numeric value".to_string()),
+            ("pattern", Some(Value::String(pattern))) => {
+                if let Err(problem) = regex::Regex::new(pattern).map_err(|e| e.to_string()) {
+                    self.constraint_error(name, format!("has an invalid regex: {}", problem));
+                }
+            },
+            ("pattern", _) => self.constraint_error(name, "needs a regex string value".to_string()),
+            ("enum", Some(Value::Array(_))) => {},
+            ("enum", _) => self.constraint_error(name, "needs a list of allowed values".to_string()),
+            _ => {},
+        }
+    }
+
+    /// Flag `min > max` between two constraints
+    fn check_range(&mut self, min_name: &str, max_name: &str) {
+        if let (Some(min), Some(max)) = (self.number(min_name), self.number(max_name)) {
+            if min > max {
+                self.error(format!("has {} {} greater than {} {}", min_name, min, max_name, max));
+            }
+        }
+    }
+
+    fn check_default_against_constraints(&mut self) {
+        let Some(default) = &self.field.default else {
+            return;
+        };
+        if !matches_type(default, self.category) {
+            return;
+        }
+
+        if let Value::Number(value) = default {
+            if let Some(min) = self.number("min_value").filter(|min| value < min) {
+                self.error(format!("has default {} below min_value {}", show(default), min));
+            }
+            if let Some(max) = self.number("max_value").filter(|max| value > max) {
+                self.error(format!("has default {} above max_value {}", show(default), max));
+            }
+        }
+
+        let length = match default {
+            Value::String(text) => Some(text.chars().count() as f64),
+            Value::Array(items) => Some(items.len() as f64),
+            _ => None,
+        };
+        if let Some(length) = length {
+            if let Some(min) = self.number("min_length").filter(|min| length < *min) {
+                self.error(format!("has default {} shorter than min_length {}", show(default), min));
+            }
+            if let Some(max) = self.number("max_length").filter(|max| length > *max) {
+                self.error(format!("has default {} longer than max_length {}", show(default), max));
+            }
+        }
+
+        let allowed = self.constraint("enum").and_then(|constraint| match &constraint.value {
+            Some(Value::Array(allowed)) => Some(allowed),
+            _ => None,
+        });
+        if let Some(allowed) = allowed {
+            if !allowed.contains(default) {
+                self.error(format!("has default {} that is not one of its enum values", show(default)));
+            }
+        }
+    }
+
+    fn constraint(&self, name: &str) -> Option<&FieldConstraint> {
+        self.field.constraints.iter().flatten().find(|constraint| constraint.constraint_type == name)
+    }
+
+    fn number(&self, name: &str) -> Option<f64> {
+        match self.constraint(name)?.value {
+            Some(Value::Number(number)) => Some(number),
+            _ => None,
+        }
+    }
+
+    fn error(&mut self, problem: String) {
+        self.errors.push(format!(
+            "Field '{}' in entity '{}' {}",
+            self.field.name, self.entity.id, problem
+        ));
+    }
+
+    fn constraint_error(&mut self, constraint: &str, problem: String) {
+        self.errors.push(format!(
+            "Constraint '{}' on field '{}' in entity '{}' {}",
+            constraint, self.field.name, self.entity.id, problem
+        ));
+    }
+}
+
+fn matches_type(value: &Value, category: Category) -> bool {
+    match (category, value) {
+        (Category::Text, Value::String(_)) => true,
+        (Category::Integer, Value::Number(number)) => number.fract() == 0.0,
+        (Category::Number, Value::Number(_)) => true,
+        (Category::Boolean, Value::Boolean(_)) => true,
+        (Category::Date, Value::String(text)) => is_now(text) || chrono::NaiveDate::parse_from_str(text, "%Y-%m-%d").is_ok(),
+        (Category::DateTime, Value::String(text)) => {
+            is_now(text)
+                || chrono::DateTime::parse_from_rfc3339(text).is_ok()
+                || chrono::NaiveDateTime::parse_from_str(text, "%Y-%m-%dT%H:%M:%S").is_ok()
+        },
+        (Category::Array, Value::Array(_)) => true,
+        (Category::Object, Value::Object(_)) => true,
+        (Category::Enum, Value::String(_) | Value::Number(_)) => true,
+        _ => false,
+    }
+}
+
+/// Defaults such as `now` that stand for the current time rather than a literal value
+fn is_now(text: &str) -> bool {
+    matches!(text.to_ascii_lowercase().as_str(), "now" | "now()" | "current_timestamp" | "current_date" | "today")
+}
+
+fn as_count(value: Option<&Value>) -> Option<usize> {
+    match value? {
+        Value::Number(number) if *number >= 0.0 && number.fract() == 0.0 => Some(*number as usize),
+        _ => None,
+    }
+}
+
+fn show(value: &Value) -> String {
+    match value {
+        Value::String(text) => format!("\"{}\"", text),
+        Value::Number(number) => number.to_string(),
+        Value::Boolean(flag) => flag.to_string(),
+        Value::Array(items) => format!("[{}]", items.iter().map(show).collect::<Vec<_>>().join(", ")),
+        Value::Object(_) => "an object".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_fields() {
+        let spec = r#"
+entities:
+  - id: user
+    fields:
+      - name: age
+        type: integer
+        default: "abc"
+      - name: active
+        type: boolean
+        constraints:
+          - type: max_length
+            value: 5
+      - name: nickname
+        type: string
+        default: "superlongname"
+        constraints:
+          - type: min_length
+            value: 10
+          - type: max_length
+            value: 5
+      - name: code
+        type: string
+        constraints:
+          - type: pattern
+            value: "^[A-Z]{3,2}$"
+      - name: score
+        type: float
+        default: 2.5
+        constraints:
+          - type: min_value
+            value: 0
+          - type: max_value
+            value: 10
+      - name: joined
+        type: date
+        default: "2025-07-23"
+      - name: owner
+        type: account
+        default: 42
+"#;
+        let document: FdmlDocument = serde_yaml::from_str(spec).unwrap();
+        assert_eq!(check_fields(&document), vec![
+            "Field 'age' in entity 'user' has default \"abc\" that is not a valid integer",
+            "Constraint 'max_length' on field 'active' in entity 'user' only applies to string or array fields, not boolean",
+            "Field 'nickname' in entity 'user' has min_length 10 greater than max_length 5",
+            "Field 'nickname' in entity 'user' has default \"superlongname\" longer than max_length 5",
+            "Constraint 'pattern' on field 'code' in entity 'user' has an invalid regex: regex parse error:\n    ^[A-Z]{3,2}$\n          ^^^^^\nerror: invalid repetition count range, the start must be <= the end",
+        ]);
+    }
+
+    #[test]
+    fn test_pattern_constraints() {
+        let pattern_errors = |pattern: &str| {
+            let spec = format!(
+                "entities:\n  - id: user\n    fields:\n      - name: code\n        type: string\n        constraints:\n          - type: pattern\n            value: '{}'\n",
+                pattern
+            );
+            let document: FdmlDocument = serde_yaml::from_str(&spec).unwrap();
+            check_fields(&document).len()
+        };
+        assert_eq!(pattern_errors(r"^[a-z0-9._%+-]+@[a-z0-9.-]+\.[a-z]{2,}$"), 0);
+        assert_eq!(pattern_errors(r"(?i)^(foo|bar)+?\d{4}$"), 0);
+        assert_eq!(pattern_errors(r"^\p{L}+$"), 0);
+        assert_eq!(pattern_errors("(abc"), 1);
+        assert_eq!(pattern_errors("*abc"), 1);
+        assert_eq!(pattern_errors(r"^(?=x)a$"), 1);
+        assert_eq!(pattern_errors(r"(a)\1"), 1);
+    }
+}
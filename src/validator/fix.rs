@@ -0,0 +1,505 @@
+//! Safe automatic fixes for validation findings (`fdml validate --fix`).
+
+use crate::error::{FdmlError, Result};
+use crate::parser::ast::FdmlDocument;
+use crate::parser::yaml_edit::update_yaml;
+use crate::validator::rules::{AppliedFix, Validator};
+use crate::validator::references::{reference_slots, reference_values_mut, unresolved_references};
+use crate::validator::suggest::{is_known_field_type, suggest_field_type, suggest_relation, RELATIONS};
+use serde_yaml::Value;
+use std::collections::HashSet;
+
+/// Fixed file content and the fixes that produced it
+#[derive(Debug, Clone, PartialEq)]
+pub struct FixOutcome {
+    pub content: String,
+    pub fixes: Vec<AppliedFix>,
+}
+
+/// Apply every safe fix to an FDML file's content, keeping its formatting and comments
+pub fn fix_content(validator: &Validator, content: &str) -> Result<FixOutcome> {
+    let mut value: Value = serde_yaml::from_str(content)
+        .map_err(|e| FdmlError::simple_parser_error(format!("YAML parsing failed: {}", e)))?;
+
+    // Scenarios without an `id` key don't deserialize at all, so they're completed first
+    let mut fixes: Vec<AppliedFix> = fill_missing_scenario_ids(&mut value)
+        .into_iter()
+        .map(|description| AppliedFix { rule: "required_ids".to_string(), description })
+        .collect();
+    let content = if fixes.is_empty() {
+        content.to_string()
+    } else {
+        update_yaml(content, &value)?
+    };
+
+    let mut document: FdmlDocument = serde_yaml::from_value(value)
+        .map_err(|e| FdmlError::simple_parser_error(format!("YAML parsing failed: {}", e)))?;
+    let applied = validator.fix(&mut document);
+    if applied.is_empty() {
+        return Ok(FixOutcome { content, fixes });
+    }
+
+    fixes.extend(applied);
+    Ok(FixOutcome { content: update_yaml(&content, &document)?, fixes })
+}
+
+fn fill_missing_scenario_ids(value: &mut Value) -> Vec<String> {
+    let mut taken = HashSet::new();
+    collect_ids(value, &mut taken);
+
+    let mut fixes = Vec::new();
+    let Some(features) = value.get_mut("features").and_then(Value::as_sequence_mut) else {
+        return fixes;
+    };
+    for feature in features {
+        let feature_id = feature.get("id").and_then(Value::as_str).unwrap_or_default().to_string();
+        let Some(scenarios) = feature.get_mut("scenarios").and_then(Value::as_sequence_mut) else {
+            continue;
+        };
+        for (index, scenario) in scenarios.iter_mut().enumerate() {
+            let Some(mapping) = scenario.as_mapping_mut() else {
+                continue;
+            };
+            if mapping.contains_key("id") {
+                continue;
+            }
+            let title = mapping.get("title").and_then(Value::as_str).unwrap_or_default();
+            let id = unique_id(&scenario_base_id(title, &feature_id, index), &mut taken);
+            fixes.push(format!("Generated id '{}' for scenario {} in feature '{}'", id, index, feature_id));
+            mapping.insert(Value::from("id"), Value::from(id));
+        }
+    }
+    fixes
+}
+
+fn collect_ids(value: &Value, ids: &mut HashSet<String>) {
+    match value {
+        Value::Mapping(mapping) => {
+            if let Some(id) = mapping.get("id").and_then(Value::as_str) {
+                ids.insert(id.to_string());
+            }
+            mapping.values().for_each(|value| collect_ids(value, ids));
+        }
+        Value::Sequence(items) => items.iter().for_each(|value| collect_ids(value, ids)),
+        _ => {}
+    }
+}
+
+/// Fixer for `required_ids`: derives missing IDs from names and titles
+pub fn fix_required_ids(document: &mut FdmlDocument) -> Vec<String> {
+    let mut taken: HashSet<String> = element_ids(document).into_iter().collect();
+    let mut fixes = Vec::new();
+    let mut assign = |id: &mut String, base: String, label: String, taken: &mut HashSet<String>| {
+        if !id.is_empty() || base.is_empty() {
+            return;
+        }
+        *id = unique_id(&base, taken);
+        fixes.push(format!("Generated id '{}' for {}", id, label));
+    };
+
+    for (index, entity) in document.entities.iter_mut().enumerate() {
+        let base = to_snake_case(entity.name.as_deref().unwrap_or_default());
+        assign(&mut entity.id, base, format!("entity at index {}", index), &mut taken);
+    }
+    for (index, feature) in document.features.iter_mut().enumerate() {
+        let base = to_snake_case(&feature.title);
+        assign(&mut feature.id, base, format!("feature at index {}", index), &mut taken);
+        for (scenario_index, scenario) in feature.scenarios.iter_mut().enumerate() {
+            let base = scenario_base_id(&scenario.title, &feature.id, scenario_index);
+            let label = format!("scenario {} in feature '{}'", scenario_index, feature.id);
+            assign(&mut scenario.id, base, label, &mut taken);
+        }
+    }
+    for (index, action) in document.actions.iter_mut().enumerate() {
+        let base = to_snake_case(action.name.as_deref().unwrap_or_default());
+        assign(&mut action.id, base, format!("action at index {}", index), &mut taken);
+    }
+    for (index, flow) in document.flows.iter_mut().enumerate() {
+        let base = to_snake_case(&flow.name);
+        assign(&mut flow.id, base, format!("flow at index {}", index), &mut taken);
+    }
+    for (index, constraint) in document.constraints.iter_mut().enumerate() {
+        let base = to_snake_case(&constraint.name);
+        assign(&mut constraint.id, base, format!("constraint at index {}", index), &mut taken);
+    }
+
+    fixes
+}
+
+/// Fixer for `id_format`: renames IDs to snake_case and updates references to them
+pub fn fix_id_format(document: &mut FdmlDocument) -> Vec<String> {
+    let mut taken: HashSet<String> = element_ids(document).into_iter().collect();
+    let mut fixes = Vec::new();
+
+    for (index, old) in element_ids(document).into_iter().enumerate() {
+        if old.is_empty() || is_snake_case(&old) {
+            continue;
+        }
+        let new = to_snake_case(&old);
+        if !is_snake_case(&new) || taken.contains(&new) {
+            continue;
+        }
+
+        *element_ids_mut(document).swap_remove(index) = new.clone();
+        taken.insert(new.clone());
+        let updated = if element_ids(document).contains(&old) {
+            // Another element still owns the old ID, so references keep pointing at it
+            0
+        } else {
+            rename_references(document, &old, &new)
+        };
+        fixes.push(match updated {
+            0 => format!("Renamed '{}' to '{}'", old, new),
+            count => format!("Renamed '{}' to '{}' ({} reference(s) updated)", old, new, count),
+        });
+    }
+
+    fixes
+}
+
+/// Fixer for `unique_ids`: suffixes every repeated ID after its first use
+pub fn fix_unique_ids(document: &mut FdmlDocument) -> Vec<String> {
+    let mut taken: HashSet<String> = element_ids(document).into_iter().collect();
+    let mut seen = HashSet::new();
+    let mut fixes = Vec::new();
+
+    for id in element_ids_mut(document) {
+        if id.is_empty() || seen.insert(id.clone()) {
+            continue;
+        }
+        let renamed = unique_id(&format!("{}_2", id), &mut taken);
+        fixes.push(format!("Renamed duplicate id '{}' to '{}'", id, renamed));
+        seen.insert(renamed.clone());
+        *id = renamed;
+    }
+
+    fixes
+}
+
+/// Fixer for `valid_references`: applies the suggestion reported for each unknown reference
+pub fn fix_references(document: &mut FdmlDocument) -> Vec<String> {
+    let slots = reference_slots(document);
+    let unresolved = unresolved_references(document);
+    let mut values = reference_values_mut(document);
+
+    unresolved
+        .into_iter()
+        .filter_map(|reference| {
+            let suggestion = reference.suggestion?;
+            let slot = &slots[reference.slot];
+            let old = std::mem::replace(values[reference.slot], suggestion);
+            Some(format!(
+                "Replaced unknown {} '{}' with '{}' in {}",
+                slot.label, old, values[reference.slot], slot.owner
+            ))
+        })
+        .collect()
+}
+
+/// Fixer for `field_types`: applies the suggested type to misspelled field types
+pub fn fix_field_types(document: &mut FdmlDocument) -> Vec<String> {
+    let mut replacements = Vec::new();
+    for (index, entity) in document.entities.iter().enumerate() {
+        for (position, field) in entity.fields.iter().enumerate() {
+            if field.field_type.is_empty() || is_known_field_type(document, &field.field_type) {
+                continue;
+            }
+            if let Some(suggestion) = suggest_field_type(document, &field.field_type) {
+                replacements.push((index, position, suggestion.to_string()));
+            }
+        }
+    }
+
+    replacements
+        .into_iter()
+        .map(|(index, position, suggestion)| {
+            let entity = &mut document.entities[index];
+            let field = &mut entity.fields[position];
+            let old = std::mem::replace(&mut field.field_type, suggestion);
+            format!(
+                "Changed type of field '{}' in entity '{}' from '{}' to '{}'",
+                field.name, entity.id, old, field.field_type
+            )
+        })
+        .collect()
+}
+
+/// Fixer for `traceability_relations`: applies the suggested relation to misspelled relations
+pub fn fix_relations(document: &mut FdmlDocument) -> Vec<String> {
+    let mut fixes = Vec::new();
+    for trace in &mut document.traceability {
+        if RELATIONS.contains(&trace.relation.as_str()) {
+            continue;
+        }
+        if let Some(suggestion) = suggest_relation(&trace.relation) {
+            fixes.push(format!(
+                "Replaced unknown relation '{}' with '{}' from '{}' to '{}'",
+                trace.relation, suggestion, trace.from, trace.to
+            ));
+            trace.relation = suggestion.to_string();
+        }
+    }
+    fixes
+}
+
+/// IDs of all identifiable elements, in the order the validator checks them
+fn element_ids(document: &FdmlDocument) -> Vec<String> {
+    let mut ids: Vec<String> = document.entities.iter().map(|entity| entity.id.clone()).collect();
+    for feature in &document.features {
+        ids.push(feature.id.clone());
+        ids.extend(feature.scenarios.iter().map(|scenario| scenario.id.clone()));
+    }
+    ids.extend(document.actions.iter().map(|action| action.id.clone()));
+    ids.extend(document.flows.iter().map(|flow| flow.id.clone()));
+    ids.extend(document.constraints.iter().map(|constraint| constraint.id.clone()));
+    ids
+}
+
+/// Mutable counterpart of [`element_ids`], in the same order
+fn element_ids_mut(document: &mut FdmlDocument) -> Vec<&mut String> {
+    let mut ids: Vec<&mut String> = document.entities.iter_mut().map(|entity| &mut entity.id).collect();
+    for feature in &mut document.features {
+        ids.push(&mut feature.id);
+        ids.extend(feature.scenarios.iter_mut().map(|scenario| &mut scenario.id));
+    }
+    ids.extend(document.actions.iter_mut().map(|action| &mut action.id));
+    ids.extend(document.flows.iter_mut().map(|flow| &mut flow.id));
+    ids.extend(document.constraints.iter_mut().map(|constraint| &mut constraint.id));
+    ids
+}
+
+/// Point references to `old` at `new`, including field types naming a renamed entity
+pub(crate) fn rename_references(document: &mut FdmlDocument, old: &str, new: &str) -> usize {
+    let slots = reference_slots(document);
+    let mut count = 0;
+    for (slot, value) in slots.iter().zip(reference_values_mut(document)) {
+        if slot.refers_to_elements() && value == old {
+            *value = new.to_string();
+            count += 1;
+        }
+    }
+
+    if document.entities.iter().any(|entity| entity.id == new) {
+        for field in document.entities.iter_mut().flat_map(|entity| &mut entity.fields) {
+            if field.field_type == old {
+                field.field_type = new.to_string();
+                count += 1;
+            }
+        }
+    }
+    count
+}
+
+fn scenario_base_id(title: &str, feature_id: &str, index: usize) -> String {
+    match to_snake_case(title) {
+        base if base.is_empty() => format!("{}_scenario_{}", feature_id, index + 1),
+        base => base,
+    }
+}
+
+/// `base`, or `base` with the lowest numeric suffix not already taken; the result is marked taken
+pub(crate) fn unique_id(base: &str, taken: &mut HashSet<String>) -> String {
+    let stem = base.rsplit_once('_').filter(|(_, n)| n.parse::<usize>().is_ok()).map_or(base, |(stem, _)| stem);
+    let mut id = base.to_string();
+    let mut suffix = 2;
+    while taken.contains(&id) {
+        id = format!("{}_{}", stem, suffix);
+        suffix += 1;
+    }
+    taken.insert(id.clone());
+    id
+}
+
+/// Whether every dot-separated segment of an ID is lowercase letters, digits and underscores
+pub fn is_snake_case(id: &str) -> bool {
+    id.split('.').all(|segment| {
+        !segment.is_empty()
+            && !segment.starts_with('_')
+            && !segment.ends_with('_')
+            && !segment.contains("__")
+            && segment.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+    })
+}
+
+/// Convert a name, title or ID to snake_case, keeping dots as namespace separators
+pub fn to_snake_case(text: &str) -> String {
+    text.split('.').map(snake_segment).collect::<Vec<_>>().join(".")
+}
+
+fn snake_segment(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut out = String::new();
+
+    for (index, &c) in chars.iter().enumerate() {
+        if !c.is_alphanumeric() {
+            if !out.is_empty() && !out.ends_with('_') {
+                out.push('_');
+            }
+            continue;
+        }
+        if c.is_uppercase() && index > 0 {
+            let previous = chars[index - 1];
+            let next_lower = chars.get(index + 1).is_some_and(|next| next.is_lowercase());
+            let boundary = previous.is_lowercase() || previous.is_ascii_digit() || (previous.is_uppercase() && next_lower);
+            if boundary && !out.ends_with('_') {
+                out.push('_');
+            }
+        }
+        out.extend(c.to_lowercase());
+    }
+
+    out.trim_end_matches('_').to_string()
+}
+
+/// Unified diff of two versions of a file, with three lines of context
+pub fn unified_diff(path: &str, old: &str, new: &str) -> String {
+    const CONTEXT: usize = 3;
+    let a: Vec<&str> = old.lines().collect();
+    let b: Vec<&str> = new.lines().collect();
+
+    // Longest common subsequence lengths of every pair of suffixes
+    let mut lcs = vec![vec![0u32; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lcs[i][j] = if a[i] == b[j] { lcs[i + 1][j + 1] + 1 } else { lcs[i + 1][j].max(lcs[i][j + 1]) };
+        }
+    }
+
+    // (marker, line, old position, new position)
+    let mut ops: Vec<(char, &str, usize, usize)> = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() || j < b.len() {
+        if i < a.len() && j < b.len() && a[i] == b[j] {
+            ops.push((' ', a[i], i, j));
+            i += 1;
+            j += 1;
+        } else if j == b.len() || (i < a.len() && lcs[i + 1][j] >= lcs[i][j + 1]) {
+            ops.push(('-', a[i], i, j));
+            i += 1;
+        } else {
+            ops.push(('+', b[j], i, j));
+            j += 1;
+        }
+    }
+
+    let mut diff = String::new();
+    let mut k = 0;
+    while k < ops.len() {
+        if ops[k].0 == ' ' {
+            k += 1;
+            continue;
+        }
+        let mut last = k;
+        for (m, op) in ops.iter().enumerate().skip(k + 1) {
+            if op.0 == ' ' {
+                continue;
+            }
+            if m - last > 2 * CONTEXT {
+                break;
+            }
+            last = m;
+        }
+        let start = k.saturating_sub(CONTEXT);
+        let end = (last + CONTEXT + 1).min(ops.len());
+        let hunk = &ops[start..end];
+
+        let old_len = hunk.iter().filter(|op| op.0 != '+').count();
+        let new_len = hunk.iter().filter(|op| op.0 != '-').count();
+        let old_start = if old_len == 0 { hunk[0].2 } else { hunk[0].2 + 1 };
+        let new_start = if new_len == 0 { hunk[0].3 } else { hunk[0].3 + 1 };
+        if diff.is_empty() {
+            diff.push_str(&format!("--- {}\n+++ {}\n", path, path));
+        }
+        diff.push_str(&format!("@@ -{},{} +{},{} @@\n", old_start, old_len, new_start, new_len));
+        for (marker, line, _, _) in hunk {
+            diff.push_str(&format!("{}{}\n", marker, line));
+        }
+        k = end;
+    }
+
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SPEC: &str = r#"# Shop spec
+entities:
+  - id: UserProfile   # account holder
+    name: User Profile
+    fields: []
+  - id: cart
+    name: Cart
+    fields: []
+features:
+  - id: checkout
+    title: Checkout
+    dependencies: [cart, UserProfile]
+    scenarios:
+      - title: Happy path checkout
+        given: [a cart]
+        when: [user pays]
+        then: [order is placed]
+  - id: cart
+    title: Cart page
+    dependencies: [chekout]
+    scenarios: []
+"#;
+
+    #[test]
+    fn test_fix_content_applies_safe_fixes() {
+        let outcome = fix_content(&Validator::new(), SPEC).unwrap();
+        let document: FdmlDocument = serde_yaml::from_str(&outcome.content).unwrap();
+
+        assert_eq!(document.entities[0].id, "user_profile");
+        assert_eq!(document.features[0].dependencies, Some(vec!["cart".to_string(), "user_profile".to_string()]));
+        assert_eq!(document.features[0].scenarios[0].id, "happy_path_checkout");
+        assert_eq!(document.features[1].id, "cart_2");
+        assert_eq!(document.features[1].dependencies, Some(vec!["checkout".to_string()]));
+        assert!(Validator::new().validate(&document).unwrap().is_empty());
+
+        // Formatting and comments survive
+        assert!(outcome.content.starts_with("# Shop spec\n"));
+        assert!(outcome.content.contains("  - id: user_profile   # account holder\n"));
+        assert!(outcome.content.contains("dependencies: [cart, user_profile]"));
+        assert_eq!(outcome.fixes.len(), 4);
+    }
+
+    #[test]
+    fn test_fix_renames_entity_used_as_field_type() {
+        let spec = "entities:\n  - id: ShippingAddress\n    fields:\n      - name: home\n        type: ShippingAddress\n";
+        let outcome = fix_content(&Validator::new(), spec).unwrap();
+        let document: FdmlDocument = serde_yaml::from_str(&outcome.content).unwrap();
+
+        assert_eq!(document.entities[0].id, "shipping_address");
+        assert_eq!(document.entities[0].fields[0].field_type, "shipping_address");
+        assert_eq!(outcome.fixes[0].description, "Renamed 'ShippingAddress' to 'shipping_address' (1 reference(s) updated)");
+    }
+
+    #[test]
+    fn test_fix_never_points_a_reference_at_its_owner() {
+        let spec = "features:\n  - id: user_oauth\n    title: OAuth login\n    scenarios: []\n    dependencies: [user_auth]\n";
+        let outcome = fix_content(&Validator::new(), spec).unwrap();
+
+        assert_eq!(outcome.content, spec);
+        assert!(outcome.fixes.is_empty());
+    }
+
+    #[test]
+    fn test_to_snake_case() {
+        assert_eq!(to_snake_case("UserProfile"), "user_profile");
+        assert_eq!(to_snake_case("user-profile"), "user_profile");
+        assert_eq!(to_snake_case("HTTPRequest id"), "http_request_id");
+        assert_eq!(to_snake_case("Orders.LineItem"), "orders.line_item");
+        assert!(is_snake_case("orders.line_item_2"));
+        assert!(!is_snake_case("lineItem"));
+    }
+
+    #[test]
+    fn test_unified_diff() {
+        let diff = unified_diff("spec.fdml", "a\nb\nc\n", "a\nB\nc\n");
+        assert_eq!(diff, "--- spec.fdml\n+++ spec.fdml\n@@ -1,3 +1,3 @@\n a\n-b\n+B\n c\n");
+        assert!(unified_diff("spec.fdml", "a\n", "a\n").is_empty());
+    }
+}
@@ -1,3 +1,15 @@
+pub mod conditions;
+pub mod dependencies;
+pub mod fields;
+pub mod fix;
+pub mod naming;
+pub mod principles;
+pub mod references;
+pub mod report;
 pub mod rules;
+pub mod scenarios;
+pub mod suggest;
+pub mod unused;
 
-pub use rules::Validator;
\ No newline at end of file
+pub use dependencies::DependencyGraph;
+pub use rules::{AppliedFix, RuleFindings, Validator};
@@ -0,0 +1,444 @@
+//! Naming conventions for IDs and field names, so the identifiers generated from them
+//! are valid and distinct in every target language.
+
+use crate::parser::ast::FdmlDocument;
+use crate::validator::fix::{is_snake_case, rename_references, to_snake_case, unique_id};
+use std::collections::{HashMap, HashSet};
+
+/// Longest ID or field name accepted
+pub const MAX_NAME_LENGTH: usize = 64;
+
+const PYTHON_KEYWORDS: &[&str] = &[
+    "False", "None", "True", "and", "as", "assert", "async", "await", "break", "class", "continue", "def", "del", "elif",
+    "else", "except", "finally", "for", "from", "global", "if", "import", "in", "is", "lambda", "nonlocal", "not", "or",
+    "pass", "raise", "return", "try", "while", "with", "yield",
+];
+
+const TYPESCRIPT_KEYWORDS: &[&str] = &[
+    "break", "case", "catch", "class", "const", "continue", "debugger", "default", "delete", "do", "else", "enum", "export",
+    "extends", "false", "finally", "for", "function", "if", "implements", "import", "in", "instanceof", "interface", "let",
+    "new", "null", "package", "private", "protected", "public", "return", "static", "super", "switch", "this", "throw",
+    "true", "try", "typeof", "var", "void", "while", "with", "yield",
+];
+
+/// Target languages that reserve a name, either as written (field names, function names)
+/// or in its PascalCase form (type names). Go output only uses exported PascalCase
+/// identifiers, which none of its keywords can clash with.
+pub fn reserved_in(name: &str) -> Vec<&'static str> {
+    let mut languages = Vec::new();
+    if PYTHON_KEYWORDS.contains(&name) || PYTHON_KEYWORDS.contains(&generated_name(name).as_str()) {
+        languages.push("Python");
+    }
+    if TYPESCRIPT_KEYWORDS.contains(&name) {
+        languages.push("TypeScript");
+    }
+    languages
+}
+
+/// The PascalCase identifier the generators derive from a name, which only split on `_`
+pub fn generated_name(name: &str) -> String {
+    name.split('_')
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                None => String::new(),
+                Some(first) => first.to_uppercase().collect::<String>() + chars.as_str(),
+            }
+        })
+        .collect()
+}
+
+/// An ID or field name, as listed by [`names`]
+struct Name {
+    /// e.g. `Entity ID` or `Field name`
+    label: &'static str,
+    /// Suffix for renames away from reserved words, e.g. `entity`
+    kind: &'static str,
+    value: String,
+    /// Entity index of field names, which only need to be distinct within their entity
+    entity: Option<usize>,
+    /// e.g. ` in entity 'user'` for field names
+    context: String,
+}
+
+impl Name {
+    fn describe(&self) -> String {
+        format!("{} '{}'{}", self.label, self.value, self.context)
+    }
+
+    /// [`Name::describe`] for use mid-sentence, e.g. `entity ID 'class'`
+    fn describe_inline(&self) -> String {
+        let mut chars = self.label.chars();
+        let label: String = chars.next().map(|first| first.to_lowercase().chain(chars).collect()).unwrap_or_default();
+        format!("{} '{}'{}", label, self.value, self.context)
+    }
+}
+
+/// Every element ID, then every field name, in the order of [`names_mut`]
+fn names(document: &FdmlDocument) -> Vec<Name> {
+    let mut names = Vec::new();
+    let mut id = |label: &'static str, kind: &'static str, value: &str| {
+        names.push(Name { label, kind, value: value.to_string(), entity: None, context: String::new() });
+    };
+
+    for entity in &document.entities {
+        id("Entity ID", "entity", &entity.id);
+    }
+    for feature in &document.features {
+        id("Feature ID", "feature", &feature.id);
+        for scenario in &feature.scenarios {
+            id("Scenario ID", "scenario", &scenario.id);
+        }
+    }
+    for action in &document.actions {
+        id("Action ID", "action", &action.id);
+    }
+    for flow in &document.flows {
+        id("Flow ID", "flow", &flow.id);
+    }
+    for constraint in &document.constraints {
+        id("Constraint ID", "constraint", &constraint.id);
+    }
+
+    for (index, entity) in document.entities.iter().enumerate() {
+        for field in &entity.fields {
+            names.push(Name {
+                label: "Field name",
+                kind: "field",
+                value: field.name.clone(),
+                entity: Some(index),
+                context: format!(" in entity '{}'", entity.id),
+            });
+        }
+    }
+
+    names
+}
+
+/// Mutable counterpart of [`names`], in the same order
+fn names_mut(document: &mut FdmlDocument) -> Vec<&mut String> {
+    let mut ids = Vec::new();
+    let mut fields = Vec::new();
+    for entity in &mut document.entities {
+        ids.push(&mut entity.id);
+        fields.extend(entity.fields.iter_mut().map(|field| &mut field.name));
+    }
+    for feature in &mut document.features {
+        ids.push(&mut feature.id);
+        ids.extend(feature.scenarios.iter_mut().map(|scenario| &mut scenario.id));
+    }
+    ids.extend(document.actions.iter_mut().map(|action| &mut action.id));
+    ids.extend(document.flows.iter_mut().map(|flow| &mut flow.id));
+    ids.extend(document.constraints.iter_mut().map(|constraint| &mut constraint.id));
+    ids.extend(fields);
+    ids
+}
+
+/// Names in use, per scope (element IDs, or the fields of one entity), with the identifiers they generate
+struct Taken {
+    values: HashMap<Option<usize>, HashSet<String>>,
+    generated: HashMap<Option<usize>, HashSet<String>>,
+}
+
+impl Taken {
+    fn new(names: &[Name]) -> Self {
+        let mut taken = Self { values: HashMap::new(), generated: HashMap::new() };
+        for name in names {
+            taken.values.entry(name.entity).or_default().insert(name.value.clone());
+            taken.generated.entry(name.entity).or_default().insert(generated_name(&to_snake_case(&name.value)));
+        }
+        taken
+    }
+
+    fn contains(&self, scope: Option<usize>, value: &str) -> bool {
+        self.values.get(&scope).is_some_and(|values| values.contains(value))
+    }
+
+    /// `base`, or `base` with a numeric suffix, such that neither it nor its generated identifier is in use
+    fn claim(&mut self, scope: Option<usize>, base: &str) -> String {
+        let values = self.values.entry(scope).or_default();
+        let generated = self.generated.entry(scope).or_default();
+        loop {
+            let candidate = unique_id(base, values);
+            if generated.insert(generated_name(&candidate)) {
+                return candidate;
+            }
+        }
+    }
+}
+
+/// Rename the `index`th name to `new`, updating what refers to it, and describe the change
+fn rename(document: &mut FdmlDocument, name: &Name, index: usize, new: &str) -> String {
+    *names_mut(document).swap_remove(index) = new.to_string();
+
+    let updated = match name.entity {
+        // Another element still owns the old ID, so references keep pointing at it
+        None if names(document).iter().any(|other| other.entity.is_none() && other.value == name.value) => 0,
+        None => rename_references(document, &name.value, new),
+        Some(entity) => rename_field_references(document, entity, &name.value, new),
+    };
+    match updated {
+        0 => format!("Renamed {} to '{}'", name.describe_inline(), new),
+        count => format!("Renamed {} to '{}' ({} reference(s) updated)", name.describe_inline(), new, count),
+    }
+}
+
+/// Update the field lists of action inputs and outputs for the entity's renamed field
+fn rename_field_references(document: &mut FdmlDocument, entity: usize, old: &str, new: &str) -> usize {
+    let entity_id = document.entities[entity].id.clone();
+    let mut count = 0;
+    for action in &mut document.actions {
+        for data in [&mut action.input, &mut action.output].into_iter().flatten() {
+            if data.entity.as_deref() != Some(entity_id.as_str()) {
+                continue;
+            }
+            for field in data.fields.iter_mut().flatten().filter(|field| *field == old) {
+                *field = new.to_string();
+                count += 1;
+            }
+        }
+    }
+    count
+}
+
+pub fn check_field_names(document: &FdmlDocument) -> Vec<String> {
+    names(document)
+        .iter()
+        .filter(|name| name.entity.is_some() && !name.value.is_empty() && !is_snake_case(&name.value))
+        .map(|name| format!("{} should be snake_case", name.describe()))
+        .collect()
+}
+
+/// Fixer for `field_name_format`: renames fields to snake_case unless that name is taken
+pub fn fix_field_names(document: &mut FdmlDocument) -> Vec<String> {
+    let names = names(document);
+    let mut taken = Taken::new(&names);
+    let mut fixes = Vec::new();
+
+    for (index, name) in names.iter().enumerate() {
+        if name.entity.is_none() || name.value.is_empty() || is_snake_case(&name.value) {
+            continue;
+        }
+        let new = to_snake_case(&name.value);
+        if !is_snake_case(&new) || taken.contains(name.entity, &new) {
+            continue;
+        }
+        // Its generated identifier is already reserved, as the old name's
+        taken.values.entry(name.entity).or_default().insert(new.clone());
+        fixes.push(rename(document, name, index, &new));
+    }
+
+    fixes
+}
+
+pub fn check_reserved_words(document: &FdmlDocument) -> Vec<String> {
+    names(document)
+        .iter()
+        .filter_map(|name| {
+            let languages = reserved_in(&name.value);
+            (!languages.is_empty())
+                .then(|| format!("{} is a reserved word in {}", name.describe(), languages.join(" and ")))
+        })
+        .collect()
+}
+
+/// Fixer for `reserved_words`: suffixes reserved names with their kind, e.g. `class_entity`
+pub fn fix_reserved_words(document: &mut FdmlDocument) -> Vec<String> {
+    let names = names(document);
+    let mut taken = Taken::new(&names);
+    let mut fixes = Vec::new();
+
+    for (index, name) in names.iter().enumerate() {
+        if reserved_in(&name.value).is_empty() {
+            continue;
+        }
+        let new = taken.claim(name.entity, &format!("{}_{}", name.value, name.kind));
+        fixes.push(rename(document, name, index, &new));
+    }
+
+    fixes
+}
+
+pub fn check_name_lengths(document: &FdmlDocument) -> Vec<String> {
+    names(document)
+        .iter()
+        .filter(|name| name.value.chars().count() > MAX_NAME_LENGTH)
+        .map(|name| format!("{} is longer than {} characters", name.describe(), MAX_NAME_LENGTH))
+        .collect()
+}
+
+/// Fixer for `name_length`: shortens names at a word boundary, leaving room for a numeric suffix
+pub fn fix_name_lengths(document: &mut FdmlDocument) -> Vec<String> {
+    let names = names(document);
+    let mut taken = Taken::new(&names);
+    let mut fixes = Vec::new();
+
+    for (index, name) in names.iter().enumerate() {
+        if name.value.chars().count() <= MAX_NAME_LENGTH {
+            continue;
+        }
+        let new = taken.claim(name.entity, &shorten(&name.value, MAX_NAME_LENGTH - 3));
+        fixes.push(rename(document, name, index, &new));
+    }
+
+    fixes
+}
+
+/// At most `limit` characters of `name`, cut after a whole word when that keeps most of it
+fn shorten(name: &str, limit: usize) -> String {
+    let cut: String = name.chars().take(limit).collect();
+    let cut = match cut.rfind('_') {
+        Some(boundary) if boundary >= limit / 2 => &cut[..boundary],
+        _ => cut.as_str(),
+    };
+    cut.trim_end_matches('_').to_string()
+}
+
+pub fn check_name_collisions(document: &FdmlDocument) -> Vec<String> {
+    let names = names(document);
+    let mut errors = Vec::new();
+    for (index, first, generated) in collisions(&names) {
+        errors.push(format!(
+            "{} collides with '{}' after case conversion (both become '{}')",
+            names[index].describe(),
+            names[first].value,
+            generated
+        ));
+    }
+    errors
+}
+
+/// Fixer for `name_collisions`: renames every colliding name after the first one
+pub fn fix_name_collisions(document: &mut FdmlDocument) -> Vec<String> {
+    let names = names(document);
+    let mut taken = Taken::new(&names);
+    let renames: Vec<(usize, String)> = collisions(&names)
+        .into_iter()
+        .map(|(index, _, _)| {
+            let name = &names[index];
+            (index, taken.claim(name.entity, &format!("{}_2", to_snake_case(&name.value))))
+        })
+        .collect();
+
+    renames.into_iter().map(|(index, new)| rename(document, &names[index], index, &new)).collect()
+}
+
+/// Indexes of names whose generated identifier matches an earlier, different name in the
+/// same scope, with the index of that earlier name and the shared identifier
+fn collisions(names: &[Name]) -> Vec<(usize, usize, String)> {
+    let mut first: HashMap<(Option<usize>, String), usize> = HashMap::new();
+    let mut found = Vec::new();
+    for (index, name) in names.iter().enumerate().filter(|(_, name)| !name.value.is_empty()) {
+        let generated = generated_name(&to_snake_case(&name.value));
+        match first.get(&(name.entity, generated.clone())) {
+            Some(&other) if names[other].value != name.value => found.push((index, other, generated)),
+            Some(_) => {},
+            None => {
+                first.insert((name.entity, generated), index);
+            },
+        }
+    }
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SPEC: &str = r#"
+entities:
+  - id: user_auth
+    fields:
+      - name: firstName
+        type: string
+      - name: from
+        type: string
+  - id: UserAuth
+    fields: []
+  - id: class
+    fields: []
+actions:
+  - id: login
+    input:
+      entity: user_auth
+      fields: [firstName, from]
+  - id: create_user_account_with_verified_email_address_and_default_notification_settings
+"#;
+
+    #[test]
+    fn test_naming_checks() {
+        let document: FdmlDocument = serde_yaml::from_str(SPEC).unwrap();
+
+        assert_eq!(check_field_names(&document), vec!["Field name 'firstName' in entity 'user_auth' should be snake_case"]);
+        assert_eq!(check_reserved_words(&document), vec![
+            "Entity ID 'class' is a reserved word in Python and TypeScript",
+            "Field name 'from' in entity 'user_auth' is a reserved word in Python",
+        ]);
+        assert_eq!(check_name_lengths(&document), vec![
+            "Action ID 'create_user_account_with_verified_email_address_and_default_notification_settings' is longer than 64 characters",
+        ]);
+        assert_eq!(check_name_collisions(&document), vec![
+            "Entity ID 'UserAuth' collides with 'user_auth' after case conversion (both become 'UserAuth')",
+        ]);
+        assert_eq!(reserved_in("none"), vec!["Python"]);
+    }
+
+    #[test]
+    fn test_naming_fixes() {
+        let mut document: FdmlDocument = serde_yaml::from_str(SPEC).unwrap();
+
+        assert_eq!(fix_field_names(&mut document), vec![
+            "Renamed field name 'firstName' in entity 'user_auth' to 'first_name' (1 reference(s) updated)",
+        ]);
+        assert_eq!(fix_reserved_words(&mut document), vec![
+            "Renamed entity ID 'class' to 'class_entity'",
+            "Renamed field name 'from' in entity 'user_auth' to 'from_field' (1 reference(s) updated)",
+        ]);
+        assert_eq!(fix_name_lengths(&mut document), vec![
+            "Renamed action ID 'create_user_account_with_verified_email_address_and_default_notification_settings' to 'create_user_account_with_verified_email_address_and_default'",
+        ]);
+        assert_eq!(fix_name_collisions(&mut document), vec!["Renamed entity ID 'UserAuth' to 'user_auth_2'"]);
+
+        assert_eq!(document.actions[0].input.as_ref().unwrap().fields, Some(vec!["first_name".to_string(), "from_field".to_string()]));
+        for check in [check_field_names, check_reserved_words, check_name_lengths, check_name_collisions] {
+            assert!(check(&document).is_empty());
+        }
+    }
+
+    #[test]
+    fn test_field_name_fix_reserves_only_the_new_name() {
+        let mut document: FdmlDocument = serde_yaml::from_str(
+            "entities:\n  - id: user\n    fields:\n      - name: firstName\n        type: string\n      - name: First_Name_2\n        type: string\n",
+        ).unwrap();
+
+        assert_eq!(fix_field_names(&mut document), vec![
+            "Renamed field name 'firstName' in entity 'user' to 'first_name'",
+            "Renamed field name 'First_Name_2' in entity 'user' to 'first_name_2'",
+        ]);
+    }
+
+    #[test]
+    fn test_naming_fixes_rename_field_types() {
+        let mut document: FdmlDocument = serde_yaml::from_str(r#"
+entities:
+  - id: class
+    fields: []
+  - id: user_auth
+    fields: []
+  - id: UserAuth
+    fields: []
+  - id: enrolment
+    fields:
+      - name: course
+        type: class
+      - name: student
+        type: UserAuth
+"#).unwrap();
+
+        assert_eq!(fix_reserved_words(&mut document), vec!["Renamed entity ID 'class' to 'class_entity' (1 reference(s) updated)"]);
+        assert_eq!(fix_name_collisions(&mut document), vec!["Renamed entity ID 'UserAuth' to 'user_auth_2' (1 reference(s) updated)"]);
+
+        let types: Vec<&str> = document.entities[3].fields.iter().map(|field| field.field_type.as_str()).collect();
+        assert_eq!(types, vec!["class_entity", "user_auth_2"]);
+    }
+}
@@ -0,0 +1,238 @@
+//! Design principle rules of extension 1.3.3 (section 16.5): known codes, strategies
+//! aligned with declared principles, and no conflicting principles within a group.
+
+use crate::parser::ast::{DesignPrinciples, FdmlDocument, PrincipleStrategy};
+use crate::principles::{conflict, lookup, PRINCIPLES};
+use crate::validator::suggest::did_you_mean;
+
+/// An element's declared principles and the strategies and hints that apply them
+pub struct PrincipledElement<'a> {
+    /// e.g. `Entity 'order'`
+    pub owner: String,
+    pub declared: Option<&'a DesignPrinciples>,
+    /// What applies a principle, e.g. `implementation strategy`, and the principle code
+    pub uses: Vec<(String, &'a str)>,
+}
+
+/// The system and every entity, action, flow and constraint, in document order
+pub fn principled_elements(document: &FdmlDocument) -> Vec<PrincipledElement<'_>> {
+    let mut elements = Vec::new();
+
+    if let Some(system) = &document.system {
+        elements.push(PrincipledElement {
+            owner: format!("System '{}'", system.id),
+            declared: system.design_principles.as_ref(),
+            uses: strategies("implementation strategy", &system.implementation_strategies),
+        });
+    }
+    for entity in &document.entities {
+        let mut uses = strategies("implementation strategy", &entity.implementation_strategies);
+        for field in &entity.fields {
+            if let Some(hint) = &field.design_hints {
+                uses.push((format!("design hint on field '{}'", field.name), hint.principle.as_str()));
+            }
+        }
+        elements.push(PrincipledElement {
+            owner: format!("Entity '{}'", entity.id),
+            declared: entity.design_principles.as_ref(),
+            uses,
+        });
+    }
+    for action in &document.actions {
+        let mut uses = strategies("execution strategy", &action.execution_strategies);
+        for exception in action.exceptions.iter().flatten() {
+            if let Some(recovery) = &exception.recovery_strategy {
+                uses.push((format!("recovery strategy for '{}'", exception.code), recovery.principle.as_str()));
+            }
+        }
+        elements.push(PrincipledElement {
+            owner: format!("Action '{}'", action.id),
+            declared: action.design_principles.as_ref(),
+            uses,
+        });
+    }
+    for flow in &document.flows {
+        let mut uses = strategies("optimization strategy", &flow.optimization_strategies);
+        for step in &flow.steps {
+            if let Some(hint) = &step.execution_hints {
+                uses.push((format!("execution hint on step '{}'", step.id), hint.principle.as_str()));
+            }
+        }
+        elements.push(PrincipledElement {
+            owner: format!("Flow '{}'", flow.id),
+            declared: flow.design_principles.as_ref(),
+            uses,
+        });
+    }
+    for constraint in &document.constraints {
+        let uses = constraint
+            .enforcement_strategy
+            .iter()
+            .map(|strategy| ("enforcement strategy".to_string(), strategy.principle.as_str()))
+            .collect();
+        elements.push(PrincipledElement {
+            owner: format!("Constraint '{}'", constraint.id),
+            declared: constraint.design_principles.as_ref(),
+            uses,
+        });
+    }
+
+    elements
+}
+
+fn strategies<'a>(kind: &str, strategies: &'a Option<Vec<PrincipleStrategy>>) -> Vec<(String, &'a str)> {
+    strategies.iter().flatten().map(|strategy| (kind.to_string(), strategy.principle.as_str())).collect()
+}
+
+/// The taxonomy code spelled differently, e.g. `Mo` for `mo` or `MO`
+fn suggest_code(code: &str) -> Option<&'static str> {
+    PRINCIPLES.iter().map(|principle| principle.code).find(|known| known.eq_ignore_ascii_case(code))
+}
+
+fn describe(code: &str) -> String {
+    match lookup(code) {
+        Some(principle) => format!("'{}' ({})", code, principle.name),
+        None => format!("'{}'", code),
+    }
+}
+
+/// Principle consistency: every declared or applied code is in the taxonomy
+pub fn check_codes(document: &FdmlDocument) -> Vec<String> {
+    let mut errors = Vec::new();
+    for element in principled_elements(document) {
+        for (group, codes) in element.declared.map(DesignPrinciples::groups).unwrap_or_default() {
+            for code in codes {
+                match lookup(code) {
+                    None => errors.push(did_you_mean(
+                        format!("{} declares unknown {} principle '{}'", element.owner, group.key(), code),
+                        suggest_code(code),
+                    )),
+                    Some(principle) if principle.group != group => errors.push(format!(
+                        "{} declares {} under {}, but it is a {} principle",
+                        element.owner, describe(code), group.key(), principle.group.key()
+                    )),
+                    Some(_) => {},
+                }
+            }
+        }
+        for (kind, code) in element.uses.iter().filter(|(_, code)| lookup(code).is_none()) {
+            errors.push(did_you_mean(
+                format!("{} {} references unknown principle '{}'", element.owner, kind, code),
+                suggest_code(code),
+            ));
+        }
+    }
+    errors
+}
+
+/// Strategy alignment: strategies and hints apply a principle the element (or the system) declares
+pub fn check_alignment(document: &FdmlDocument) -> Vec<String> {
+    let system = document.system.as_ref().and_then(|system| system.design_principles.as_ref());
+    let declared_by = |principles: Option<&DesignPrinciples>, code: &str| principles.is_some_and(|p| p.declares(code));
+
+    let mut errors = Vec::new();
+    for element in principled_elements(document) {
+        for (kind, code) in &element.uses {
+            if lookup(code).is_none() || declared_by(element.declared, code) || declared_by(system, code) {
+                continue;
+            }
+            errors.push(format!(
+                "{} {} applies principle {}, which is not among its design_principles",
+                element.owner,
+                kind,
+                describe(code)
+            ));
+        }
+    }
+    errors
+}
+
+/// Group coherence: no two conflicting principles are declared in the same group
+pub fn check_conflicts(document: &FdmlDocument) -> Vec<String> {
+    let mut errors = Vec::new();
+    for element in principled_elements(document) {
+        for (group, codes) in element.declared.map(DesignPrinciples::groups).unwrap_or_default() {
+            for (index, first) in codes.iter().enumerate() {
+                for second in &codes[index + 1..] {
+                    if let Some(reason) = conflict(first, second) {
+                        errors.push(format!(
+                            "{} declares conflicting {} principles {} and {}: {}",
+                            element.owner,
+                            group.key(),
+                            describe(first),
+                            describe(second),
+                            reason
+                        ));
+                    }
+                }
+            }
+        }
+    }
+    errors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_principle_rules() {
+        let spec = r#"
+system:
+  id: shop
+  name: Shop
+  components: []
+  relationships: []
+  design_principles:
+    reliability: [Ft]
+    security: [Mo]
+entities:
+  - id: order
+    design_principles:
+      efficiency: [Ha, mo]
+    implementation_strategies:
+      - principle: Lo
+        strategy: Denormalize customer data
+    fields:
+      - name: order_id
+        type: uuid
+        design_hints:
+          principle: Ha
+          hint: Use UUIDv7
+actions:
+  - id: process_payment
+    design_principles:
+      reliability: [At, Cr]
+    execution_strategies:
+      - principle: Ft
+        strategy: Circuit breaker
+    exceptions:
+      - code: PAYMENT_TIMEOUT
+        recovery_strategy:
+          principle: Zz
+          approach: Retry
+constraints:
+  - id: deny_by_default
+    name: Deny by default
+    type: security
+    rule: access is denied unless granted
+    enforcement_strategy:
+      principle: Cf
+      implementation: Default deny
+"#;
+        let document: FdmlDocument = serde_yaml::from_str(spec).unwrap();
+
+        assert_eq!(check_codes(&document), vec![
+            "System 'shop' declares 'Mo' (Modularity) under security, but it is a structure principle",
+            "Entity 'order' declares unknown efficiency principle 'mo' (did you mean 'Mo'?)",
+            "Action 'process_payment' recovery strategy for 'PAYMENT_TIMEOUT' references unknown principle 'Zz'",
+        ]);
+        assert_eq!(check_alignment(&document), vec![
+            "Entity 'order' implementation strategy applies principle 'Lo' (Locality of Reference), which is not among its design_principles",
+            "Constraint 'deny_by_default' enforcement strategy applies principle 'Cf' (Conservative Defaults), which is not among its design_principles",
+        ]);
+        assert_eq!(check_conflicts(&document), vec![
+            "Action 'process_payment' declares conflicting reliability principles 'At' (Atomic Execution) and 'Cr' (Consistency Relaxation): all-or-nothing execution needs the consistency that relaxation gives up",
+        ]);
+    }
+}
@@ -0,0 +1,308 @@
+//! Typed references: which kinds of element each referring field may point to.
+
+use crate::parser::ast::FdmlDocument;
+use crate::validator::suggest::closest_match;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ElementKind {
+    Entity,
+    Action,
+    Feature,
+    Scenario,
+    Flow,
+    Constraint,
+    /// Entry of `system.components`
+    Component,
+}
+
+impl ElementKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            ElementKind::Entity => "entity",
+            ElementKind::Action => "action",
+            ElementKind::Feature => "feature",
+            ElementKind::Scenario => "scenario",
+            ElementKind::Flow => "flow",
+            ElementKind::Constraint => "constraint",
+            ElementKind::Component => "component",
+        }
+    }
+
+    fn with_article(self) -> String {
+        match self {
+            ElementKind::Entity | ElementKind::Action => format!("an {}", self.name()),
+            _ => format!("a {}", self.name()),
+        }
+    }
+}
+
+use ElementKind::*;
+
+/// Anything with an ID, for links such as traceability that may connect any two elements
+const ANY_ELEMENT: &[ElementKind] = &[Entity, Action, Feature, Scenario, Flow, Constraint];
+
+/// A field that refers to another element by ID
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReferenceSlot {
+    /// Element holding the reference, as shown in messages, e.g. `Flow 'checkout' step 'pay'`
+    pub owner: String,
+    /// What the field refers to, e.g. `action` or `'from' element`
+    pub label: &'static str,
+    pub expects: &'static [ElementKind],
+    /// Kinds to draw "did you mean" suggestions from
+    pub suggest_from: &'static [ElementKind],
+    pub value: String,
+    /// ID of the element holding the reference; `None` for system relationships and traceability links
+    pub owner_id: Option<String>,
+}
+
+impl ReferenceSlot {
+    fn new(owner: String, label: &'static str, expects: &'static [ElementKind], value: &str) -> Self {
+        Self { owner, label, expects, suggest_from: expects, value: value.to_string(), owner_id: None }
+    }
+
+    fn owned_by(self, id: &str) -> Self {
+        Self { owner_id: Some(id.to_string()), ..self }
+    }
+
+    /// Whether the field refers to elements with IDs, rather than to system components
+    pub fn refers_to_elements(&self) -> bool {
+        !self.expects.contains(&Component)
+    }
+}
+
+/// Every reference in the document, in the order of [`reference_values_mut`]
+pub fn reference_slots(document: &FdmlDocument) -> Vec<ReferenceSlot> {
+    let mut slots = Vec::new();
+
+    if let Some(system) = &document.system {
+        for relationship in &system.relationships {
+            slots.push(ReferenceSlot::new("System relationship".to_string(), "'from' component", &[Component], &relationship.from));
+            slots.push(ReferenceSlot::new("System relationship".to_string(), "'to' component", &[Component], &relationship.to));
+        }
+    }
+    for entity in &document.entities {
+        for relationship in entity.relationships.iter().flatten() {
+            let owner = format!("Entity '{}' relationship", entity.id);
+            slots.push(ReferenceSlot::new(owner, "entity", &[Entity], &relationship.entity).owned_by(&entity.id));
+        }
+    }
+    for action in &document.actions {
+        for (label, data) in [("input entity", &action.input), ("output entity", &action.output)] {
+            if let Some(entity) = data.as_ref().and_then(|data| data.entity.as_deref()) {
+                slots.push(ReferenceSlot::new(format!("Action '{}'", action.id), label, &[Entity], entity).owned_by(&action.id));
+            }
+        }
+    }
+    for feature in &document.features {
+        for dependency in feature.dependencies.iter().flatten() {
+            // Any element may be depended on, but features are the usual target
+            slots.push(ReferenceSlot {
+                suggest_from: &[Feature],
+                ..ReferenceSlot::new(format!("Feature '{}'", feature.id), "dependency", ANY_ELEMENT, dependency).owned_by(&feature.id)
+            });
+        }
+        if let Some(entity) = &feature.entity {
+            slots.push(ReferenceSlot::new(format!("Feature '{}'", feature.id), "entity", &[Entity], entity).owned_by(&feature.id));
+        }
+    }
+    for flow in &document.flows {
+        for step in &flow.steps {
+            let owner = format!("Flow '{}' step '{}'", flow.id, step.id);
+            slots.push(ReferenceSlot::new(owner, "action", &[Action], &step.action).owned_by(&flow.id));
+        }
+    }
+    for constraint in &document.constraints {
+        for entity in constraint.entities.iter().flatten() {
+            slots.push(ReferenceSlot::new(format!("Constraint '{}'", constraint.id), "entity", &[Entity], entity).owned_by(&constraint.id));
+        }
+        for action in constraint.actions.iter().flatten() {
+            slots.push(ReferenceSlot::new(format!("Constraint '{}'", constraint.id), "action", &[Action], action).owned_by(&constraint.id));
+        }
+    }
+    for trace in &document.traceability {
+        slots.push(ReferenceSlot::new("Traceability".to_string(), "'from' element", ANY_ELEMENT, &trace.from));
+        slots.push(ReferenceSlot::new("Traceability".to_string(), "'to' element", ANY_ELEMENT, &trace.to));
+    }
+    for rule in &document.generation_rules {
+        for trigger in &rule.triggers {
+            let owner = format!("Generation rule '{}'", rule.id);
+            slots.push(ReferenceSlot::new(owner, "trigger", ANY_ELEMENT, trigger).owned_by(&rule.id));
+        }
+    }
+
+    slots
+}
+
+/// The referring fields themselves, in the order of [`reference_slots`]
+pub fn reference_values_mut(document: &mut FdmlDocument) -> Vec<&mut String> {
+    let mut values = Vec::new();
+
+    if let Some(system) = &mut document.system {
+        for relationship in &mut system.relationships {
+            values.push(&mut relationship.from);
+            values.push(&mut relationship.to);
+        }
+    }
+    for entity in &mut document.entities {
+        values.extend(entity.relationships.iter_mut().flatten().map(|relationship| &mut relationship.entity));
+    }
+    for action in &mut document.actions {
+        for data in [&mut action.input, &mut action.output].into_iter().flatten() {
+            values.extend(data.entity.as_mut());
+        }
+    }
+    for feature in &mut document.features {
+        values.extend(feature.dependencies.iter_mut().flatten());
+        values.extend(feature.entity.as_mut());
+    }
+    for flow in &mut document.flows {
+        values.extend(flow.steps.iter_mut().map(|step| &mut step.action));
+    }
+    for constraint in &mut document.constraints {
+        values.extend(constraint.entities.iter_mut().flatten());
+        values.extend(constraint.actions.iter_mut().flatten());
+    }
+    for trace in &mut document.traceability {
+        values.push(&mut trace.from);
+        values.push(&mut trace.to);
+    }
+    for rule in &mut document.generation_rules {
+        values.extend(rule.triggers.iter_mut());
+    }
+
+    values
+}
+
+/// Kinds of the elements defined under each ID
+pub fn defined_kinds(document: &FdmlDocument) -> HashMap<&str, Vec<ElementKind>> {
+    let mut kinds: HashMap<&str, Vec<ElementKind>> = HashMap::new();
+    if let Some(system) = &document.system {
+        for component in &system.components {
+            kinds.entry(component.as_str()).or_default().push(Component);
+        }
+    }
+    for entity in &document.entities {
+        kinds.entry(entity.id.as_str()).or_default().push(Entity);
+    }
+    for action in &document.actions {
+        kinds.entry(action.id.as_str()).or_default().push(Action);
+    }
+    for feature in &document.features {
+        kinds.entry(feature.id.as_str()).or_default().push(Feature);
+        for scenario in &feature.scenarios {
+            kinds.entry(scenario.id.as_str()).or_default().push(Scenario);
+        }
+    }
+    for flow in &document.flows {
+        kinds.entry(flow.id.as_str()).or_default().push(Flow);
+    }
+    for constraint in &document.constraints {
+        kinds.entry(constraint.id.as_str()).or_default().push(Constraint);
+    }
+    kinds.remove("");
+    kinds
+}
+
+/// A reference that doesn't resolve to an element of a kind its field accepts
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnresolvedReference {
+    /// Position in [`reference_slots`]
+    pub slot: usize,
+    pub message: String,
+    /// Likely intended ID, only for references to IDs that don't exist
+    pub suggestion: Option<String>,
+}
+
+pub fn unresolved_references(document: &FdmlDocument) -> Vec<UnresolvedReference> {
+    let kinds = defined_kinds(document);
+    let mut unresolved = Vec::new();
+
+    for (index, slot) in reference_slots(document).into_iter().enumerate() {
+        match kinds.get(slot.value.as_str()) {
+            Some(found) if found.iter().any(|kind| slot.expects.contains(kind)) => {},
+            Some(found) => {
+                let expected: Vec<String> = slot.expects.iter().map(|kind| kind.with_article()).collect();
+                unresolved.push(UnresolvedReference {
+                    slot: index,
+                    message: format!(
+                        "{} {} must be {}, but '{}' is {}",
+                        slot.owner,
+                        slot.label,
+                        expected.join(" or "),
+                        slot.value,
+                        found[0].with_article()
+                    ),
+                    suggestion: None,
+                });
+            },
+            None => {
+                let candidates = kinds
+                    .iter()
+                    .filter(|(_, found)| found.iter().any(|kind| slot.suggest_from.contains(kind)))
+                    .map(|(id, _)| *id)
+                    .filter(|id| slot.owner_id.as_deref() != Some(*id));
+                unresolved.push(UnresolvedReference {
+                    slot: index,
+                    message: format!("{} references unknown {}: '{}'", slot.owner, slot.label, slot.value),
+                    suggestion: closest_match(&slot.value, candidates).map(str::to_string),
+                });
+            },
+        }
+    }
+
+    unresolved
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_references_must_point_to_expected_kind() {
+        let spec = r#"
+system:
+  id: shop
+  name: Shop
+  components: [web, api]
+  relationships:
+    - from: web
+      to: apii
+      type: calls
+entities:
+  - id: user
+    fields: []
+actions:
+  - id: register
+    input:
+      entity: user
+flows:
+  - id: signup
+    name: Signup
+    steps:
+      - id: create
+        action: user
+      - id: notify
+        action: registr
+generation_rules:
+  - id: forms
+    name: Forms
+    triggers: [user, missing]
+    generates: []
+"#;
+        let document: FdmlDocument = serde_yaml::from_str(spec).unwrap();
+        let messages: Vec<(String, Option<String>)> = unresolved_references(&document)
+            .into_iter()
+            .map(|reference| (reference.message, reference.suggestion))
+            .collect();
+
+        assert_eq!(messages, vec![
+            ("System relationship references unknown 'to' component: 'apii'".to_string(), Some("api".to_string())),
+            ("Flow 'signup' step 'create' action must be an action, but 'user' is an entity".to_string(), None),
+            ("Flow 'signup' step 'notify' references unknown action: 'registr'".to_string(), Some("register".to_string())),
+            ("Generation rule 'forms' references unknown trigger: 'missing'".to_string(), None),
+        ]);
+        assert_eq!(reference_slots(&document).len(), reference_values_mut(&mut document.clone()).len());
+    }
+}
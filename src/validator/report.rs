@@ -0,0 +1,124 @@
+//! Machine-readable validation reports for CI systems.
+
+use serde_json::{json, Value};
+use crate::parser::index::DocumentIndex;
+use crate::validator::rules::RuleFindings;
+
+/// A finding with its 1-based line and column, when the element it names is found
+struct Located<'a> {
+    message: &'a str,
+    position: Option<(usize, usize)>,
+}
+
+fn locate<'a>(content: &str, index: &DocumentIndex, messages: &'a [String]) -> Vec<Located<'a>> {
+    let lines: Vec<&str> = content.lines().collect();
+    messages
+        .iter()
+        .map(|message| Located {
+            message,
+            position: index.locate(message).map(|(line, (from, _))| {
+                let column = lines.get(line).and_then(|text| text.get(..from)).map_or(0, |prefix| prefix.encode_utf16().count());
+                (line + 1, column + 1)
+            }),
+        })
+        .collect()
+}
+
+/// SARIF 2.1.0 log with one rule per validation rule and one result per finding
+pub fn sarif_report(file: &str, content: &str, findings: &[RuleFindings], strict: bool) -> Value {
+    let index = DocumentIndex::build(content);
+    let rules: Vec<Value> = findings
+        .iter()
+        .map(|findings| json!({
+            "id": findings.rule,
+            "shortDescription": { "text": findings.description },
+        }))
+        .collect();
+
+    let mut results = Vec::new();
+    for (rule_index, rule) in findings.iter().enumerate() {
+        for finding in locate(content, &index, &rule.messages) {
+            let mut location = json!({ "artifactLocation": { "uri": file } });
+            if let Some((line, column)) = finding.position {
+                location["region"] = json!({ "startLine": line, "startColumn": column });
+            }
+            results.push(json!({
+                "ruleId": rule.rule,
+                "ruleIndex": rule_index,
+                "level": if strict { "error" } else { "warning" },
+                "message": { "text": finding.message },
+                "locations": [{ "physicalLocation": location }],
+            }));
+        }
+    }
+
+    json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "fdml",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": "https://github.com/kolanski/FDML",
+                    "rules": rules,
+                },
+            },
+            "results": results,
+        }],
+    })
+}
+
+/// JUnit XML report with one test case per validation rule, failing when it has findings
+pub fn junit_report(file: &str, content: &str, findings: &[RuleFindings]) -> String {
+    let index = DocumentIndex::build(content);
+    let failures = findings.iter().filter(|findings| !findings.messages.is_empty()).count();
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str(&format!(
+        "<testsuites name=\"fdml validate\" tests=\"{}\" failures=\"{}\">\n",
+        findings.len(),
+        failures
+    ));
+    xml.push_str(&format!(
+        "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"0\" skipped=\"0\">\n",
+        escape(file),
+        findings.len(),
+        failures
+    ));
+
+    for rule in findings {
+        let name = format!("    <testcase name=\"{}\" classname=\"fdml.validate.{}\" file=\"{}\"", escape(&rule.rule), escape(&rule.rule), escape(file));
+        if rule.messages.is_empty() {
+            xml.push_str(&format!("{}/>\n", name));
+            continue;
+        }
+
+        let details: Vec<String> = locate(content, &index, &rule.messages)
+            .iter()
+            .map(|finding| match finding.position {
+                Some((line, column)) => format!("{}:{}:{}: {}", file, line, column, finding.message),
+                None => format!("{}: {}", file, finding.message),
+            })
+            .collect();
+        xml.push_str(&format!("{}>\n", name));
+        xml.push_str(&format!(
+            "      <failure type=\"{}\" message=\"{}\">{}</failure>\n",
+            escape(&rule.rule),
+            escape(&format!("{}: {} finding(s)", rule.description, rule.messages.len())),
+            escape(&details.join("\n"))
+        ));
+        xml.push_str("    </testcase>\n");
+    }
+
+    xml.push_str("  </testsuite>\n</testsuites>\n");
+    xml
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
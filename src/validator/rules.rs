@@ -1,10 +1,38 @@
 use crate::error::{FdmlError, Result};
 use crate::parser::ast::FdmlDocument;
+use crate::validator::conditions::check_conditions;
+use crate::validator::dependencies::DependencyGraph;
+use crate::validator::fields::check_fields;
+use crate::validator::fix;
+use crate::validator::naming;
+use crate::validator::principles;
+use crate::validator::references::unresolved_references;
+use crate::validator::suggest::{did_you_mean, is_known_field_type, suggest_field_type, suggest_relation, RELATIONS};
+use crate::validator::unused::{empty_scenarios, orphaned_elements};
+
+/// Applies a rule's safe fixes in place, describing each change it made
+pub type Fixer = Box<dyn Fn(&mut FdmlDocument) -> Vec<String>>;
 
 pub struct ValidationRule {
     pub name: String,
     pub description: String,
     pub validator: Box<dyn Fn(&FdmlDocument) -> Result<Vec<String>>>,
+    pub fixer: Option<Fixer>,
+}
+
+/// Messages reported by one validation rule
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuleFindings {
+    pub rule: String,
+    pub description: String,
+    pub messages: Vec<String>,
+}
+
+/// A change made by a rule's fixer
+#[derive(Debug, Clone, PartialEq)]
+pub struct AppliedFix {
+    pub rule: String,
+    pub description: String,
 }
 
 pub struct Validator {
@@ -18,42 +46,155 @@ impl Validator {
                 name: "required_ids".to_string(),
                 description: "All entities, features, and actions must have IDs".to_string(),
                 validator: Box::new(validate_required_ids),
+                fixer: Some(Box::new(fix::fix_required_ids)),
+            },
+            ValidationRule {
+                name: "id_format".to_string(),
+                description: "IDs should be snake_case".to_string(),
+                validator: Box::new(validate_id_format),
+                fixer: Some(Box::new(fix::fix_id_format)),
             },
             ValidationRule {
                 name: "unique_ids".to_string(),
                 description: "All IDs must be unique within their scope".to_string(),
                 validator: Box::new(validate_unique_ids),
+                fixer: Some(Box::new(fix::fix_unique_ids)),
+            },
+            ValidationRule {
+                name: "field_name_format".to_string(),
+                description: "Field names should be snake_case".to_string(),
+                validator: Box::new(|document| Ok(naming::check_field_names(document))),
+                fixer: Some(Box::new(naming::fix_field_names)),
+            },
+            ValidationRule {
+                name: "reserved_words".to_string(),
+                description: "IDs and field names must not be reserved words of a target language".to_string(),
+                validator: Box::new(|document| Ok(naming::check_reserved_words(document))),
+                fixer: Some(Box::new(naming::fix_reserved_words)),
+            },
+            ValidationRule {
+                name: "name_length".to_string(),
+                description: format!("IDs and field names must be at most {} characters", naming::MAX_NAME_LENGTH),
+                validator: Box::new(|document| Ok(naming::check_name_lengths(document))),
+                fixer: Some(Box::new(naming::fix_name_lengths)),
+            },
+            ValidationRule {
+                name: "name_collisions".to_string(),
+                description: "IDs and field names must stay distinct after case conversion".to_string(),
+                validator: Box::new(|document| Ok(naming::check_name_collisions(document))),
+                fixer: Some(Box::new(naming::fix_name_collisions)),
             },
             ValidationRule {
                 name: "valid_references".to_string(),
-                description: "All references must point to existing elements".to_string(),
+                description: "All references must point to existing elements of the expected kind".to_string(),
                 validator: Box::new(validate_references),
+                fixer: Some(Box::new(fix::fix_references)),
+            },
+            ValidationRule {
+                name: "dependency_cycles".to_string(),
+                description: "Feature dependencies must not be circular".to_string(),
+                validator: Box::new(validate_dependency_cycles),
+                fixer: None,
             },
             ValidationRule {
                 name: "required_fields".to_string(),
                 description: "Required fields must be present".to_string(),
                 validator: Box::new(validate_required_fields),
+                fixer: None,
+            },
+            ValidationRule {
+                name: "field_types".to_string(),
+                description: "Field types must be built-in types or entity IDs".to_string(),
+                validator: Box::new(validate_field_types),
+                fixer: Some(Box::new(fix::fix_field_types)),
+            },
+            ValidationRule {
+                name: "field_values".to_string(),
+                description: "Field defaults and constraints must match the field type".to_string(),
+                validator: Box::new(validate_field_values),
+                fixer: None,
+            },
+            ValidationRule {
+                name: "constraint_conditions".to_string(),
+                description: "Constraint conditions must parse and refer to fields of their entities".to_string(),
+                validator: Box::new(|document| Ok(check_conditions(document))),
+                fixer: None,
+            },
+            ValidationRule {
+                name: "traceability_relations".to_string(),
+                description: "Traceability relations must be known relation types".to_string(),
+                validator: Box::new(validate_relations),
+                fixer: Some(Box::new(fix::fix_relations)),
+            },
+            ValidationRule {
+                name: "principle_codes".to_string(),
+                description: "Design principles must come from the taxonomy".to_string(),
+                validator: Box::new(|document| Ok(principles::check_codes(document))),
+                fixer: None,
+            },
+            ValidationRule {
+                name: "strategy_alignment".to_string(),
+                description: "Strategies and hints must apply a declared design principle".to_string(),
+                validator: Box::new(|document| Ok(principles::check_alignment(document))),
+                fixer: None,
+            },
+            ValidationRule {
+                name: "principle_conflicts".to_string(),
+                description: "Principles declared in a group must not conflict".to_string(),
+                validator: Box::new(|document| Ok(principles::check_conflicts(document))),
+                fixer: None,
             },
         ];
         
         Self { rules }
     }
     
+    /// The default rules plus the named optional rules, e.g. from `settings.validation.rules`
+    /// in fdml.yaml; names of default or unknown rules are ignored
+    pub fn with_optional_rules(names: &[String]) -> Self {
+        let mut validator = Self::new();
+        validator.rules.extend(optional_rules().into_iter().filter(|rule| names.contains(&rule.name)));
+        validator
+    }
+    
     pub fn validate(&self, document: &FdmlDocument) -> Result<Vec<String>> {
-        let mut all_errors = Vec::new();
-        
-        for rule in &self.rules {
-            match (rule.validator)(document) {
-                Ok(mut errors) => {
-                    all_errors.append(&mut errors);
-                }
-                Err(e) => {
-                    all_errors.push(format!("Validation rule '{}' failed: {}", rule.name, e));
+        Ok(self.validate_by_rule(document)
+            .into_iter()
+            .flat_map(|findings| findings.messages)
+            .collect())
+    }
+    
+    /// Run every rule, keeping each rule's messages apart (rules without findings included)
+    pub fn validate_by_rule(&self, document: &FdmlDocument) -> Vec<RuleFindings> {
+        self.rules
+            .iter()
+            .map(|rule| {
+                let messages = match (rule.validator)(document) {
+                    Ok(errors) => errors,
+                    Err(e) => vec![format!("Validation rule '{}' failed: {}", rule.name, e)],
+                };
+                RuleFindings {
+                    rule: rule.name.clone(),
+                    description: rule.description.clone(),
+                    messages,
                 }
-            }
+            })
+            .collect()
+    }
+    
+    /// Apply the fixers of all rules in order, so later rules see earlier fixes
+    pub fn fix(&self, document: &mut FdmlDocument) -> Vec<AppliedFix> {
+        let mut fixes = Vec::new();
+        for rule in &self.rules {
+            let Some(fixer) = &rule.fixer else {
+                continue;
+            };
+            fixes.extend(fixer(document).into_iter().map(|description| AppliedFix {
+                rule: rule.name.clone(),
+                description,
+            }));
         }
-        
-        Ok(all_errors)
+        fixes
     }
     
     pub fn validate_strict(&self, document: &FdmlDocument) -> Result<()> {
@@ -69,6 +210,24 @@ impl Validator {
     }
 }
 
+/// Rules that only run when enabled, since unused elements are common while a spec is being written
+fn optional_rules() -> Vec<ValidationRule> {
+    vec![
+        ValidationRule {
+            name: "unused_elements".to_string(),
+            description: "Entities, actions and flows must be used, and constraints must apply to something".to_string(),
+            validator: Box::new(|document| Ok(orphaned_elements(document))),
+            fixer: None,
+        },
+        ValidationRule {
+            name: "empty_scenarios".to_string(),
+            description: "Features must have scenarios, with given, when and then steps".to_string(),
+            validator: Box::new(|document| Ok(empty_scenarios(document))),
+            fixer: None,
+        },
+    ]
+}
+
 fn validate_required_ids(document: &FdmlDocument) -> Result<Vec<String>> {
     let mut errors = Vec::new();
     
@@ -138,6 +297,36 @@ fn validate_required_ids(document: &FdmlDocument) -> Result<Vec<String>> {
     Ok(errors)
 }
 
+fn validate_id_format(document: &FdmlDocument) -> Result<Vec<String>> {
+    let mut errors = Vec::new();
+    let mut check = |kind: &str, id: &str| {
+        if !id.is_empty() && !fix::is_snake_case(id) {
+            errors.push(format!("{} ID '{}' should be snake_case", kind, id));
+        }
+    };
+    
+    for entity in &document.entities {
+        check("Entity", &entity.id);
+    }
+    for feature in &document.features {
+        check("Feature", &feature.id);
+        for scenario in &feature.scenarios {
+            check("Scenario", &scenario.id);
+        }
+    }
+    for action in &document.actions {
+        check("Action", &action.id);
+    }
+    for flow in &document.flows {
+        check("Flow", &flow.id);
+    }
+    for constraint in &document.constraints {
+        check("Constraint", &constraint.id);
+    }
+    
+    Ok(errors)
+}
+
 fn validate_unique_ids(document: &FdmlDocument) -> Result<Vec<String>> {
     let mut errors = Vec::new();
     let mut all_ids = std::collections::HashSet::new();
@@ -195,60 +384,21 @@ fn validate_unique_ids(document: &FdmlDocument) -> Result<Vec<String>> {
 }
 
 fn validate_references(document: &FdmlDocument) -> Result<Vec<String>> {
-    let mut errors = Vec::new();
-    let mut all_ids = std::collections::HashSet::new();
-    
-    // Collect all valid IDs
-    for entity in &document.entities {
-        all_ids.insert(entity.id.clone());
-    }
-    for feature in &document.features {
-        all_ids.insert(feature.id.clone());
-        for scenario in &feature.scenarios {
-            all_ids.insert(scenario.id.clone());
-        }
-    }
-    for action in &document.actions {
-        all_ids.insert(action.id.clone());
-    }
-    for flow in &document.flows {
-        all_ids.insert(flow.id.clone());
-    }
-    for constraint in &document.constraints {
-        all_ids.insert(constraint.id.clone());
-    }
-    
-    // Check references in features
-    for feature in &document.features {
-        if let Some(dependencies) = &feature.dependencies {
-            for dep in dependencies {
-                if !all_ids.contains(dep) {
-                    errors.push(format!(
-                        "Feature '{}' references unknown dependency: '{}'",
-                        feature.id, dep
-                    ));
-                }
-            }
-        }
-    }
-    
-    // Check traceability references
-    for trace in &document.traceability {
-        if !all_ids.contains(&trace.from) {
-            errors.push(format!(
-                "Traceability references unknown 'from' element: '{}'",
-                trace.from
-            ));
-        }
-        if !all_ids.contains(&trace.to) {
-            errors.push(format!(
-                "Traceability references unknown 'to' element: '{}'",
-                trace.to
-            ));
-        }
-    }
-    
-    Ok(errors)
+    Ok(unresolved_references(document)
+        .into_iter()
+        .map(|reference| did_you_mean(reference.message, reference.suggestion.as_deref()))
+        .collect())
+}
+
+fn validate_dependency_cycles(document: &FdmlDocument) -> Result<Vec<String>> {
+    Ok(DependencyGraph::new(document)
+        .cycles()
+        .into_iter()
+        .map(|cycle| {
+            let path: Vec<String> = cycle.iter().map(|id| format!("'{}'", id)).collect();
+            format!("Circular feature dependency: {}", path.join(" -> "))
+        })
+        .collect())
 }
 
 fn validate_required_fields(document: &FdmlDocument) -> Result<Vec<String>> {
@@ -275,6 +425,56 @@ fn validate_required_fields(document: &FdmlDocument) -> Result<Vec<String>> {
     Ok(errors)
 }
 
+fn validate_field_values(document: &FdmlDocument) -> Result<Vec<String>> {
+    Ok(check_fields(document))
+}
+
+/// Only types close to a known one are reported; anything else is taken to be a custom type
+fn validate_field_types(document: &FdmlDocument) -> Result<Vec<String>> {
+    let mut errors = Vec::new();
+    
+    for entity in &document.entities {
+        for field in &entity.fields {
+            if field.field_type.is_empty() || is_known_field_type(document, &field.field_type) {
+                continue;
+            }
+            if let Some(suggestion) = suggest_field_type(document, &field.field_type) {
+                errors.push(did_you_mean(
+                    format!(
+                        "Field '{}' in entity '{}' has unknown type: '{}'",
+                        field.name, entity.id, field.field_type
+                    ),
+                    Some(suggestion),
+                ));
+            }
+        }
+    }
+    
+    Ok(errors)
+}
+
+/// Like field types, relations are open-ended, so only likely misspellings are reported
+fn validate_relations(document: &FdmlDocument) -> Result<Vec<String>> {
+    let mut errors = Vec::new();
+    
+    for trace in &document.traceability {
+        if RELATIONS.contains(&trace.relation.as_str()) {
+            continue;
+        }
+        if let Some(suggestion) = suggest_relation(&trace.relation) {
+            errors.push(did_you_mean(
+                format!(
+                    "Traceability from '{}' to '{}' has unknown relation: '{}'",
+                    trace.from, trace.to, trace.relation
+                ),
+                Some(suggestion),
+            ));
+        }
+    }
+    
+    Ok(errors)
+}
+
 impl Default for Validator {
     fn default() -> Self {
         Self::new()
@@ -304,6 +504,8 @@ mod tests {
             description: None,
             fields: Vec::new(),
             relationships: None,
+            design_principles: None,
+            implementation_strategies: None,
         });
         
         let validator = Validator::new();
@@ -311,4 +513,34 @@ mod tests {
         assert!(!errors.is_empty());
         assert!(errors.iter().any(|e| e.contains("missing required 'id'")));
     }
-}
\ No newline at end of file
+    
+    #[test]
+    fn test_validator_suggests_close_matches() {
+        let spec = r#"
+entities:
+  - id: user
+    fields:
+      - name: email
+        type: strng
+      - name: address
+        type: postal_address
+features:
+  - id: user_oauth
+    title: OAuth login
+    scenarios: []
+    dependencies: [user_auth]
+traceability:
+  - from: user_oauth
+    to: user
+    relation: implemnts
+"#;
+        let document: FdmlDocument = serde_yaml::from_str(spec).unwrap();
+        let errors = Validator::new().validate(&document).unwrap();
+        
+        assert_eq!(errors, vec![
+            "Feature 'user_oauth' references unknown dependency: 'user_auth'",
+            "Field 'email' in entity 'user' has unknown type: 'strng' (did you mean 'string'?)",
+            "Traceability from 'user_oauth' to 'user' has unknown relation: 'implemnts' (did you mean 'implements'?)",
+        ]);
+    }
+}
@@ -0,0 +1,208 @@
+//! Scenario writing rules from spec 6.3 (`fdml lint --scenarios`): business language,
+//! one behaviour per scenario, and independent, non-repeated scenarios.
+
+use crate::parser::ast::{FdmlDocument, Feature, Scenario};
+use std::collections::HashMap;
+
+/// UI vocabulary flagged when `fdml.yaml` doesn't configure its own
+pub const UI_TERMS: &[&str] = &[
+    "click", "tap", "button", "page", "screen", "link", "dropdown", "drop-down", "checkbox", "modal", "popup", "textbox",
+    "menu", "scroll",
+];
+
+/// Technical vocabulary flagged when `fdml.yaml` doesn't configure its own
+pub const TECHNICAL_TERMS: &[&str] = &[
+    "sql", "database", "query", "endpoint", "http", "https", "api", "json", "xml", "url", "cookie", "cache", "status code",
+];
+
+/// Word lists the scenario checks use
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScenarioLint {
+    pub ui_terms: Vec<String>,
+    pub technical_terms: Vec<String>,
+}
+
+impl Default for ScenarioLint {
+    fn default() -> Self {
+        Self {
+            ui_terms: UI_TERMS.iter().map(|term| term.to_string()).collect(),
+            technical_terms: TECHNICAL_TERMS.iter().map(|term| term.to_string()).collect(),
+        }
+    }
+}
+
+impl ScenarioLint {
+    /// Findings as `(check, message)` pairs, in document order
+    pub fn check(&self, document: &FdmlDocument) -> Vec<(&'static str, String)> {
+        let mut findings = Vec::new();
+        // First scenario with each set of steps, for duplicates
+        let mut seen: HashMap<Vec<String>, (&str, &str)> = HashMap::new();
+
+        for feature in &document.features {
+            for scenario in &feature.scenarios {
+                let name = format!("Scenario '{}' in feature '{}'", scenario.id, feature.id);
+
+                for step in steps(scenario) {
+                    if let Some(term) = find_term(step, &self.ui_terms) {
+                        findings.push(("ui_language", format!("{} mentions UI detail '{}': \"{}\"", name, term, step)));
+                    }
+                    if let Some(term) = find_term(step, &self.technical_terms) {
+                        findings.push(("technical_language", format!("{} mentions technical detail '{}': \"{}\"", name, term, step)));
+                    }
+                }
+
+                if scenario.when.len() > 1 {
+                    findings.push((
+                        "multiple_whens",
+                        format!("{} has {} when steps; a scenario should describe one behaviour", name, scenario.when.len()),
+                    ));
+                }
+                if scenario.then.iter().all(|step| step.trim().is_empty()) {
+                    findings.push(("missing_then", format!("{} has no then steps", name)));
+                }
+
+                if !document.actions.is_empty() {
+                    for step in &scenario.when {
+                        let matched = document.actions.iter().any(|action| mentions_action(step, &action.id));
+                        if !matched {
+                            findings.push(("unmatched_step", format!("{} has a when step that matches no action: \"{}\"", name, step)));
+                        }
+                    }
+                }
+
+                if let Some(duplicate) = duplicate_of(&mut seen, feature, scenario) {
+                    findings.push(("duplicate_scenario", format!("{} repeats the steps of {}", name, duplicate)));
+                }
+            }
+        }
+
+        findings
+    }
+}
+
+fn steps(scenario: &Scenario) -> impl Iterator<Item = &String> {
+    scenario.given.iter().chain(&scenario.when).chain(&scenario.then)
+}
+
+/// Describe the earlier scenario with the same steps, remembering this one if it's the first
+fn duplicate_of<'a>(
+    seen: &mut HashMap<Vec<String>, (&'a str, &'a str)>,
+    feature: &'a Feature,
+    scenario: &'a Scenario,
+) -> Option<String> {
+    let key: Vec<String> = [&scenario.given, &scenario.when, &scenario.then]
+        .into_iter()
+        .map(|section| section.iter().map(|step| words(step).join(" ")).collect::<Vec<_>>().join("\n"))
+        .collect();
+    if key.iter().all(String::is_empty) {
+        return None;
+    }
+    match seen.get(&key) {
+        Some((scenario_id, feature_id)) => Some(format!("scenario '{}' in feature '{}'", scenario_id, feature_id)),
+        None => {
+            seen.insert(key, (&scenario.id, &feature.id));
+            None
+        },
+    }
+}
+
+/// Lowercase words of a step, ignoring punctuation
+fn words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric() && c != '-')
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// First term (one or more words) the step contains, allowing a plural last word
+fn find_term<'t>(step: &str, terms: &'t [String]) -> Option<&'t str> {
+    let step_words = words(step);
+    terms.iter().map(String::as_str).find(|term| {
+        let term_words = words(term);
+        !term_words.is_empty()
+            && step_words.windows(term_words.len()).any(|window| {
+                window.iter().zip(&term_words).enumerate().all(|(index, (word, term_word))| {
+                    let last = index == term_words.len() - 1;
+                    word == term_word || (last && (*word == format!("{}s", term_word) || *word == format!("{}es", term_word)))
+                })
+            })
+    })
+}
+
+/// Whether the step mentions every word of the action ID, e.g. "the user creates an order" for `create_order`
+fn mentions_action(step: &str, action_id: &str) -> bool {
+    let step_words = words(step);
+    action_id
+        .split('_')
+        .filter(|word| !word.is_empty())
+        .all(|action_word| step_words.iter().any(|word| same_stem(word, &action_word.to_lowercase())))
+}
+
+/// Words sharing their first few letters, so "registers" matches "register" and "pays" matches "payment"
+fn same_stem(a: &str, b: &str) -> bool {
+    let length = a.chars().count().min(b.chars().count()).min(5);
+    a == b || (length >= 3 && a.chars().take(length).eq(b.chars().take(length)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scenario_lint() {
+        let spec = r#"
+actions:
+  - id: place_order
+features:
+  - id: checkout
+    title: Checkout
+    scenarios:
+      - id: places_order
+        title: Places order
+        given: ["the cart has items"]
+        when: ["the customer places the order"]
+        then: ["the order is confirmed"]
+      - id: clicks_buy
+        title: Clicks buy
+        given: ["the checkout page is open"]
+        when: ["the customer clicks the Buy button", "the customer places the order"]
+        then: []
+  - id: orders
+    title: Orders
+    scenarios:
+      - id: same_order
+        title: Same order
+        given: ["The cart has items."]
+        when: ["the customer places the order"]
+        then: ["the order is confirmed"]
+      - id: stored
+        title: Stored
+        given: []
+        when: ["the customer sends a POST to the orders endpoint"]
+        then: ["the order row is in the database"]
+"#;
+        let document: FdmlDocument = serde_yaml::from_str(spec).unwrap();
+        let findings = ScenarioLint::default().check(&document);
+
+        assert_eq!(findings, vec![
+            ("ui_language", "Scenario 'clicks_buy' in feature 'checkout' mentions UI detail 'page': \"the checkout page is open\"".to_string()),
+            ("ui_language", "Scenario 'clicks_buy' in feature 'checkout' mentions UI detail 'click': \"the customer clicks the Buy button\"".to_string()),
+            ("multiple_whens", "Scenario 'clicks_buy' in feature 'checkout' has 2 when steps; a scenario should describe one behaviour".to_string()),
+            ("missing_then", "Scenario 'clicks_buy' in feature 'checkout' has no then steps".to_string()),
+            ("unmatched_step", "Scenario 'clicks_buy' in feature 'checkout' has a when step that matches no action: \"the customer clicks the Buy button\"".to_string()),
+            ("duplicate_scenario", "Scenario 'same_order' in feature 'orders' repeats the steps of scenario 'places_order' in feature 'checkout'".to_string()),
+            ("technical_language", "Scenario 'stored' in feature 'orders' mentions technical detail 'endpoint': \"the customer sends a POST to the orders endpoint\"".to_string()),
+            ("technical_language", "Scenario 'stored' in feature 'orders' mentions technical detail 'database': \"the order row is in the database\"".to_string()),
+            ("unmatched_step", "Scenario 'stored' in feature 'orders' has a when step that matches no action: \"the customer sends a POST to the orders endpoint\"".to_string()),
+        ]);
+    }
+
+    #[test]
+    fn test_find_term() {
+        let terms = vec!["button".to_string(), "status code".to_string()];
+        assert_eq!(find_term("Press both Buttons", &terms), Some("button"));
+        assert_eq!(find_term("the response status code is 200", &terms), Some("status code"));
+        assert_eq!(find_term("the order status is coded", &terms), None);
+        assert_eq!(find_term("buttonhole sewing", &terms), None);
+    }
+}
@@ -0,0 +1,118 @@
+//! Close-match lookup for misspelled identifiers, types and relations.
+
+use crate::parser::ast::FdmlDocument;
+
+/// Field types understood by the code generators, also offered by LSP completion
+pub const FIELD_TYPES: &[&str] = &[
+    "string", "text", "int", "integer", "float", "double", "decimal", "bool", "boolean",
+    "date", "datetime", "uuid", "email", "enum", "object", "array",
+];
+
+/// Traceability relation types named by the FDML specification and examples
+pub const RELATIONS: &[&str] = &["implements", "verifies", "tests", "depends_on", "blocks", "creates", "operates_on"];
+
+/// Levenshtein distance between two strings, counted in characters
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+
+    for (i, ca) in a.chars().enumerate() {
+        current[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    previous[b.len()]
+}
+
+/// The single candidate within a small edit distance of `target`, if there is exactly one best match
+pub fn closest_match<'a, I>(target: &str, candidates: I) -> Option<&'a str>
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    let limit = if target.chars().count() < 5 { 1 } else { 2 };
+    let mut best: Option<(usize, &str)> = None;
+    let mut tied = false;
+
+    for candidate in candidates {
+        if candidate.is_empty() || candidate == target {
+            continue;
+        }
+        let distance = edit_distance(target, candidate);
+        if distance > limit {
+            continue;
+        }
+        match best {
+            Some((closest, found)) if distance == closest => tied |= found != candidate,
+            Some((closest, _)) if distance > closest => {}
+            _ => {
+                best = Some((distance, candidate));
+                tied = false;
+            }
+        }
+    }
+
+    if tied {
+        return None;
+    }
+    best.map(|(_, candidate)| candidate)
+}
+
+/// Append a "did you mean" hint to a validation message
+pub fn did_you_mean(message: String, suggestion: Option<&str>) -> String {
+    match suggestion {
+        Some(suggestion) => format!("{} (did you mean '{}'?)", message, suggestion),
+        None => message,
+    }
+}
+
+/// Split a validation message into its text and the suggestion appended by [`did_you_mean`]
+pub fn split_suggestion(message: &str) -> (&str, Option<&str>) {
+    message
+        .strip_suffix("'?)")
+        .and_then(|rest| rest.rsplit_once(" (did you mean '"))
+        .map_or((message, None), |(text, suggestion)| (text, Some(suggestion)))
+}
+
+/// Whether a field type is built in or names an entity (by ID or name)
+pub fn is_known_field_type(document: &FdmlDocument, field_type: &str) -> bool {
+    FIELD_TYPES.contains(&field_type)
+        || document.entities.iter().any(|entity| entity.id == field_type || entity.name.as_deref() == Some(field_type))
+}
+
+/// Closest built-in type or entity ID for an unknown field type
+pub fn suggest_field_type<'a>(document: &'a FdmlDocument, field_type: &str) -> Option<&'a str> {
+    let entities = document.entities.iter().map(|entity| entity.id.as_str());
+    closest_match(field_type, FIELD_TYPES.iter().copied().chain(entities))
+}
+
+/// Closest known relation for an unknown traceability relation
+pub fn suggest_relation(relation: &str) -> Option<&'static str> {
+    closest_match(relation, RELATIONS.iter().copied())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_closest_match() {
+        assert_eq!(edit_distance("checkout", "chekout"), 1);
+        assert_eq!(closest_match("chekout", ["checkout", "cart", "user_login"]), Some("checkout"));
+        assert_eq!(closest_match("payment", ["checkout", "cart"]), None);
+        // Two equally close candidates are ambiguous
+        assert_eq!(closest_match("user_b", ["user_a", "user_c"]), None);
+    }
+
+    #[test]
+    fn test_split_suggestion() {
+        let message = did_you_mean("Unknown dependency: 'user_auth'".to_string(), Some("user_oauth"));
+        assert_eq!(message, "Unknown dependency: 'user_auth' (did you mean 'user_oauth'?)");
+        assert_eq!(split_suggestion(&message), ("Unknown dependency: 'user_auth'", Some("user_oauth")));
+        assert_eq!(split_suggestion("Duplicate ID found: 'cart'"), ("Duplicate ID found: 'cart'", None));
+    }
+}
@@ -0,0 +1,150 @@
+//! Elements nothing refers to, and features and scenarios without content (`fdml lint --unused`).
+
+use crate::parser::ast::FdmlDocument;
+use crate::validator::references::reference_slots;
+use std::collections::HashSet;
+
+/// Entities, actions and flows no other element refers to, and constraints that apply to nothing
+pub fn orphaned_elements(document: &FdmlDocument) -> Vec<String> {
+    let referenced = referenced_ids(document);
+    let orphaned = |id: &str| !id.is_empty() && !referenced.contains(id);
+    let mut findings = Vec::new();
+
+    for entity in document.entities.iter().filter(|entity| orphaned(&entity.id)) {
+        findings.push(format!("Entity '{}' is not used by any action, feature or other element", entity.id));
+    }
+    for action in document.actions.iter().filter(|action| orphaned(&action.id)) {
+        findings.push(format!("Action '{}' is not used by any flow, feature or other element", action.id));
+    }
+    for flow in document.flows.iter().filter(|flow| orphaned(&flow.id)) {
+        findings.push(format!("Flow '{}' is not referenced by any feature or other element", flow.id));
+    }
+    for constraint in &document.constraints {
+        let applies = constraint.entities.iter().chain(&constraint.actions).any(|ids| !ids.is_empty());
+        if !applies {
+            findings.push(format!("Constraint '{}' does not apply to any entity or action", constraint.id));
+        }
+    }
+
+    findings
+}
+
+/// Features without scenarios, and scenarios with an empty `given`, `when` or `then`
+pub fn empty_scenarios(document: &FdmlDocument) -> Vec<String> {
+    let mut findings = Vec::new();
+
+    for feature in &document.features {
+        if feature.scenarios.is_empty() {
+            findings.push(format!("Feature '{}' has no scenarios", feature.id));
+        }
+        for scenario in &feature.scenarios {
+            let empty: Vec<&str> = [("given", &scenario.given), ("when", &scenario.when), ("then", &scenario.then)]
+                .into_iter()
+                .filter(|(_, steps)| steps.iter().all(|step| step.trim().is_empty()))
+                .map(|(section, _)| section)
+                .collect();
+            if !empty.is_empty() {
+                findings.push(format!(
+                    "Scenario '{}' in feature '{}' has no {} steps",
+                    scenario.id,
+                    feature.id,
+                    empty.join("/")
+                ));
+            }
+        }
+    }
+
+    findings
+}
+
+/// IDs referenced by some other element, including entities used as field types
+fn referenced_ids(document: &FdmlDocument) -> HashSet<String> {
+    let mut referenced: HashSet<String> = reference_slots(document)
+        .into_iter()
+        .filter(|slot| slot.refers_to_elements() && slot.owner_id.as_ref() != Some(&slot.value))
+        .map(|slot| slot.value)
+        .collect();
+
+    for entity in &document.entities {
+        for field in &entity.fields {
+            if field.field_type != entity.id {
+                referenced.insert(field.field_type.clone());
+            }
+        }
+    }
+
+    referenced
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SPEC: &str = r#"
+entities:
+  - id: user
+    fields: []
+  - id: category
+    fields:
+      - name: parent
+        type: category
+    relationships:
+      - type: belongs_to
+        entity: category
+  - id: address
+    fields: []
+  - id: profile
+    fields:
+      - name: home
+        type: address
+actions:
+  - id: register
+    input:
+      entity: user
+  - id: export
+features:
+  - id: signup
+    title: Signup
+    scenarios:
+      - id: registers
+        title: Registers
+        given: []
+        when: ["the visitor registers"]
+        then: [" "]
+  - id: reports
+    title: Reports
+    scenarios: []
+flows:
+  - id: onboarding
+    name: Onboarding
+    steps:
+      - id: register
+        action: register
+constraints:
+  - id: unique_email
+    name: Unique email
+    type: business_rule
+    rule: emails are unique
+"#;
+
+    #[test]
+    fn test_orphaned_elements() {
+        let document: FdmlDocument = serde_yaml::from_str(SPEC).unwrap();
+        assert_eq!(orphaned_elements(&document), vec![
+            "Entity 'category' is not used by any action, feature or other element",
+            "Entity 'profile' is not used by any action, feature or other element",
+            "Action 'export' is not used by any flow, feature or other element",
+            "Flow 'onboarding' is not referenced by any feature or other element",
+            "Constraint 'unique_email' does not apply to any entity or action",
+        ]);
+    }
+
+    #[test]
+    fn test_empty_scenarios() {
+        let document: FdmlDocument = serde_yaml::from_str(SPEC).unwrap();
+        assert_eq!(empty_scenarios(&document), vec![
+            "Scenario 'registers' in feature 'signup' has no given/then steps",
+            "Feature 'reports' has no scenarios",
+        ]);
+    }
+}
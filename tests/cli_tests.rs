@@ -630,6 +630,41 @@ constraints: []
     assert!(content.contains("Test Entity"));
 }
 
+#[test]
+fn test_add_entity_preserves_formatting() {
+    let temp_dir = TempDir::new().unwrap();
+    let test_file = temp_dir.path().join("test.fdml");
+    let original = r#"# Test specification
+metadata:
+  version: "1.3"   # spec version
+  author: "Test"
+
+entities:
+  # Existing entity
+  - id: user
+    name: "User"
+    fields: []
+"#;
+    fs::write(&test_file, original).unwrap();
+    
+    let mut cmd = Command::cargo_bin("fdml").unwrap();
+    cmd.arg("add")
+        .arg("entity")
+        .arg("order")
+        .arg("--name")
+        .arg("Order")
+        .arg("--target")
+        .arg(&test_file)
+        .assert()
+        .success();
+    
+    // The original text is kept as is and the new entity is appended to the list
+    let content = fs::read_to_string(&test_file).unwrap();
+    assert!(content.starts_with(original), "unexpected rewrite:\n{}", content);
+    assert!(content.contains("  - id: order\n    name: Order\n"));
+    assert!(!content.contains("null"));
+}
+
 #[test]
 fn test_add_action_command() {
     let temp_dir = TempDir::new().unwrap();
@@ -834,7 +869,7 @@ fn test_list_actions_command() {
         .arg("examples/e-commerce/ecommerce.fdml")
         .assert()
         .success()
-        .stdout(predicate::str::contains("Actions (6):"))
+        .stdout(predicate::str::contains("Actions (11):"))
         .stdout(predicate::str::contains("create_user"))
         .stdout(predicate::str::contains("list_products"));
 }
@@ -889,4 +924,974 @@ constraints: []
         .assert()
         .failure()
         .stderr(predicate::str::contains("Entity 'nonexistent_entity' not found"));
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_migrate_history_principles() {
+    let temp_dir = TempDir::new().unwrap();
+    let migration_dir = temp_dir.path().join("migrations");
+    fs::create_dir_all(&migration_dir).unwrap();
+    
+    fs::write(migration_dir.join("001_secure_payments.yaml"), r#"
+id: "001_secure_payments"
+title: "Harden payment processing"
+design_impact:
+  principles_added:
+    - element_id: process_payment
+      principle: Lp
+      rationale: "Payment workers only need gateway access"
+up: []
+down: []
+"#).unwrap();
+    
+    let mut cmd = Command::cargo_bin("fdml").unwrap();
+    cmd.arg("migrate")
+        .arg("status")
+        .arg("--path")
+        .arg(&migration_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("+ principle Lp on process_payment"));
+    
+    let mut cmd = Command::cargo_bin("fdml").unwrap();
+    cmd.arg("migrate")
+        .arg("history")
+        .arg("--principles")
+        .arg("--path")
+        .arg(&migration_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("process_payment"))
+        .stdout(predicate::str::contains("+ Lp [001_secure_payments] (pending)"))
+        .stdout(predicate::str::contains("Payment workers only need gateway access"));
+}
+
+#[test]
+fn test_migrate_status_detects_modified_migration() {
+    let temp_dir = TempDir::new().unwrap();
+    let migration_dir = temp_dir.path().join("migrations");
+    let target_file = temp_dir.path().join("spec.fdml");
+    fs::create_dir_all(&migration_dir).unwrap();
+    fs::write(&target_file, "entities: []\n").unwrap();
+    
+    let migration_file = migration_dir.join("001_add_order.yaml");
+    fs::write(&migration_file, r#"
+id: "001_add_order"
+up:
+  - type: "add_entity"
+    id: "order"
+    name: "Order"
+down:
+  - type: "remove_entity"
+    id: "order"
+"#).unwrap();
+    
+    let mut cmd = Command::cargo_bin("fdml").unwrap();
+    cmd.arg("migrate")
+        .arg("apply")
+        .arg("--path")
+        .arg(&migration_dir)
+        .arg("--target")
+        .arg(&target_file)
+        .assert()
+        .success();
+    
+    // Edit the migration after it was applied
+    let edited = fs::read_to_string(&migration_file).unwrap().replace("Order", "PurchaseOrder");
+    fs::write(&migration_file, edited).unwrap();
+    
+    let mut cmd = Command::cargo_bin("fdml").unwrap();
+    cmd.arg("migrate")
+        .arg("status")
+        .arg("--path")
+        .arg(&migration_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("'001_add_order' was modified after it was applied"));
+    
+    let mut cmd = Command::cargo_bin("fdml").unwrap();
+    cmd.arg("migrate")
+        .arg("apply")
+        .arg("--path")
+        .arg(&migration_dir)
+        .arg("--target")
+        .arg(&target_file)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Migration history validation failed"));
+}
+
+#[test]
+fn test_migrate_apply_and_rollback_to_target() {
+    let temp_dir = TempDir::new().unwrap();
+    let migration_dir = temp_dir.path().join("migrations");
+    let target_file = temp_dir.path().join("spec.fdml");
+    fs::create_dir_all(&migration_dir).unwrap();
+    fs::write(&target_file, "entities: []\n").unwrap();
+    
+    for (id, entity, deps) in [("001_order", "order", "[]"), ("002_invoice", "invoice", "[\"001_order\"]"), ("003_coupon", "coupon", "[]")] {
+        fs::write(migration_dir.join(format!("{}.yaml", id)), format!(r#"
+id: "{id}"
+dependencies: {deps}
+up:
+  - type: "add_entity"
+    id: "{entity}"
+    name: "{entity}"
+down:
+  - type: "remove_entity"
+    id: "{entity}"
+"#)).unwrap();
+    }
+    
+    let mut cmd = Command::cargo_bin("fdml").unwrap();
+    cmd.arg("migrate")
+        .arg("apply")
+        .arg("--path")
+        .arg(&migration_dir)
+        .arg("--target")
+        .arg(&target_file)
+        .arg("--to")
+        .arg("002_invoice")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Migration plan to reach '002_invoice'"))
+        .stdout(predicate::str::contains("Applied 2 migrations"));
+    
+    let content = fs::read_to_string(&target_file).unwrap();
+    assert!(content.contains("invoice"));
+    assert!(!content.contains("coupon"));
+    
+    let mut cmd = Command::cargo_bin("fdml").unwrap();
+    cmd.arg("migrate")
+        .arg("rollback")
+        .arg("--path")
+        .arg(&migration_dir)
+        .arg("--target")
+        .arg(&target_file)
+        .arg("--to")
+        .arg("001_order")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Migration plan to return to '001_order'"))
+        .stdout(predicate::str::contains("Rolled back 1 migrations"));
+    
+    let content = fs::read_to_string(&target_file).unwrap();
+    assert!(!content.contains("invoice"));
+    assert!(content.contains("order"));
+}
+
+#[test]
+fn test_migrate_new_creates_template() {
+    let temp_dir = TempDir::new().unwrap();
+    let migration_dir = temp_dir.path().join("migrations");
+    
+    let mut cmd = Command::cargo_bin("fdml").unwrap();
+    cmd.arg("migrate")
+        .arg("new")
+        .arg("Add order tracking")
+        .arg("--path")
+        .arg(&migration_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("_add_order_tracking.yaml"));
+    
+    let entries: Vec<_> = fs::read_dir(&migration_dir).unwrap().collect();
+    assert_eq!(entries.len(), 1);
+    
+    let mut cmd = Command::cargo_bin("fdml").unwrap();
+    cmd.arg("migrate")
+        .arg("status")
+        .arg("--path")
+        .arg(&migration_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("add_order_tracking"));
+}
+
+#[test]
+fn test_migrate_verify() {
+    let temp_dir = TempDir::new().unwrap();
+    let migration_dir = temp_dir.path().join("migrations");
+    let target_file = temp_dir.path().join("spec.fdml");
+    fs::create_dir_all(&migration_dir).unwrap();
+    fs::write(&target_file, "entities: []\n").unwrap();
+    
+    fs::write(migration_dir.join("001_add_order.yaml"), r#"
+id: "001_add_order"
+up:
+  - type: "add_entity"
+    id: "order"
+    name: "Order"
+down:
+  - type: "remove_entity"
+    id: "order"
+"#).unwrap();
+    
+    let mut cmd = Command::cargo_bin("fdml").unwrap();
+    cmd.arg("migrate")
+        .arg("apply")
+        .arg("--path")
+        .arg(&migration_dir)
+        .arg("--target")
+        .arg(&target_file)
+        .assert()
+        .success();
+    
+    let mut cmd = Command::cargo_bin("fdml").unwrap();
+    cmd.arg("migrate")
+        .arg("verify")
+        .arg("--path")
+        .arg(&migration_dir)
+        .arg("--target")
+        .arg(&target_file)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("matches the result of 1 migrations"));
+    
+    let content = fs::read_to_string(&target_file).unwrap();
+    fs::write(&target_file, content.replace("name: Order", "name: Purchase Order")).unwrap();
+    
+    let mut cmd = Command::cargo_bin("fdml").unwrap();
+    cmd.arg("migrate")
+        .arg("verify")
+        .arg("--path")
+        .arg(&migration_dir)
+        .arg("--target")
+        .arg(&target_file)
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("entity 'order' was edited without a migration"));
+}
+
+#[test]
+fn test_migrate_squash() {
+    let temp_dir = TempDir::new().unwrap();
+    let migration_dir = temp_dir.path().join("migrations");
+    fs::create_dir_all(&migration_dir).unwrap();
+    
+    fs::write(migration_dir.join("001_add_draft.yaml"), r#"
+id: "001_add_draft"
+up:
+  - type: "add_entity"
+    id: "draft"
+    name: "Draft"
+down:
+  - type: "remove_entity"
+    id: "draft"
+"#).unwrap();
+    fs::write(migration_dir.join("002_drop_draft.yaml"), r#"
+id: "002_drop_draft"
+dependencies: ["001_add_draft"]
+up:
+  - type: "remove_entity"
+    id: "draft"
+down:
+  - type: "add_entity"
+    id: "draft"
+    name: "Draft"
+"#).unwrap();
+    
+    let mut cmd = Command::cargo_bin("fdml").unwrap();
+    cmd.arg("migrate")
+        .arg("squash")
+        .arg("--path")
+        .arg(&migration_dir)
+        .arg("--from")
+        .arg("001_add_draft")
+        .arg("--to")
+        .arg("002_drop_draft")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("up: 0 operations (from 2)"))
+        .stdout(predicate::str::contains("Squashed 2 migrations into 002_drop_draft_squashed"));
+    
+    assert!(migration_dir.join("002_drop_draft_squashed.yaml").exists());
+    assert!(!migration_dir.join("001_add_draft.yaml").exists());
+}
+
+#[test]
+fn test_migrate_backups() {
+    let temp_dir = TempDir::new().unwrap();
+    let migration_dir = temp_dir.path().join("migrations");
+    let target_file = temp_dir.path().join("spec.fdml");
+    fs::create_dir_all(&migration_dir).unwrap();
+    fs::write(&target_file, "entities: []\n").unwrap();
+    
+    fs::write(migration_dir.join("001_add_order.yaml"), r#"
+id: "001_add_order"
+up:
+  - type: "add_entity"
+    id: "order"
+    name: "Order"
+down:
+  - type: "remove_entity"
+    id: "order"
+"#).unwrap();
+    
+    let mut cmd = Command::cargo_bin("fdml").unwrap();
+    cmd.arg("migrate")
+        .arg("apply")
+        .arg("--path")
+        .arg(&migration_dir)
+        .arg("--target")
+        .arg(&target_file)
+        .assert()
+        .success();
+    
+    let mut cmd = Command::cargo_bin("fdml").unwrap();
+    let output = cmd.arg("migrate")
+        .arg("backups")
+        .arg("list")
+        .arg("--path")
+        .arg(&migration_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("apply 001_add_order"))
+        .get_output()
+        .stdout
+        .clone();
+    let output = String::from_utf8(output).unwrap();
+    let name = output
+        .split_whitespace()
+        .find(|word| word.starts_with("backup_"))
+        .unwrap();
+    
+    let mut cmd = Command::cargo_bin("fdml").unwrap();
+    cmd.arg("migrate")
+        .arg("backups")
+        .arg("restore")
+        .arg(name)
+        .arg("--path")
+        .arg(&migration_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Restored backup"));
+    
+    assert_eq!(fs::read_to_string(&target_file).unwrap(), "entities: []\n");
+    
+    let mut cmd = Command::cargo_bin("fdml").unwrap();
+    cmd.arg("migrate")
+        .arg("backups")
+        .arg("prune")
+        .arg("--keep")
+        .arg("0")
+        .arg("--path")
+        .arg(&migration_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Pruned 2 backups"));
+}
+
+#[test]
+fn test_migrate_status_with_spec_syntax() {
+    let temp_dir = TempDir::new().unwrap();
+    let migration_dir = temp_dir.path().join("migrations");
+    fs::create_dir_all(&migration_dir).unwrap();
+    
+    fs::write(migration_dir.join("2025_07_24_001_add_crud_user.yaml"), r#"
+id: 2025_07_24_001_add_crud_user
+author: ara
+date: 2025-07-24T12:00:00Z
+description: Added CRUD features for User entity
+up:
+  - add_feature:
+      id: user.create
+      title: Create user
+      entity: user
+      operation: create
+      roles: [admin]
+down:
+  - remove_feature:
+      id: user.create
+"#).unwrap();
+    
+    let mut cmd = Command::cargo_bin("fdml").unwrap();
+    cmd.arg("migrate")
+        .arg("status")
+        .arg("--path")
+        .arg(&migration_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("2025_07_24_001_add_crud_user (by ara, 2025-07-24T12:00:00Z)"));
+}
+
+#[test]
+fn test_fmt_check_and_rewrite() {
+    let temp_dir = TempDir::new().unwrap();
+    let test_file = temp_dir.path().join("spec.fdml");
+    fs::write(&test_file, r#"entities:
+  # Users of the system
+  - name: "User"
+    id: user
+    description: null
+    fields: []
+metadata:
+  version: "1.3"
+"#).unwrap();
+    
+    let mut cmd = Command::cargo_bin("fdml").unwrap();
+    cmd.arg("fmt")
+        .arg("--check")
+        .arg(temp_dir.path())
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("spec.fdml"));
+    
+    let mut cmd = Command::cargo_bin("fdml").unwrap();
+    cmd.arg("fmt")
+        .arg(&test_file)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Formatted 1 of 1 file(s)"));
+    
+    let content = fs::read_to_string(&test_file).unwrap();
+    assert_eq!(content, "metadata:\n  version: '1.3'\n\nentities:\n  # Users of the system\n  - id: user\n    name: User\n    fields: []\n");
+    
+    let mut cmd = Command::cargo_bin("fdml").unwrap();
+    cmd.arg("fmt")
+        .arg("--check")
+        .arg(temp_dir.path())
+        .assert()
+        .success();
+}
+
+#[test]
+fn test_watch_once_reports_problems() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(temp_dir.path().join("app.fdml"), r#"features:
+  - id: signup
+    title: Signup
+    scenarios: []
+    dependencies: [missing]
+"#).unwrap();
+    
+    let mut cmd = Command::cargo_bin("fdml").unwrap();
+    cmd.arg("watch")
+        .arg(temp_dir.path())
+        .arg("--once")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("Watching 1 file(s)"))
+        .stdout(predicate::str::contains("app.fdml: Feature 'signup' references unknown dependency: 'missing'"))
+        .stdout(predicate::str::contains("1 problem(s) in 1 file(s)"));
+}
+
+const INVALID_REFERENCES_SPEC: &str = r#"entities:
+  - id: user
+    fields: []
+features:
+  - id: signup
+    title: Signup
+    scenarios: []
+    dependencies:
+      - missing
+"#;
+
+#[test]
+fn test_validate_sarif_output() {
+    let temp_dir = TempDir::new().unwrap();
+    let test_file = temp_dir.path().join("spec.fdml");
+    fs::write(&test_file, INVALID_REFERENCES_SPEC).unwrap();
+    
+    let mut cmd = Command::cargo_bin("fdml").unwrap();
+    let output = cmd.arg("validate")
+        .arg(&test_file)
+        .arg("--output")
+        .arg("sarif")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    
+    let sarif: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(sarif["version"], "2.1.0");
+    let run = &sarif["runs"][0];
+    assert_eq!(run["tool"]["driver"]["rules"].as_array().unwrap().len(), 17);
+    
+    let result = &run["results"][0];
+    assert_eq!(result["ruleId"], "valid_references");
+    assert_eq!(result["level"], "warning");
+    let region = &result["locations"][0]["physicalLocation"]["region"];
+    assert_eq!(region["startLine"], 9);
+    assert_eq!(region["startColumn"], 9);
+}
+
+#[test]
+fn test_validate_junit_output() {
+    let temp_dir = TempDir::new().unwrap();
+    let test_file = temp_dir.path().join("spec.fdml");
+    fs::write(&test_file, INVALID_REFERENCES_SPEC).unwrap();
+    
+    let mut cmd = Command::cargo_bin("fdml").unwrap();
+    cmd.arg("validate")
+        .arg(&test_file)
+        .arg("--output")
+        .arg("junit")
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("<?xml"))
+        .stdout(predicate::str::contains("tests=\"17\" failures=\"1\""))
+        .stdout(predicate::str::contains("<testcase name=\"unique_ids\" classname=\"fdml.validate.unique_ids\""))
+        .stdout(predicate::str::contains("spec.fdml:9:9: Feature &apos;signup&apos; references unknown dependency: &apos;missing&apos;</failure>"));
+}
+
+const FIXABLE_SPEC: &str = r#"# Checkout spec
+entities:
+  - id: CartItem   # line in a cart
+    fields: []
+features:
+  - id: checkout
+    title: Checkout
+    dependencies: [CartItem]
+    scenarios:
+      - title: Pay with card
+        given: [a cart]
+        when: [user pays]
+        then: [order is placed]
+  - id: refunds
+    title: Refunds
+    dependencies: [chekout]
+    scenarios: []
+"#;
+
+#[test]
+fn test_validate_fix_dry_run_shows_diff() {
+    let temp_dir = TempDir::new().unwrap();
+    let test_file = temp_dir.path().join("spec.fdml");
+    fs::write(&test_file, FIXABLE_SPEC).unwrap();
+    
+    let mut cmd = Command::cargo_bin("fdml").unwrap();
+    cmd.arg("validate")
+        .arg(&test_file)
+        .arg("--fix")
+        .arg("--dry-run")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Would apply 3 fix(es)"))
+        .stdout(predicate::str::contains("-  - id: CartItem   # line in a cart"))
+        .stdout(predicate::str::contains("+  - id: cart_item   # line in a cart"))
+        .stdout(predicate::str::contains("+    dependencies: [checkout]"));
+    
+    // Nothing is written in a dry run
+    assert_eq!(fs::read_to_string(&test_file).unwrap(), FIXABLE_SPEC);
+}
+
+#[test]
+fn test_validate_fix_rewrites_file() {
+    let temp_dir = TempDir::new().unwrap();
+    let test_file = temp_dir.path().join("spec.fdml");
+    fs::write(&test_file, FIXABLE_SPEC).unwrap();
+    
+    let mut cmd = Command::cargo_bin("fdml").unwrap();
+    cmd.arg("validate")
+        .arg(&test_file)
+        .arg("--fix")
+        .arg("--strict")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Applied 3 fix(es)"));
+    
+    let content = fs::read_to_string(&test_file).unwrap();
+    assert!(content.starts_with("# Checkout spec\n"));
+    assert!(content.contains("  - id: cart_item   # line in a cart\n"));
+    assert!(content.contains("    dependencies: [cart_item]\n"));
+    assert!(content.contains("        id: pay_with_card\n"));
+    assert!(content.contains("    dependencies: [checkout]\n"));
+}
+
+const DEPENDENT_FEATURES_SPEC: &str = r#"features:
+  - id: checkout
+    title: Checkout
+    scenarios: []
+    dependencies: [cart]
+    effort: 5
+  - id: cart
+    title: Cart
+    scenarios: []
+    effort: 2
+"#;
+
+#[test]
+fn test_plan_outputs_waves() {
+    let temp_dir = TempDir::new().unwrap();
+    let test_file = temp_dir.path().join("spec.fdml");
+    fs::write(&test_file, DEPENDENT_FEATURES_SPEC).unwrap();
+    
+    let mut cmd = Command::cargo_bin("fdml").unwrap();
+    cmd.arg("plan")
+        .arg(&test_file)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("2 feature(s) in 2 wave(s), total effort 7."))
+        .stdout(predicate::str::contains("## Wave 1 (effort 2)\n\n| Feature | Title | Effort | Depends on |\n|---|---|---|---|\n| `cart` | Cart | 2 | - |"));
+    
+    let mut cmd = Command::cargo_bin("fdml").unwrap();
+    let output = cmd.arg("plan")
+        .arg(&test_file)
+        .arg("--format")
+        .arg("json")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let plan: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(plan["waves"][1]["features"][0]["id"], "checkout");
+    assert_eq!(plan["total_effort"], 7.0);
+}
+
+#[test]
+fn test_circular_dependencies_are_reported() {
+    let temp_dir = TempDir::new().unwrap();
+    let test_file = temp_dir.path().join("spec.fdml");
+    fs::write(&test_file, DEPENDENT_FEATURES_SPEC.replace("    effort: 2\n", "    effort: 2\n    dependencies: [checkout]\n")).unwrap();
+    
+    let mut cmd = Command::cargo_bin("fdml").unwrap();
+    cmd.arg("validate")
+        .arg(&test_file)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Circular feature dependency: 'checkout' -> 'cart' -> 'checkout'"));
+    
+    let mut cmd = Command::cargo_bin("fdml").unwrap();
+    cmd.arg("plan")
+        .arg(&test_file)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("circular dependencies: checkout -> cart -> checkout"));
+}
+
+#[test]
+fn test_validate_reports_references_to_wrong_kind() {
+    let temp_dir = TempDir::new().unwrap();
+    let test_file = temp_dir.path().join("spec.fdml");
+    fs::write(&test_file, r#"entities:
+  - id: user
+    fields: []
+flows:
+  - id: signup
+    name: Signup
+    steps:
+      - id: create
+        action: user
+"#).unwrap();
+    
+    let mut cmd = Command::cargo_bin("fdml").unwrap();
+    cmd.arg("validate")
+        .arg(&test_file)
+        .arg("--strict")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("Flow 'signup' step 'create' action must be an action, but 'user' is an entity"));
+}
+
+#[test]
+fn test_validate_checks_field_defaults_and_constraints() {
+    let temp_dir = TempDir::new().unwrap();
+    let test_file = temp_dir.path().join("spec.fdml");
+    fs::write(&test_file, r#"entities:
+  - id: user
+    fields:
+      - name: age
+        type: integer
+        default: "abc"
+      - name: active
+        type: boolean
+        constraints:
+          - type: max_length
+            value: 5
+"#).unwrap();
+    
+    let mut cmd = Command::cargo_bin("fdml").unwrap();
+    cmd.arg("validate")
+        .arg(&test_file)
+        .arg("--strict")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("Field 'age' in entity 'user' has default \"abc\" that is not a valid integer"))
+        .stdout(predicate::str::contains("Constraint 'max_length' on field 'active' in entity 'user' only applies to string or array fields, not boolean"));
+}
+
+#[test]
+fn test_lint_unused_reports_orphaned_elements() {
+    let temp_dir = TempDir::new().unwrap();
+    let test_file = temp_dir.path().join("spec.fdml");
+    fs::write(&test_file, r#"entities:
+  - id: user
+    fields: []
+  - id: audit_log
+    fields: []
+actions:
+  - id: register
+    input:
+      entity: user
+features:
+  - id: signup
+    title: Signup
+    scenarios: []
+    dependencies: [register]
+"#).unwrap();
+    
+    let mut cmd = Command::cargo_bin("fdml").unwrap();
+    cmd.arg("lint")
+        .arg(&test_file)
+        .arg("--unused")
+        .arg("--strict")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("[unused_elements] Entity 'audit_log' is not used by any action, feature or other element"))
+        .stdout(predicate::str::contains("[empty_scenarios] Feature 'signup' has no scenarios"))
+        .stdout(predicate::str::contains("Entity 'user'").not())
+        .stdout(predicate::str::contains("Action 'register'").not());
+}
+
+#[test]
+fn test_optional_validation_rules_from_project_config() {
+    let temp_dir = TempDir::new().unwrap();
+    let test_file = temp_dir.path().join("spec.fdml");
+    fs::write(&test_file, "entities:\n  - id: audit_log\n    fields: []\n").unwrap();
+    
+    let mut cmd = Command::cargo_bin("fdml").unwrap();
+    cmd.arg("validate")
+        .arg(&test_file)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("is valid"));
+    
+    fs::write(temp_dir.path().join("fdml.yaml"), "settings:\n  validation:\n    rules:\n      - unused_elements\n").unwrap();
+    let mut cmd = Command::cargo_bin("fdml").unwrap();
+    cmd.arg("validate")
+        .arg(&test_file)
+        .arg("--strict")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("Entity 'audit_log' is not used by any action, feature or other element"));
+}
+
+#[test]
+fn test_lint_scenarios_uses_configured_word_lists() {
+    let temp_dir = TempDir::new().unwrap();
+    let test_file = temp_dir.path().join("spec.fdml");
+    fs::write(&test_file, r#"features:
+  - id: checkout
+    title: Checkout
+    scenarios:
+      - id: pays
+        title: Pays
+        given: ["the basket page is open"]
+        when: ["the customer swipes the card"]
+        then: []
+"#).unwrap();
+    
+    let mut cmd = Command::cargo_bin("fdml").unwrap();
+    cmd.arg("lint")
+        .arg(&test_file)
+        .arg("--scenarios")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("[ui_language] Scenario 'pays' in feature 'checkout' mentions UI detail 'page'"))
+        .stdout(predicate::str::contains("[missing_then] Scenario 'pays' in feature 'checkout' has no then steps"));
+    
+    fs::write(temp_dir.path().join("fdml.yaml"), "settings:\n  lint:\n    ui_terms: [swipe]\n").unwrap();
+    let mut cmd = Command::cargo_bin("fdml").unwrap();
+    cmd.arg("lint")
+        .arg(&test_file)
+        .arg("--scenarios")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("mentions UI detail 'swipe'"))
+        .stdout(predicate::str::contains("UI detail 'page'").not());
+}
+
+#[test]
+fn test_validate_fix_applies_naming_conventions() {
+    let temp_dir = TempDir::new().unwrap();
+    let test_file = temp_dir.path().join("spec.fdml");
+    fs::write(&test_file, r#"entities:
+  - id: user_auth
+    fields:
+      - name: firstName  # shown on the profile
+        type: string
+      - name: from
+        type: string
+  - id: UserAuth
+    fields: []
+actions:
+  - id: login
+    input:
+      entity: user_auth
+      fields: [firstName, from]
+"#).unwrap();
+    
+    let mut cmd = Command::cargo_bin("fdml").unwrap();
+    cmd.arg("validate")
+        .arg(&test_file)
+        .arg("--strict")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("Field name 'from' in entity 'user_auth' is a reserved word in Python"))
+        .stdout(predicate::str::contains("Entity ID 'UserAuth' collides with 'user_auth' after case conversion (both become 'UserAuth')"));
+    
+    let mut cmd = Command::cargo_bin("fdml").unwrap();
+    cmd.arg("validate")
+        .arg(&test_file)
+        .arg("--fix")
+        .arg("--strict")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("[name_collisions] Renamed entity ID 'UserAuth' to 'user_auth_2'"));
+    
+    let fixed = fs::read_to_string(&test_file).unwrap();
+    assert!(fixed.contains("      - name: first_name  # shown on the profile\n"));
+    assert!(fixed.contains("      fields: [first_name, from_field]\n"));
+}
+
+#[test]
+fn test_design_principles_are_parsed_and_validated() {
+    let temp_dir = TempDir::new().unwrap();
+    let test_file = temp_dir.path().join("spec.fdml");
+    fs::write(&test_file, r#"system:
+  id: payments
+  name: Payments
+  components: []
+  relationships: []
+  design_principles:
+    efficiency: [Sc, Op]
+    reliability: [At, Cr]
+  implementation_strategies:
+    - principle: Op
+      strategy: "Process payments optimistically"
+    - principle: Lp
+      strategy: "Scoped service credentials"
+"#).unwrap();
+    
+    let mut cmd = Command::cargo_bin("fdml").unwrap();
+    cmd.arg("parse")
+        .arg(&test_file)
+        .arg("--output")
+        .arg("yaml")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("design_principles:"))
+        .stdout(predicate::str::contains("strategy: Process payments optimistically"));
+    
+    let mut cmd = Command::cargo_bin("fdml").unwrap();
+    cmd.arg("validate")
+        .arg(&test_file)
+        .arg("--strict")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("System 'payments' implementation strategy applies principle 'Lp' (Least Privilege), which is not among its design_principles"))
+        .stdout(predicate::str::contains("System 'payments' declares conflicting reliability principles 'At' (Atomic Execution) and 'Cr' (Consistency Relaxation)"));
+}
+
+#[test]
+fn test_principles_report() {
+    let temp_dir = TempDir::new().unwrap();
+    let test_file = temp_dir.path().join("spec.fdml");
+    fs::write(&test_file, r#"system:
+  id: shop
+  name: Shop
+  components: []
+  relationships: []
+  design_principles:
+    reliability: [Ft]
+actions:
+  - id: pay
+    side_effects: ["charges the card"]
+    execution_strategies:
+      - principle: Ft
+        strategy: Circuit breaker
+"#).unwrap();
+    
+    let mut cmd = Command::cargo_bin("fdml").unwrap();
+    cmd.arg("principles")
+        .arg("report")
+        .arg(&test_file)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("| Ft (Fault Tolerance) | System 'shop' | Action 'pay' execution strategy |"))
+        .stdout(predicate::str::contains("| Action 'pay' | has 1 side effect(s) |"));
+    
+    let mut cmd = Command::cargo_bin("fdml").unwrap();
+    let output = cmd.arg("principles")
+        .arg("report")
+        .arg(&test_file)
+        .arg("--format")
+        .arg("json")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["groups"][6]["group"], "reliability");
+    assert_eq!(report["groups"][6]["principles"][0]["declared_by"][0], "System 'shop'");
+    assert_eq!(report["uncovered"][0]["element"], "Action 'pay'");
+    
+    let html_file = temp_dir.path().join("report.html");
+    let mut cmd = Command::cargo_bin("fdml").unwrap();
+    cmd.arg("principles")
+        .arg("report")
+        .arg(&test_file)
+        .arg("--format")
+        .arg("html")
+        .arg("--output")
+        .arg(&html_file)
+        .assert()
+        .success();
+    assert!(fs::read_to_string(&html_file).unwrap().contains("<h2>Reliability</h2>"));
+}
+
+#[test]
+fn test_constraint_conditions() {
+    let temp_dir = TempDir::new().unwrap();
+    let test_file = temp_dir.path().join("spec.fdml");
+    let records_file = temp_dir.path().join("records.json");
+    fs::write(&test_file, r#"entities:
+  - id: user
+    fields:
+      - name: email
+        type: email
+      - name: age
+        type: integer
+constraints:
+  - id: unique_email
+    name: Unique email
+    type: uniqueness
+    rule: email is unique and email matches /@/
+    entities: [user]
+  - id: adult
+    name: Adult
+    type: validation
+    rule: user.age >= 18
+    entities: [user]
+"#).unwrap();
+    fs::write(&records_file, r#"{"user": [{"email": "ann@example.com", "age": 30}, {"email": "ann@example.com", "age": 12}]}"#).unwrap();
+    
+    let mut cmd = Command::cargo_bin("fdml").unwrap();
+    cmd.arg("constraints")
+        .arg("eval")
+        .arg(&test_file)
+        .arg(&records_file)
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("unique_email (email is unique and email matches /@/): 2 of 2 'user' record(s) fail"))
+        .stdout(predicate::str::contains("adult (user.age >= 18): 1 of 2 'user' record(s) fail"))
+        .stdout(predicate::str::contains("user[1] {\"age\":12,\"email\":\"ann@example.com\"}"))
+        .stderr(predicate::str::contains("3 record(s) violate constraint conditions"));
+    
+    let mut cmd = Command::cargo_bin("fdml").unwrap();
+    cmd.arg("constraints")
+        .arg("eval")
+        .arg(&test_file)
+        .arg(&records_file)
+        .arg("--constraint")
+        .arg("adult")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("unique_email").not());
+    
+    fs::write(&test_file, fs::read_to_string(&test_file).unwrap().replace("user.age >= 18", "user.agee >= 18")).unwrap();
+    let mut cmd = Command::cargo_bin("fdml").unwrap();
+    cmd.arg("validate")
+        .arg(&test_file)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Constraint 'adult' rule refers to unknown field 'agee' of entity 'user' (did you mean 'age'?)"));
+}
@@ -0,0 +1,265 @@
+use assert_cmd::cargo::cargo_bin;
+use serde_json::{json, Value};
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+const URI: &str = "file:///shop/spec.fdml";
+
+const SPEC: &str = r#"entities:
+  - id: user
+    name: User
+    description: Someone with an account
+    fields:
+      - name: email
+        type: string
+        required: true
+  - id: order
+    fields: []
+
+actions:
+  - id: place_order
+    input:
+      entity: order
+
+features:
+  - id: checkout
+    title: Checkout
+    scenarios: []
+    dependencies:
+      - missing_feature
+      - place_order
+
+traceability:
+  - from: checkout
+    to: user
+    relation: uses
+"#;
+
+/// Run `fdml lsp` with the given messages and return everything it sent back
+fn run_session(messages: &[Value]) -> Vec<Value> {
+    let bodies: Vec<String> = messages.iter().map(Value::to_string).collect();
+    run_raw_session(&bodies)
+}
+
+/// Like [`run_session`], with message bodies that need not be valid JSON
+fn run_raw_session(bodies: &[String]) -> Vec<Value> {
+    let mut child = Command::new(cargo_bin("fdml"))
+        .arg("lsp")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    let mut stdin = child.stdin.take().unwrap();
+    for body in bodies {
+        write!(stdin, "Content-Length: {}\r\n\r\n{}", body.len(), body).unwrap();
+    }
+    drop(stdin);
+
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+
+    let mut replies = Vec::new();
+    let mut rest = String::from_utf8(output.stdout).unwrap();
+    while let Some(header_end) = rest.find("\r\n\r\n") {
+        let length: usize = rest[..header_end].trim_start_matches("Content-Length: ").parse().unwrap();
+        let body_start = header_end + 4;
+        replies.push(serde_json::from_str(&rest[body_start..body_start + length]).unwrap());
+        rest = rest[body_start + length..].to_string();
+    }
+    replies
+}
+
+fn session(requests: &[Value]) -> Vec<Value> {
+    let mut messages = vec![
+        json!({ "jsonrpc": "2.0", "id": 0, "method": "initialize", "params": { "capabilities": {} } }),
+        json!({ "jsonrpc": "2.0", "method": "initialized", "params": {} }),
+        json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/didOpen",
+            "params": { "textDocument": { "uri": URI, "languageId": "fdml", "version": 1, "text": SPEC } },
+        }),
+    ];
+    messages.extend(requests.iter().cloned());
+    messages.push(json!({ "jsonrpc": "2.0", "id": 99, "method": "shutdown" }));
+    messages.push(json!({ "jsonrpc": "2.0", "method": "exit" }));
+    run_session(&messages)
+}
+
+fn request(id: u64, method: &str, line: u64, character: u64) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "method": method,
+        "params": {
+            "textDocument": { "uri": URI },
+            "position": { "line": line, "character": character },
+            "context": { "includeDeclaration": true },
+        },
+    })
+}
+
+fn result(replies: &[Value], id: u64) -> Value {
+    replies.iter().find(|reply| reply["id"] == id).unwrap()["result"].clone()
+}
+
+#[test]
+fn test_lsp_initialize_and_diagnostics() {
+    let replies = session(&[]);
+
+    let capabilities = &result(&replies, 0)["capabilities"];
+    assert_eq!(capabilities["definitionProvider"], true);
+    assert_eq!(capabilities["documentSymbolProvider"], true);
+
+    let diagnostics = replies
+        .iter()
+        .find(|reply| reply["method"] == "textDocument/publishDiagnostics")
+        .unwrap();
+    let diagnostics = diagnostics["params"]["diagnostics"].as_array().unwrap();
+    assert_eq!(diagnostics.len(), 1);
+    assert!(diagnostics[0]["message"].as_str().unwrap().contains("missing_feature"));
+    // Placed on the unknown dependency
+    assert_eq!(diagnostics[0]["range"]["start"], json!({ "line": 21, "character": 8 }));
+
+    assert_eq!(result(&replies, 99), Value::Null);
+}
+
+#[test]
+fn test_lsp_malformed_message_gets_parse_error() {
+    let replies = run_raw_session(&[
+        "{bad".to_string(),
+        json!({ "jsonrpc": "2.0", "id": 0, "method": "initialize", "params": {} }).to_string(),
+        json!({ "jsonrpc": "2.0", "method": "exit" }).to_string(),
+    ]);
+
+    assert_eq!(replies[0]["id"], Value::Null);
+    assert_eq!(replies[0]["error"]["code"], -32700);
+    assert_eq!(result(&replies, 0)["serverInfo"]["name"], "fdml");
+}
+
+#[test]
+fn test_lsp_parse_error_diagnostic() {
+    let replies = run_session(&[
+        json!({ "jsonrpc": "2.0", "id": 0, "method": "initialize", "params": {} }),
+        json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/didOpen",
+            "params": { "textDocument": { "uri": URI, "text": "entities:\n  - id: [broken\n" } },
+        }),
+        json!({ "jsonrpc": "2.0", "method": "exit" }),
+    ]);
+
+    let diagnostics = &replies[1]["params"]["diagnostics"];
+    assert_eq!(diagnostics[0]["severity"], 1);
+    assert!(diagnostics[0]["message"].as_str().unwrap().starts_with("YAML parsing failed"));
+}
+
+#[test]
+fn test_lsp_definition_and_references() {
+    let replies = session(&[
+        // `order` in the action input
+        request(1, "textDocument/definition", 14, 15),
+        // `user` in the traceability link
+        request(2, "textDocument/references", 26, 10),
+        // `place_order` as a feature dependency
+        request(3, "textDocument/definition", 22, 10),
+    ]);
+
+    assert_eq!(result(&replies, 1)["range"]["start"], json!({ "line": 8, "character": 8 }));
+    assert_eq!(result(&replies, 1)["uri"], URI);
+
+    let references = result(&replies, 2);
+    let lines: Vec<&Value> = references.as_array().unwrap().iter().map(|r| &r["range"]["start"]["line"]).collect();
+    assert_eq!(lines, vec![&json!(1), &json!(26)]);
+
+    assert_eq!(result(&replies, 3)["range"]["start"]["line"], 12);
+}
+
+#[test]
+fn test_lsp_hover_shows_description_and_fields() {
+    let replies = session(&[request(1, "textDocument/hover", 1, 9)]);
+
+    let hover = result(&replies, 1)["contents"]["value"].as_str().unwrap().to_string();
+    assert!(hover.starts_with("**entity** `user` — User"));
+    assert!(hover.contains("Someone with an account"));
+    assert!(hover.contains("- `email`: string (required)"));
+}
+
+#[test]
+fn test_lsp_completion() {
+    let replies = session(&[
+        // After `type:` of a field
+        request(1, "textDocument/completion", 6, 14),
+        // After `entity:` in the action input
+        request(2, "textDocument/completion", 14, 14),
+    ]);
+
+    let labels = |id| -> Vec<String> {
+        result(&replies, id).as_array().unwrap().iter().map(|item| item["label"].as_str().unwrap().to_string()).collect()
+    };
+    assert!(labels(1).contains(&"datetime".to_string()));
+    assert!(labels(1).contains(&"uuid".to_string()));
+    assert_eq!(labels(2), vec!["user", "order"]);
+}
+
+#[test]
+fn test_lsp_document_symbols() {
+    let replies = session(&[json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "textDocument/documentSymbol",
+        "params": { "textDocument": { "uri": URI } },
+    })]);
+
+    let sections = result(&replies, 1);
+    let names: Vec<&str> = sections.as_array().unwrap().iter().map(|s| s["name"].as_str().unwrap()).collect();
+    assert_eq!(names, vec!["entities", "actions", "features", "traceability"]);
+
+    let user = &sections[0]["children"][0];
+    assert_eq!(user["name"], "user");
+    assert_eq!(user["range"]["start"]["line"], 1);
+    assert_eq!(user["range"]["end"]["line"], 7);
+    assert_eq!(user["children"][0]["name"], "email");
+}
+
+#[test]
+fn test_lsp_quick_fix_for_misspelled_reference() {
+    let text = "features:\n  - id: checkout\n    title: Checkout\n    scenarios: []\n  - id: refunds\n    title: Refunds\n    scenarios: []\n    dependencies:\n      - chekout\n";
+    let open = |messages: &mut Vec<Value>| {
+        messages.push(json!({ "jsonrpc": "2.0", "id": 0, "method": "initialize", "params": {} }));
+        messages.push(json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/didOpen",
+            "params": { "textDocument": { "uri": URI, "text": text } },
+        }));
+    };
+    let mut messages = Vec::new();
+    open(&mut messages);
+    let replies = run_session(&messages);
+
+    let diagnostic = replies[1]["params"]["diagnostics"][0].clone();
+    assert!(diagnostic["message"].as_str().unwrap().ends_with("(did you mean 'checkout'?)"));
+    assert_eq!(diagnostic["data"]["suggestion"], "checkout");
+    assert_eq!(diagnostic["range"]["start"], json!({ "line": 8, "character": 8 }));
+
+    let mut messages = Vec::new();
+    open(&mut messages);
+    messages.push(json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "textDocument/codeAction",
+        "params": {
+            "textDocument": { "uri": URI },
+            "range": diagnostic["range"],
+            "context": { "diagnostics": [diagnostic] },
+        },
+    }));
+    messages.push(json!({ "jsonrpc": "2.0", "method": "exit" }));
+    let replies = run_session(&messages);
+
+    let action = &result(&replies, 1)[0];
+    assert_eq!(action["kind"], "quickfix");
+    let edit = &action["edit"]["changes"][URI][0];
+    assert_eq!(edit["newText"], "checkout");
+    assert_eq!(edit["range"]["end"], json!({ "line": 8, "character": 15 }));
+}